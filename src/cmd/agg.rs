@@ -5,7 +5,7 @@ use std::sync::Arc;
 use rayon::prelude::*;
 use thread_local::ThreadLocal;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util::{self, ChunksIteratorExt};
 use crate::CliResult;
 
@@ -43,6 +43,13 @@ You can rename the output columns using the 'as' syntax:
 
     $ xan agg 'sum(n) as sum, max(replies_count) as \"Max Replies\"' file.csv
 
+Aggregation results can also be post-processed once, at the end, using
+the --post flag, to derive new values from them. The given named expressions,
+which will replace the aggregation results in the output, see the aggregation
+results as their variables, under their respective names:
+
+    $ xan agg 'sum(x) as sum, count() as count' --post 'sum, sum / count as avg' file.csv
+
 For a quick review of the capabilities of the script language, use
 the --cheatsheet flag.
 
@@ -63,6 +70,9 @@ agg options:
                                - \"ignore\": ignore row altogether
                                - \"log\": print error to stderr
                              [default: panic].
+    --post <expr>            Named expressions evaluated once on the aggregation
+                             results, referenced by their names, and whose results
+                             will be output instead.
     -p, --parallel           Whether to use parallelization to speed up computations.
                              Will automatically select a suitable number of threads to use
                              based on your number of cores.
@@ -77,6 +87,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 ";
 
 #[derive(Deserialize)]
@@ -86,8 +98,10 @@ struct Args {
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_aggs: bool,
     flag_errors: String,
+    flag_post: Option<String>,
     flag_cheatsheet: bool,
     flag_functions: bool,
     flag_parallel: bool,
@@ -116,6 +130,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
     let mut rdr = rconf.reader()?;
@@ -124,6 +139,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let mut program = AggregationProgram::parse(&args.arg_expression, headers)?;
 
+    if let Some(post) = &args.flag_post {
+        program.set_post_processing(post)?;
+    }
+
     wtr.write_record(program.headers())?;
 
     if !args.flag_parallel {
@@ -1,25 +1,48 @@
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
 static USAGE: &str = "
 Drop a CSV file's header.
 
+The command can also drop more than one row, e.g. when the file starts with
+some banner or preamble lines, using the --rows flag:
+
+    $ xan behead --rows 3 file.csv
+
+Conversely, it is also able to add a header to a headerless file using
+the --add flag, in which case no row will be dropped, unless --rows is also
+given:
+
+    $ xan behead --add name,surname file.csv
+
 Usage:
     xan behead [options] [<input>]
     xan guillotine [options] [<input>]
 
+behead options:
+    -r, --rows <n>         Number of rows to drop. Defaults to 1, or 0 when
+                           using --add.
+    -A, --add <names>      Add the given header, written in CSV format, to the
+                           file. Its number of columns must match the one of
+                           the first row of the file.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
+    flag_rows: Option<usize>,
+    flag_add: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
 }
 
@@ -27,12 +50,43 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
-        .no_headers(false);
+        .comment(args.flag_comment)
+        .no_headers(true)
+        .flexible(true);
+
+    let rows_to_drop = args
+        .flag_rows
+        .unwrap_or(if args.flag_add.is_some() { 0 } else { 1 });
 
     let mut rdr = conf.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let mut record = csv::ByteRecord::new();
 
+    for _ in 0..rows_to_drop {
+        if !rdr.read_byte_record(&mut record)? {
+            return Ok(wtr.flush()?);
+        }
+    }
+
+    if let Some(names) = &args.flag_add {
+        let header = util::str_to_csv_byte_record(names);
+
+        if rdr.read_byte_record(&mut record)? {
+            if record.len() != header.len() {
+                Err(format!(
+                    "given header has {} columns but first row has {}!",
+                    header.len(),
+                    record.len()
+                ))?;
+            }
+
+            wtr.write_byte_record(&header)?;
+            wtr.write_byte_record(&record)?;
+        } else {
+            wtr.write_byte_record(&header)?;
+        }
+    }
+
     while rdr.read_byte_record(&mut record)? {
         wtr.write_byte_record(&record)?;
     }
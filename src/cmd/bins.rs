@@ -3,7 +3,7 @@ use std::cmp::Ordering;
 use bstr::ByteSlice;
 use rayon::slice::ParallelSliceMut;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::scales::LinearScale;
 use crate::select::SelectColumns;
 use crate::util;
@@ -44,6 +44,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -52,6 +54,7 @@ struct Args {
     flag_select: SelectColumns,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
     flag_no_extra: bool,
     flag_bins: Option<usize>,
@@ -65,6 +68,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
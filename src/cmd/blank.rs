@@ -1,4 +1,4 @@
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -29,6 +29,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -37,6 +39,7 @@ struct Args {
     flag_select: SelectColumns,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
     flag_redact: Option<String>,
 }
@@ -45,6 +48,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
@@ -1,20 +1,22 @@
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
 static USAGE: &str = "
-Concatenates CSV data by column or by row.
+Concatenates CSV data by column, by row or by interleaving rows.
 
 When concatenating by column, the columns will be written in the same order as
 the inputs given. The number of rows in the result is always equivalent to to
 the minimum number of rows across all given CSV data. (This behavior can be
 reversed with the '--pad' flag.)
 
-When concatenating by row, all CSV data must have the same number of columns.
-If you need to rearrange the columns or fix the lengths of records, use the
-'select' or 'fixlengths' commands. Also, only the headers of the *first* CSV
-data given are used. Headers in subsequent inputs are ignored. (This behavior
+When concatenating by row, all CSV data must have the same headers, and only
+the headers of the *first* CSV data given are written. The command will
+error, reporting the differing columns, if the headers of subsequent inputs
+do not match. Use the --flexible flag to realign the columns of those inputs by
+name instead. If you need to rearrange the columns or fix the lengths of records,
+you can also use the 'select' or 'fixlengths' commands. (This behavior
 can be disabled with --no-headers.)
 
 When concatenating a large number of CSV files exceeding your shell's
@@ -22,6 +24,13 @@ command arguments limit, prefer using the --paths flag to read the list of CSV
 files to concatenate from input lines or from a CSV file containing paths in a
 column given to the --path-column flag.
 
+When concatenating by row, the -s/--select flag can also be used to project
+each input on a subset of its columns, resolved against its own headers,
+before concatenating. This is useful when inputs share some common columns
+but have varying extra ones:
+
+    $ xan cat rows -s name,age *.csv > concatenated.csv
+
 Feeding --paths lines:
 
     $ xan cat rows --paths paths.txt > concatenated.csv
@@ -30,6 +39,16 @@ Feeding --paths CSV file:
 
     $ xan cat rows --paths files.csv --path-column path > concatenated.csv
 
+When interleaving rows, the first row of each CSV data given is emitted, then
+the second row of each, and so on. Files must have the same headers, following
+the same rules as when concatenating by row (the --flexible flag can also be
+used). When files differ in length, shorter files are simply skipped once
+exhausted, and the remaining rows of longer files are emitted in turn until all
+files are exhausted. This can be useful to compare streams row by row or to
+reassemble data that was split using 'xan split --parts --round-robin'.
+
+    $ xan cat interleave a.csv b.csv > interleaved.csv
+
 Feeding stdin (\"-\") to --paths:
 
     $ find . -name '*.csv' | xan cat rows --paths - > concatenated.csv
@@ -41,6 +60,7 @@ Feeding CSV as stdin (\"-\") to --paths (typically using `xan glob`):
 Usage:
     xan cat rows    [options] [<inputs>...]
     xan cat columns [options] [<inputs>...]
+    xan cat interleave [options] [<inputs>...]
     xan cat --help
 
 cat columns options:
@@ -48,13 +68,18 @@ cat columns options:
                                 all records to appear. It will pad each row if
                                 other CSV data isn't long enough.
 
-cat rows options:
+cat rows & cat interleave options:
     --paths <input>             When concatenating rows, give a text file (use \"-\" for stdin)
                                 containing one path of CSV file to concatenate per line.
     --path-column <name>        When given a column name, --paths will be considered as CSV, and paths
                                 to CSV files to concatenate will be extracted from the selected column.
     -S, --source-column <name>  Name of a column to prepend in the output of \"cat rows\"
-                                indicating the path to source file.
+                                or \"cat interleave\" indicating the path to source file.
+    --flexible                  When concatenating rows, realign the columns of inputs
+                                whose headers differ from the first one, using their names.
+                                Missing columns will be filled with empty values.
+    -s, --select <cols>         When concatenating rows, only keep the selected columns
+                                of each input, resolved using its own headers.
 
 Common options:
     -h, --help             Display this message
@@ -64,12 +89,15 @@ Common options:
                            concatenating columns.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     cmd_rows: bool,
     cmd_columns: bool,
+    cmd_interleave: bool,
     arg_inputs: Vec<String>,
     flag_paths: Option<String>,
     flag_path_column: Option<SelectColumns>,
@@ -77,7 +105,90 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_source_column: Option<String>,
+    flag_flexible: bool,
+    flag_select: Option<SelectColumns>,
+}
+
+// NOTE: returns, for each column of the reference headers, the index of the
+// column having the same name in the given headers, or None if the headers
+// are identical.
+fn align_headers(
+    reference: &csv::ByteRecord,
+    headers: &csv::ByteRecord,
+    path: &str,
+    flexible: bool,
+) -> CliResult<Option<Vec<Option<usize>>>> {
+    if reference == headers {
+        return Ok(None);
+    }
+
+    let mut used = vec![false; headers.len()];
+    let mut mapping = Vec::with_capacity(reference.len());
+    let mut missing = Vec::new();
+
+    for name in reference.iter() {
+        let found = headers
+            .iter()
+            .enumerate()
+            .position(|(i, h)| !used[i] && h == name);
+
+        if let Some(i) = found {
+            used[i] = true;
+        } else {
+            missing.push(String::from_utf8_lossy(name).into_owned());
+        }
+
+        mapping.push(found);
+    }
+
+    let unexpected = headers
+        .iter()
+        .zip(used.iter())
+        .filter(|(_, u)| !**u)
+        .map(|(h, _)| String::from_utf8_lossy(h).into_owned())
+        .collect::<Vec<_>>();
+
+    if flexible && unexpected.is_empty() {
+        return Ok(Some(mapping));
+    }
+
+    let mut msg = format!(
+        "headers of \"{}\" do not match those of the first file!",
+        path
+    );
+
+    if !missing.is_empty() {
+        msg.push_str(&format!("\n  missing columns: {}", missing.join(", ")));
+    }
+
+    if !unexpected.is_empty() {
+        msg.push_str(&format!(
+            "\n  unexpected columns: {}",
+            unexpected.join(", ")
+        ));
+    }
+
+    if missing.is_empty() && unexpected.is_empty() {
+        msg.push_str("\n  columns are in a different order");
+    }
+
+    if unexpected.is_empty() {
+        msg.push_str("\nUse --flexible to realign columns by name.");
+    }
+
+    Err(msg)?
+}
+
+fn realign(record: &csv::ByteRecord, mapping: &[Option<usize>]) -> csv::ByteRecord {
+    mapping
+        .iter()
+        .map(|i| match i {
+            Some(i) => &record[*i],
+            None => b"",
+        })
+        .collect()
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -87,6 +198,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("--paths cannot be used with other positional arguments!")?;
     }
 
+    if args.flag_paths.is_some() && args.cmd_columns {
+        Err("--paths only works when concatenating rows or interleaving!")?;
+    }
+
+    if args.flag_select.is_some() && !args.cmd_rows {
+        Err("-s, --select only works when concatenating rows!")?;
+    }
+
     if args.cmd_rows {
         if args.flag_paths.is_some() {
             args.cat_rows_with_input()
@@ -95,6 +214,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     } else if args.cmd_columns {
         args.cat_columns()
+    } else if args.cmd_interleave {
+        args.cat_interleave()
     } else {
         unreachable!();
     }
@@ -102,9 +223,22 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
 impl Args {
     fn configs(&self) -> CliResult<Vec<Config>> {
+        let mut inputs = self.arg_inputs.clone();
+
+        if let Some(paths) = &self.flag_paths {
+            inputs = Config::new(&Some(paths.clone()))
+                .lines(&self.flag_path_column)?
+                .collect::<CliResult<Vec<_>>>()?;
+
+            if inputs.is_empty() {
+                return Ok(Vec::new());
+            }
+        }
+
         util::many_configs(
-            &self.arg_inputs,
+            &inputs,
             self.flag_delimiter,
+            self.flag_comment,
             self.flag_no_headers,
             None,
         )
@@ -112,37 +246,21 @@ impl Args {
     }
 
     fn cat_rows(&self) -> CliResult<()> {
-        let mut row = csv::ByteRecord::new();
         let mut wtr = Config::new(&self.flag_output).writer()?;
-        for (i, conf) in self.configs()?.into_iter().enumerate() {
-            let mut rdr = conf.reader()?;
+        let mut cat = RowConcatenator::default();
 
-            match &self.flag_source_column {
-                None => {
-                    if i == 0 {
-                        conf.write_headers(&mut rdr, &mut wtr)?;
-                    }
-                    while rdr.read_byte_record(&mut row)? {
-                        wtr.write_byte_record(&row)?;
-                    }
-                }
-                Some(source_column) => {
-                    if i == 0 {
-                        let headers = rdr.byte_headers()?;
-                        wtr.write_record([source_column.as_bytes()].into_iter().chain(headers))?;
-                    }
+        for conf in self.configs()?.into_iter() {
+            let source = conf
+                .path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or("<stdin>".to_string());
 
-                    let source = conf
-                        .path
-                        .map(|p| p.to_string_lossy().into_owned())
-                        .unwrap_or("<stdin>".to_string());
+            let mut rdr = conf.reader()?;
 
-                    while rdr.read_byte_record(&mut row)? {
-                        wtr.write_record([source.as_bytes()].into_iter().chain(&row))?;
-                    }
-                }
-            }
+            cat.write(self, &source, &mut rdr, &mut wtr)?;
         }
+
         wtr.flush().map_err(From::from)
     }
 
@@ -150,42 +268,93 @@ impl Args {
         let paths =
             Config::new(&Some(self.flag_paths.clone().unwrap())).lines(&self.flag_path_column)?;
 
-        let mut record = csv::ByteRecord::new();
         let mut wtr = Config::new(&self.flag_output).writer()?;
-
-        let mut headers_written = self.flag_no_headers;
+        let mut cat = RowConcatenator::default();
 
         for result in paths {
             let path = result?;
 
-            let mut reader = Config::new(&Some(path.clone()))
+            let mut rdr = Config::new(&Some(path.clone()))
                 .delimiter(self.flag_delimiter)
+                .comment(self.flag_comment)
                 .no_headers(self.flag_no_headers)
                 .reader()?;
 
-            match &self.flag_source_column {
+            cat.write(self, &path, &mut rdr, &mut wtr)?;
+        }
+
+        Ok(wtr.flush()?)
+    }
+
+    fn cat_interleave(&self) -> CliResult<()> {
+        let mut wtr = Config::new(&self.flag_output).writer()?;
+
+        let mut sources = Vec::new();
+        let mut headers: Option<csv::ByteRecord> = None;
+
+        for conf in self.configs()?.into_iter() {
+            let source = conf
+                .path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned())
+                .unwrap_or("<stdin>".to_string());
+
+            let mut rdr = conf.reader()?;
+            let mut mapping: Option<Vec<Option<usize>>> = None;
+
+            match &headers {
                 None => {
-                    if !headers_written {
-                        let headers = reader.byte_headers()?;
-                        wtr.write_byte_record(headers)?;
-                        headers_written = true;
+                    let first_headers = rdr.byte_headers()?.clone();
+
+                    if !self.flag_no_headers {
+                        match &self.flag_source_column {
+                            None => wtr.write_byte_record(&first_headers)?,
+                            Some(source_column) => wtr.write_record(
+                                [source_column.as_bytes()].into_iter().chain(&first_headers),
+                            )?,
+                        }
                     }
 
-                    while reader.read_byte_record(&mut record)? {
-                        wtr.write_byte_record(&record)?;
-                    }
+                    headers = Some(first_headers);
                 }
-                Some(source_column) => {
-                    if !headers_written {
-                        let headers = reader.byte_headers()?;
-                        wtr.write_record(
-                            [source_column.as_bytes()].into_iter().chain(headers.iter()),
+                Some(reference) => {
+                    if !self.flag_no_headers {
+                        mapping = align_headers(
+                            reference,
+                            rdr.byte_headers()?,
+                            &source,
+                            self.flag_flexible,
                         )?;
-                        headers_written = true;
                     }
+                }
+            }
+
+            sources.push((source, rdr, mapping));
+        }
 
-                    while reader.read_byte_record(&mut record)? {
-                        wtr.write_record([path.as_bytes()].into_iter().chain(&record))?;
+        let mut exhausted = vec![false; sources.len()];
+        let mut record = csv::ByteRecord::new();
+
+        while exhausted.iter().any(|e| !e) {
+            for ((source, rdr, mapping), done) in sources.iter_mut().zip(exhausted.iter_mut()) {
+                if *done {
+                    continue;
+                }
+
+                if !rdr.read_byte_record(&mut record)? {
+                    *done = true;
+                    continue;
+                }
+
+                let record = match mapping {
+                    None => std::borrow::Cow::Borrowed(&record),
+                    Some(m) => std::borrow::Cow::Owned(realign(&record, m)),
+                };
+
+                match &self.flag_source_column {
+                    None => wtr.write_byte_record(&record)?,
+                    Some(_) => {
+                        wtr.write_record([source.as_bytes()].into_iter().chain(record.iter()))?
                     }
                 }
             }
@@ -243,3 +412,74 @@ impl Args {
         wtr.flush().map_err(From::from)
     }
 }
+
+#[derive(Default)]
+struct RowConcatenator {
+    headers: Option<csv::ByteRecord>,
+    record: csv::ByteRecord,
+}
+
+impl RowConcatenator {
+    fn write<R: std::io::Read, W: std::io::Write>(
+        &mut self,
+        args: &Args,
+        source: &str,
+        rdr: &mut csv::Reader<R>,
+        wtr: &mut csv::Writer<W>,
+    ) -> CliResult<()> {
+        let mut mapping: Option<Vec<Option<usize>>> = None;
+
+        let mut headers = rdr.byte_headers()?.clone();
+
+        // NOTE: the selection is resolved against the headers of each file
+        let sel = args
+            .flag_select
+            .as_ref()
+            .map(|cols| cols.selection(&headers, !args.flag_no_headers))
+            .transpose()?;
+
+        if let Some(sel) = &sel {
+            headers = sel.select(&headers).collect();
+        }
+
+        match &self.headers {
+            None => {
+                if !args.flag_no_headers {
+                    match &args.flag_source_column {
+                        None => wtr.write_byte_record(&headers)?,
+                        Some(source_column) => wtr
+                            .write_record([source_column.as_bytes()].into_iter().chain(&headers))?,
+                    }
+                }
+
+                self.headers = Some(headers);
+            }
+            Some(reference) => {
+                if !args.flag_no_headers {
+                    mapping = align_headers(reference, &headers, source, args.flag_flexible)?;
+                }
+            }
+        }
+
+        while rdr.read_byte_record(&mut self.record)? {
+            let record = match &sel {
+                None => std::borrow::Cow::Borrowed(&self.record),
+                Some(sel) => std::borrow::Cow::Owned(sel.select(&self.record).collect()),
+            };
+
+            let record = match &mapping {
+                None => record,
+                Some(m) => std::borrow::Cow::Owned(realign(&record, m)),
+            };
+
+            match &args.flag_source_column {
+                None => wtr.write_byte_record(&record)?,
+                Some(_) => {
+                    wtr.write_record([source.as_bytes()].into_iter().chain(record.iter()))?
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -3,8 +3,8 @@ use std::io::{self, Write};
 
 use serde::ser::{Serialize, SerializeStruct, Serializer};
 
-use crate::config::{Config, Delimiter};
-use crate::moonblade::Program;
+use crate::config::{Comment, Config, Delimiter};
+use crate::moonblade::{jaro_winkler, levenshtein, Program};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -17,8 +17,18 @@ Usage:
     xan cluster --help
 
 cluster options:
-    -k, --key <expr>  An expression to evaluate to generate a key
-                      for each row by transforming the selected cell.
+    -k, --key <expr>        An expression to evaluate to generate a key
+                            for each row by transforming the selected cell.
+    --threshold <t>         Cluster values by similarity instead of key collision.
+                            A value will join the first cluster whose leader,
+                            i.e. its first value, is at least this similar to it.
+                            Only values sharing a same key are compared, so that
+                            keys act as blocks. In this case, the key defaults
+                            to the lowercased first character of the value.
+    --similarity <fn>       Similarity function to use with --threshold. Can be
+                            either \"jaro_winkler\" or \"levenshtein\" (normalized
+                            by the length of the longest value).
+                            [default: jaro_winkler]
 
 Common options:
     -h, --help               Display this message
@@ -27,6 +37,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 ";
 
 #[derive(Deserialize)]
@@ -34,9 +46,12 @@ struct Args {
     arg_column: SelectColumns,
     arg_input: Option<String>,
     flag_key: Option<String>,
+    flag_threshold: Option<f64>,
+    flag_similarity: String,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -44,6 +59,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_column);
 
@@ -52,13 +68,27 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let sel_index = rconf.single_selection(headers)?;
 
-    let key_expr = match &args.flag_key {
-        Some(expr) => format!("col({}) | {}", sel_index, expr),
-        None => format!("col({})", sel_index),
+    let key_expr = match (&args.flag_key, args.flag_threshold) {
+        (Some(expr), _) => format!("col({}) | {}", sel_index, expr),
+        (None, Some(_)) => format!("col({}) | first(lower(trim(_)))", sel_index),
+        (None, None) => format!("col({})", sel_index),
     };
 
     let program = Program::parse(&key_expr, headers)?;
-    let mut clustering: Box<dyn ClusteringAlgorithm> = Box::<KeyCollision>::default();
+
+    let mut clustering: Box<dyn ClusteringAlgorithm> = match args.flag_threshold {
+        Some(threshold) => {
+            if !(0.0..=1.0).contains(&threshold) {
+                Err("--threshold must be between 0 and 1!")?;
+            }
+
+            Box::new(LeaderCollision::new(LeaderIndex::new(
+                Similarity::parse(&args.flag_similarity)?,
+                threshold,
+            )))
+        }
+        None => Box::<KeyCollision>::default(),
+    };
 
     let mut record = csv::ByteRecord::new();
     let mut index: usize = 0;
@@ -220,3 +250,109 @@ impl ClusteringAlgorithm for KeyCollision {
             .collect()
     }
 }
+
+#[derive(Clone, Copy)]
+pub enum Similarity {
+    JaroWinkler,
+    Levenshtein,
+}
+
+impl Similarity {
+    pub fn parse(name: &str) -> Result<Self, String> {
+        Ok(match name {
+            "jaro_winkler" => Self::JaroWinkler,
+            "levenshtein" => Self::Levenshtein,
+            _ => return Err(format!("unknown similarity \"{}\"", name)),
+        })
+    }
+
+    fn compute(&self, a: &str, b: &str) -> f64 {
+        match self {
+            Self::JaroWinkler => jaro_winkler(a, b),
+            Self::Levenshtein => {
+                let longest = a.chars().count().max(b.chars().count());
+
+                if longest == 0 {
+                    return 1.0;
+                }
+
+                1.0 - levenshtein(a, b) as f64 / longest as f64
+            }
+        }
+    }
+}
+
+// NOTE: values are only compared to the leaders, i.e. the first values, of
+// the clusters found in their block, i.e. sharing their key. This remains
+// quadratic in the worst case, but blocks usually keep it tractable.
+pub struct LeaderIndex {
+    similarity: Similarity,
+    threshold: f64,
+    blocks: HashMap<String, Vec<(String, usize)>>,
+    count: usize,
+}
+
+impl LeaderIndex {
+    pub fn new(similarity: Similarity, threshold: f64) -> Self {
+        Self {
+            similarity,
+            threshold,
+            blocks: HashMap::new(),
+            count: 0,
+        }
+    }
+
+    // NOTE: returns the id of the cluster the value belongs to, along with
+    // whether it just became the leader of a new cluster.
+    pub fn assign(&mut self, key: String, value: &str) -> (usize, bool) {
+        let block = self.blocks.entry(key).or_default();
+
+        for (leader, id) in block.iter() {
+            if leader == value || self.similarity.compute(leader, value) >= self.threshold {
+                return (*id, false);
+            }
+        }
+
+        let id = self.count;
+        self.count += 1;
+
+        block.push((value.to_string(), id));
+
+        (id, true)
+    }
+}
+
+struct LeaderCollision {
+    index: LeaderIndex,
+    clusters: Vec<(String, Vec<(usize, String)>)>,
+}
+
+impl LeaderCollision {
+    fn new(index: LeaderIndex) -> Self {
+        Self {
+            index,
+            clusters: Vec::new(),
+        }
+    }
+}
+
+impl ClusteringAlgorithm for LeaderCollision {
+    fn process(&mut self, index: usize, key: String, value: String) {
+        let (id, is_new) = self.index.assign(key.clone(), &value);
+
+        if is_new {
+            self.clusters.push((key, Vec::new()));
+        }
+
+        self.clusters[id].1.push((index, value));
+    }
+
+    fn into_clusters(self: Box<Self>) -> Vec<Cluster> {
+        self.clusters
+            .into_iter()
+            .enumerate()
+            .map(|(id, (key, entries))| Cluster::from_entries(id, key, entries))
+            .filter(|cluster| cluster.values.len() > 1)
+            .collect()
+    }
+}
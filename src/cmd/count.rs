@@ -1,4 +1,10 @@
-use crate::config::{Config, Delimiter};
+use std::fs;
+use std::io::Read;
+
+use indexmap::IndexMap;
+
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
@@ -8,12 +14,36 @@ Prints a count of the number of records in the CSV data.
 Note that the count will not include the header row (unless --no-headers is
 given).
 
+For a quick estimate on huge files, the --approx-lines flag will count
+newlines without parsing the CSV data at all. It is much faster, but will
+overcount rows whose quoted fields contain embedded newlines. The --bytes flag
+will simply report the size of the file in bytes (or the number of bytes read
+from stdin, or decompressed, for gzipped files).
+
+The -g, --groupby flag can be used to count the number of records per group
+instead, using a selection of columns, in a single pass. Groups will be written
+as a CSV file, in the order they were first seen, along with a \"count\" column:
+
+    $ xan count -g country file.csv
+
+This is equivalent to running \"xan groupby country 'count() as count'\", but
+groups can also be sorted by decreasing count using the --sort flag.
+
 Usage:
     xan count [options] [<input>]
 
 count options:
-    --csv  Output the result as a single column, single row CSV file with
-           a \"count\" header.
+    --csv                 Output the result as a single column, single row CSV
+                          file with a \"count\" header (or \"bytes\" when
+                          using --bytes).
+    --approx-lines        Count lines by scanning for newline bytes instead of
+                          parsing CSV data. Fast but inaccurate if some quoted
+                          fields contain newlines.
+    --bytes               Report the size of the input in bytes instead.
+    -g, --groupby <cols>  Count the number of records per group, given by
+                          this selection of columns, instead.
+    --sort                Sort groups by decreasing count. Only meaningful
+                          when grouping with -g, --groupby.
 
 Common options:
     -h, --help             Display this message
@@ -22,42 +52,78 @@ Common options:
                            the count.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
     flag_csv: bool,
+    flag_approx_lines: bool,
+    flag_bytes: bool,
+    flag_groupby: Option<SelectColumns>,
+    flag_sort: bool,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
     let wconf = Config::new(&args.flag_output);
 
-    let count = match conf.indexed()? {
-        Some(idx) => idx.count(),
-        None => {
-            let mut rdr = conf.reader()?;
-            let mut count = 0u64;
-            let mut record = csv::ByteRecord::new();
-            while rdr.read_byte_record(&mut record)? {
-                count += 1;
+    if args.flag_approx_lines && args.flag_bytes {
+        Err("--approx-lines and --bytes cannot be used together!")?;
+    }
+
+    if let Some(cols) = args.flag_groupby {
+        if args.flag_approx_lines || args.flag_bytes {
+            Err("-g, --groupby cannot be used with --approx-lines nor --bytes!")?;
+        }
+
+        return count_groups(conf.select(cols), &wconf, args.flag_sort);
+    }
+
+    if args.flag_sort {
+        Err("--sort can only be used with -g, --groupby!")?;
+    }
+
+    let count = if args.flag_bytes {
+        count_bytes(&conf)?
+    } else if args.flag_approx_lines {
+        let lines = count_lines(&conf)?;
+
+        if conf.no_headers {
+            lines
+        } else {
+            lines.saturating_sub(1)
+        }
+    } else {
+        match conf.indexed()? {
+            Some(idx) => idx.count(),
+            None => {
+                let mut rdr = conf.reader()?;
+                let mut count = 0u64;
+                let mut record = csv::ByteRecord::new();
+                while rdr.read_byte_record(&mut record)? {
+                    count += 1;
+                }
+                count
             }
-            count
         }
     };
 
     if args.flag_csv {
         let mut writer = wconf.writer()?;
         let mut record = csv::ByteRecord::new();
-        record.push_field(b"count");
+        record.push_field(if args.flag_bytes { b"bytes" } else { b"count" });
         writer.write_byte_record(&record)?;
 
         record.clear();
@@ -72,3 +138,87 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     Ok(())
 }
+
+fn count_groups(conf: Config, wconf: &Config, sort: bool) -> CliResult<()> {
+    let mut rdr = conf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+    let sel = conf.selection(&headers)?;
+
+    let mut counts: IndexMap<Vec<Vec<u8>>, u64> = IndexMap::new();
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        counts
+            .entry(sel.collect(&record))
+            .and_modify(|count| *count += 1)
+            .or_insert(1);
+    }
+
+    if sort {
+        counts.sort_by(|_, a, _, b| b.cmp(a));
+    }
+
+    let mut writer = wconf.writer()?;
+
+    let mut output_headers = csv::ByteRecord::new();
+
+    for (i, header) in sel.iter().zip(sel.select(&headers)) {
+        if conf.no_headers {
+            output_headers.push_field(i.to_string().as_bytes());
+        } else {
+            output_headers.push_field(header);
+        }
+    }
+
+    output_headers.push_field(b"count");
+    writer.write_byte_record(&output_headers)?;
+
+    for (group, count) in counts {
+        record.clear();
+
+        for cell in group {
+            record.push_field(&cell);
+        }
+
+        record.push_field(count.to_string().as_bytes());
+        writer.write_byte_record(&record)?;
+    }
+
+    Ok(writer.flush()?)
+}
+
+fn count_bytes(conf: &Config) -> CliResult<u64> {
+    if let Some(path) = &conf.path {
+        if !path.to_string_lossy().ends_with(".gz") {
+            return Ok(fs::metadata(path)?.len());
+        }
+    }
+
+    Ok(std::io::copy(&mut conf.io_reader()?, &mut std::io::sink())?)
+}
+
+fn count_lines(conf: &Config) -> CliResult<u64> {
+    let mut reader = conf.io_reader()?;
+    let mut buffer = vec![0; 1 << 16];
+    let mut count = 0u64;
+    let mut last_byte: Option<u8> = None;
+
+    loop {
+        let n = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err)?,
+        };
+
+        count += buffer[..n].iter().filter(|b| **b == b'\n').count() as u64;
+        last_byte = Some(buffer[n - 1]);
+    }
+
+    // NOTE: last line may not end with a newline
+    if matches!(last_byte, Some(b) if b != b'\n') {
+        count += 1;
+    }
+
+    Ok(count)
+}
@@ -4,7 +4,8 @@ use dlv_list::{Index, VecList};
 use indexmap::{map::Entry as IndexMapEntry, IndexMap};
 use transient_btree_index::{BtreeConfig, BtreeIndex};
 
-use crate::config::{Config, Delimiter};
+use crate::cmd::cluster::{LeaderIndex, Similarity};
+use crate::config::{Comment, Config, Delimiter};
 use crate::moonblade::ChooseProgram;
 use crate::select::SelectColumns;
 use crate::util;
@@ -36,6 +37,21 @@ Notice how the column names of the currently kept row were prefixed
 with \"current_\", while the ones of the new row were prefixed
 with \"new_\" instead.
 
+It is also possible to perform a fuzzy deduplication using the --fuzzy flag,
+in which case a row will be considered a duplicate if the value of the given
+column is similar enough (see --threshold and --similarity) to the one of an
+already kept row. For instance, to deduplicate a messy contact list:
+
+    $ xan dedup --fuzzy name --threshold 0.9 contacts.csv > deduped.csv
+
+Beware that fuzzy deduplication is quadratic in the worst case, since each
+new value must be compared to every kept one. To mitigate this, values are
+only compared when they belong to a same block, which is by default defined
+by their first character (see --blocking). This means, however, that similar
+values not starting with the same character will never be considered
+duplicates. Note also that values are lowercased and trimmed before being
+compared.
+
 Note that if you need to aggregate cell values from duplicated
 rows, you should probably check out `xan groupby` instead, that can
 be used for this very purpose, especially with the --keep flag.
@@ -59,10 +75,30 @@ dedup options:
     -e, --external      Use an external btree index to keep the index on disk and avoid
                         overflowing RAM. Does not work with -l/--keep-last and --keep-duplicates.
     --keep-duplicates   Emit only the duplicated rows.
+    --dups-to <file>    Write the rows dropped by the deduplication to <file>,
+                        with the same headers as the main output. Does not
+                        work with --keep-duplicates, --choose and --check.
     --choose <expr>     Evaluate an expression that must return whether to
                         keep a newly seen row or not. Column name in the given
                         expression will be prefixed with \"current_\" for the
                         currently kept row and \"new_\" for the new row to consider.
+    --fuzzy <column>    Perform a fuzzy deduplication using the values of given
+                        column, relying on the same machinery as the similarity
+                        clustering of `xan cluster`. Cannot be used with the
+                        selection, sorted, external, keep-last, keep-duplicates,
+                        choose nor check options.
+    --threshold <t>     Minimum similarity, between 0 and 1, for two values to
+                        be considered duplicates when using --fuzzy.
+                        [default: 0.9]
+    --similarity <fn>   Similarity function to use with --fuzzy. Can be either
+                        \"jaro_winkler\" or \"levenshtein\" (normalized by the
+                        length of the longest value).
+                        [default: jaro_winkler]
+    --blocking <mode>   How to block values to compare when using --fuzzy.
+                        Can be either \"first\", to only compare values starting
+                        with the same character, or \"none\", to compare all
+                        values (beware, this can be very slow).
+                        [default: first]
 
 Common options:
     -h, --help               Display this message
@@ -71,6 +107,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 ";
 
 #[derive(Deserialize)]
@@ -81,11 +119,17 @@ struct Args {
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_sorted: bool,
     flag_keep_last: bool,
     flag_external: bool,
     flag_keep_duplicates: bool,
     flag_choose: Option<String>,
+    flag_dups_to: Option<String>,
+    flag_fuzzy: Option<SelectColumns>,
+    flag_threshold: f64,
+    flag_similarity: String,
+    flag_blocking: String,
 }
 
 type DeduplicationKey = Vec<Vec<u8>>;
@@ -127,12 +171,34 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("must select only one of --choose, -l/--keep-last, --keep-duplicates")?;
     }
 
+    if args.flag_dups_to.is_some()
+        && (args.flag_keep_duplicates || args.flag_choose.is_some() || args.flag_check)
+    {
+        Err("--dups-to does not work with --keep-duplicates, --choose nor --check!")?;
+    }
+
+    if args.flag_fuzzy.is_some() {
+        if !args.flag_select.is_empty()
+            || args.flag_sorted
+            || args.flag_external
+            || args.flag_check
+            || mutually_exclusive_count > 0
+        {
+            Err("--fuzzy cannot be used with -s/--select, -S/--sorted, -e/--external, --check, -l/--keep-last, --keep-duplicates nor --choose!")?;
+        }
+
+        if !(0.0..=1.0).contains(&args.flag_threshold) {
+            Err("--threshold must be between 0 and 1!")?;
+        }
+    }
+
     if args.flag_sorted {
         args.flag_external = false;
     }
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
@@ -164,6 +230,66 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     rconf.write_headers(&mut rdr, &mut wtr)?;
 
+    let mut dups_wtr = match &args.flag_dups_to {
+        Some(path) => {
+            let mut dups_wtr = Config::new(&Some(path.clone())).writer()?;
+            rconf.write_headers(&mut rdr, &mut dups_wtr)?;
+            Some(dups_wtr)
+        }
+        None => None,
+    };
+
+    macro_rules! write_dup {
+        ($record:expr) => {
+            if let Some(dups_wtr) = dups_wtr.as_mut() {
+                dups_wtr.write_byte_record($record)?;
+            }
+        };
+    }
+
+    // Fuzzy
+    if let Some(fuzzy_column) = args.flag_fuzzy {
+        let column_index = fuzzy_column.single_selection(&headers, !args.flag_no_headers)?;
+
+        let blocking = match args.flag_blocking.as_str() {
+            "first" => true,
+            "none" => false,
+            _ => Err(format!("unknown blocking mode \"{}\"", args.flag_blocking))?,
+        };
+
+        let mut leaders = LeaderIndex::new(
+            Similarity::parse(&args.flag_similarity)?,
+            args.flag_threshold,
+        );
+
+        let mut record = csv::ByteRecord::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            let value = String::from_utf8_lossy(&record[column_index])
+                .trim()
+                .to_lowercase();
+
+            let key = if blocking {
+                value.chars().next().map(String::from).unwrap_or_default()
+            } else {
+                String::new()
+            };
+
+            // NOTE: only the first row of each fuzzy cluster is kept
+            if leaders.assign(key, &value).1 {
+                wtr.write_byte_record(&record)?;
+            } else {
+                write_dup!(&record);
+            }
+        }
+
+        if let Some(mut dups_wtr) = dups_wtr {
+            dups_wtr.flush()?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
     // External
     if args.flag_external {
         let mut record = csv::ByteRecord::new();
@@ -178,9 +304,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
             if btree_index.insert(key, ())?.is_none() {
                 wtr.write_byte_record(&record)?;
+            } else {
+                write_dup!(&record);
             }
         }
 
+        if let Some(mut dups_wtr) = dups_wtr {
+            dups_wtr.flush()?;
+        }
+
         return Ok(wtr.flush()?);
     }
 
@@ -212,6 +344,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
                 if already_seen.insert(key) {
                     wtr.write_byte_record(&record)?;
+                } else {
+                    write_dup!(&record);
                 }
             }
         }
@@ -223,7 +357,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             for result in rdr.byte_records() {
                 let record = result?;
                 let key = sel.collect(&record);
-                set.push(key, record);
+
+                if let Some(dropped) = set.push(key, record) {
+                    write_dup!(&dropped);
+                }
             }
 
             for record in set.into_iter() {
@@ -248,7 +385,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         wtr.write_byte_record(&record)?;
                         current = Some(key);
                     }
-                    _ => (),
+                    _ => {
+                        write_dup!(&record);
+                    }
                 };
             }
         }
@@ -265,7 +404,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     Some((current_key, record_to_flush)) if current_key != key => {
                         wtr.write_byte_record(&record_to_flush)?;
                     }
-                    _ => (),
+                    Some((_, dropped)) => {
+                        write_dup!(&dropped);
+                    }
+                    None => (),
                 }
 
                 current = Some((key, record));
@@ -421,6 +563,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     }
 
+    if let Some(mut dups_wtr) = dups_wtr {
+        dups_wtr.flush()?;
+    }
+
     Ok(wtr.flush()?)
 }
 
@@ -437,18 +583,23 @@ impl KeepLastSet {
         }
     }
 
-    fn push(&mut self, key: DeduplicationKey, record: csv::ByteRecord) {
+    // NOTE: returns the record replaced by the given one, if any
+    fn push(&mut self, key: DeduplicationKey, record: csv::ByteRecord) -> Option<csv::ByteRecord> {
         match self.map.entry(key) {
             Entry::Occupied(mut entry) => {
                 let current_index = entry.get_mut();
 
-                self.list.remove(*current_index);
+                let dropped = self.list.remove(*current_index);
                 *current_index = self.list.push_back(record);
+
+                dropped
             }
             Entry::Vacant(entry) => {
                 entry.insert(self.list.push_back(record));
+
+                None
             }
-        };
+        }
     }
 
     fn into_iter(self) -> impl Iterator<Item = csv::ByteRecord> {
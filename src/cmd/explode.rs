@@ -1,6 +1,8 @@
+use std::borrow::Cow;
+
 use bstr::ByteSlice;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliError;
@@ -10,7 +12,9 @@ static USAGE: &str = "
 Explode CSV rows into multiple ones by splitting selected cell using the pipe
 character (\"|\") or any separator given to the --sep flag.
 
-This is conceptually the inverse of the \"implode\" command.
+This is the inverse of the \"implode\" command, meaning that exploding a
+column and then imploding it back using the same separator will reproduce the
+original file, as long as exploded rows remain consecutive.
 
 For instance the following CSV:
 
@@ -31,6 +35,21 @@ John,blue
 John,yellow
 Mary,red
 
+Cells containing JSON arrays can also be exploded using the --json flag, in
+which case one row will be emitted per array item. Strings are emitted as-is,
+null values as empty cells, while nested arrays and objects are serialized
+back to JSON:
+
+    $ xan explode --json items file.csv
+
+When using --json, rows whose cells contain an empty array (or are empty) will
+be dropped, unless the --keep-empty flag is given, in which case they will be
+emitted once with an empty cell instead.
+
+Empty cells will be exploded into a single row with an empty cell, so that
+the round trip with \"implode\" remains lossless. Use the --skip-empty flag
+to drop rows whose selected cells are all empty instead.
+
 Note finally that the file can be exploded on multiple well-aligned columns (that
 is to say selected cells must all be splitted into a same number of values).
 
@@ -47,6 +66,13 @@ explode options:
                          in CSV format if exploding multiple columns.
                          See 'xan rename' help for more details.
                          Does not work with -S, --singular.
+    --skip-empty         Drop rows whose selected cells are all empty, instead
+                         of emitting them once with empty cells. Does not work
+                         with --json.
+    --json               Parse the cells as JSON arrays instead of splitting
+                         them using --sep.
+    --keep-empty         When using --json, keep the rows having empty arrays
+                         with an empty cell, instead of dropping them.
 
 Common options:
     -h, --help             Display this message
@@ -55,6 +81,8 @@ Common options:
                            as headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -64,9 +92,13 @@ struct Args {
     flag_sep: String,
     flag_singular: bool,
     flag_rename: Option<String>,
+    flag_skip_empty: bool,
+    flag_json: bool,
+    flag_keep_empty: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -76,8 +108,21 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("-S/--singular cannot work with -r/--rename!")?;
     }
 
+    if args.flag_keep_empty && !args.flag_json {
+        Err("--keep-empty only works with --json!")?;
+    }
+
+    if args.flag_skip_empty && args.flag_json {
+        Err("--skip-empty does not work with --json!")?;
+    }
+
+    if args.flag_sep.is_empty() {
+        Err("--sep cannot be empty!")?;
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_columns);
 
@@ -139,10 +184,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::ByteRecord::new();
 
     while rdr.read_byte_record(&mut record)? {
-        let splits: Vec<Vec<&[u8]>> = sel
-            .select(&record)
-            .map(|cell| cell.split_str(&args.flag_sep).collect())
-            .collect();
+        if args.flag_skip_empty && sel.select(&record).all(|cell| cell.is_empty()) {
+            continue;
+        }
+
+        let splits: Vec<Vec<Cow<[u8]>>> = if args.flag_json {
+            sel.select(&record)
+                .map(parse_json_array)
+                .collect::<Result<_, _>>()?
+        } else {
+            sel.select(&record)
+                .map(|cell| cell.split_str(&args.flag_sep).map(Cow::Borrowed).collect())
+                .collect()
+        };
 
         if splits.iter().skip(1).any(|s| s.len() != splits[0].len()) {
             return Err(CliError::Other(
@@ -151,7 +205,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
 
         if splits[0].is_empty() {
-            wtr.write_byte_record(&record)?;
+            if !args.flag_json {
+                wtr.write_byte_record(&record)?;
+            } else if args.flag_keep_empty {
+                let output_record: csv::ByteRecord = record
+                    .iter()
+                    .zip(sel_mask.iter())
+                    .map(|(cell, mask)| if mask.is_none() { cell } else { b"" })
+                    .collect();
+
+                wtr.write_byte_record(&output_record)?;
+            }
+
             continue;
         }
 
@@ -161,7 +226,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 .zip(sel_mask.iter())
                 .map(|(cell, mask)| {
                     if let Some(j) = mask {
-                        splits[*j][i]
+                        splits[*j][i].as_ref()
                     } else {
                         cell
                     }
@@ -174,3 +239,36 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     Ok(wtr.flush()?)
 }
+
+fn parse_json_array(cell: &[u8]) -> CliResult<Vec<Cow<'static, [u8]>>> {
+    if cell.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let value: serde_json::Value = serde_json::from_slice(cell).map_err(|_| {
+        format!(
+            "could not parse cell as JSON: {}",
+            String::from_utf8_lossy(cell)
+        )
+    })?;
+
+    match value {
+        serde_json::Value::Array(items) => Ok(items
+            .into_iter()
+            .map(|item| {
+                Cow::Owned(match item {
+                    serde_json::Value::Null => vec![],
+                    serde_json::Value::String(string) => string.into_bytes(),
+                    serde_json::Value::Bool(_) | serde_json::Value::Number(_) => {
+                        item.to_string().into_bytes()
+                    }
+                    _ => serde_json::to_vec(&item).unwrap(),
+                })
+            })
+            .collect()),
+        _ => Err(CliError::Other(format!(
+            "expected a JSON array but got: {}",
+            String::from_utf8_lossy(cell)
+        ))),
+    }
+}
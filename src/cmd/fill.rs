@@ -1,4 +1,4 @@
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -12,6 +12,15 @@ For instance, replacing empty values with 0 everywhere in the file:
 
     $ xan fill -v 0 data.csv > filled.csv
 
+Use --up to fill empty cells with the next non-empty value instead (this is
+usually called backward filling). Note that this requires to buffer the whole
+file in memory.
+
+Combining -v, --value with --down or --up will fill the cells for which no
+previous (respectively next) non-empty value exists with the given value:
+
+    $ xan fill -s status --down -v unknown data.csv > filled.csv
+
 Usage:
     xan fill [options] [<input>]
     xan fill --help
@@ -20,6 +29,9 @@ fill options:
     -s, --select <cols>  Selection of columns to fill.
     -v, --value <value>  Fill empty cells using provided value instead of using
                          last non-empty value.
+    --down               Fill empty cells using the previous non-empty value.
+                         This is the default, unless -v, --value is given.
+    --up                 Fill empty cells using the next non-empty value.
 
 Common options:
     -h, --help             Display this message
@@ -28,6 +40,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -35,15 +49,60 @@ struct Args {
     arg_input: Option<String>,
     flag_select: SelectColumns,
     flag_value: Option<String>,
+    flag_down: bool,
+    flag_up: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
 }
 
+struct Filler {
+    mask: Vec<Option<usize>>,
+    last_values: Vec<Option<Vec<u8>>>,
+    fallback: Option<Vec<u8>>,
+    propagate: bool,
+}
+
+impl Filler {
+    fn fill(&mut self, record: &csv::ByteRecord) -> csv::ByteRecord {
+        let mut filled_record = csv::ByteRecord::new();
+
+        for (i, cell) in record.iter().enumerate() {
+            if !matches!(self.mask.get(i), Some(Some(_))) {
+                filled_record.push_field(cell);
+                continue;
+            }
+
+            if !cell.is_empty() {
+                if self.propagate {
+                    self.last_values[i] = Some(cell.to_vec());
+                }
+
+                filled_record.push_field(cell);
+            } else if let Some(last_value) = &self.last_values[i] {
+                filled_record.push_field(last_value);
+            } else if let Some(value) = &self.fallback {
+                filled_record.push_field(value);
+            } else {
+                filled_record.push_field(cell);
+            }
+        }
+
+        filled_record
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_down && args.flag_up {
+        Err("--down and --up cannot be used at the same time!")?;
+    }
+
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
@@ -55,44 +114,37 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let sel = rconf.selection(headers)?;
     let mask = sel.indexed_mask(headers.len());
 
+    let mut filler = Filler {
+        last_values: vec![None; mask.len()],
+        mask,
+        propagate: args.flag_down || args.flag_up || args.flag_value.is_none(),
+        fallback: args.flag_value.map(|value| value.into_bytes()),
+    };
+
     rconf.write_headers(&mut rdr, &mut wtr)?;
 
-    let mut previous: Option<csv::ByteRecord> = None;
+    if args.flag_up {
+        let records = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
+
+        let mut filled_records = records
+            .iter()
+            .rev()
+            .map(|record| filler.fill(record))
+            .collect::<Vec<_>>();
+
+        filled_records.reverse();
+
+        for record in filled_records {
+            wtr.write_byte_record(&record)?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
 
     for result in rdr.byte_records() {
         let record = result?;
 
-        match previous.as_mut() {
-            None => {
-                wtr.write_byte_record(&record)?;
-                previous = Some(record);
-            }
-            Some(previous_record) => {
-                let filled_record = mask
-                    .iter()
-                    .enumerate()
-                    .map(|(i, opt)| {
-                        let current_cell = &record[i];
-
-                        if opt.is_some() {
-                            if !current_cell.is_empty() {
-                                current_cell
-                            } else if let Some(value) = &args.flag_value {
-                                value.as_bytes()
-                            } else {
-                                &previous_record[i]
-                            }
-                        } else {
-                            current_cell
-                        }
-                    })
-                    .collect::<csv::ByteRecord>();
-
-                wtr.write_byte_record(&filled_record)?;
-
-                *previous_record = filled_record;
-            }
-        };
+        wtr.write_byte_record(&filler.fill(&record))?;
     }
 
     Ok(wtr.flush()?)
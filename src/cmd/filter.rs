@@ -1,7 +1,10 @@
+use std::collections::HashSet;
+
 use crate::cmd::moonblade::{
-    run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMode,
+    run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMembership, MoonbladeMode,
 };
-use crate::config::Delimiter;
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
@@ -31,8 +34,23 @@ the --cheatsheet flag.
 
 If you want to list available functions, use the --functions flag.
 
+Rows can also be kept only if the value of one of their columns can be found
+in a column of another CSV file, without needing to write a join, using
+the --in flag (or --not-in, to keep only the rows whose value cannot be found):
+
+    $ xan filter --in ids.csv id data.csv
+
+The reference column will be loaded in memory as a set of values. It is
+read in the given reference file using the same column name, unless
+you give another one through the --ref-column flag. This can be combined
+with an expression using the --where flag:
+
+    $ xan filter --not-in blacklist.csv user --where 'count > 10' data.csv
+
 Usage:
     xan filter [options] <expression> [<input>]
+    xan filter [options] --in <file> <column> [<input>]
+    xan filter [options] --not-in <file> <column> [<input>]
     xan filter --cheatsheet
     xan filter --functions
     xan filter --help
@@ -45,6 +63,7 @@ filter options:
     -t, --threads <threads>    Parellize computations using this many threads. Use -p, --parallel
                                if you want the number of threads to be automatically chosen instead.
     -v, --invert-match         If set, will invert the evaluated value.
+                               Cannot be used with --in nor --not-in.
     -l, --limit <n>            Maximum number of rows to return. Useful to avoid downstream
                                buffering some times (e.g. when searching for very few
                                rows in a big file before piping to `view` or `flatten`).
@@ -54,6 +73,18 @@ filter options:
                                  - "ignore": coerce result for row to null
                                  - "log": print error to stderr
                                [default: panic].
+    --in <file>                Only keep rows whose value in <column> can be found in
+                               the reference column of given CSV file.
+    --not-in <file>            Only keep rows whose value in <column> cannot be found in
+                               the reference column of given CSV file.
+    --ref-column <name>        Column of the reference file to read when using --in
+                               or --not-in. Will default to <column>.
+    --where <expression>       Expression to evaluate on rows kept when using --in
+                               or --not-in.
+    --glob <pattern>           Read every file matching given glob pattern instead of
+                               a single input. Files must all have the same headers.
+                               Use the filename() function to know which file a row
+                               comes from.
 
 Common options:
     -h, --help               Display this message
@@ -62,22 +93,65 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 "#;
 
 #[derive(Deserialize)]
 struct Args {
-    arg_expression: String,
+    arg_expression: Option<String>,
+    arg_column: Option<String>,
     arg_input: Option<String>,
     flag_output: Option<String>,
     flag_cheatsheet: bool,
     flag_functions: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_limit: Option<usize>,
     flag_threads: Option<usize>,
     flag_errors: String,
     flag_invert_match: bool,
+    flag_in: Option<String>,
+    flag_not_in: Option<String>,
+    flag_ref_column: Option<String>,
+    flag_where: Option<String>,
+    flag_glob: Option<String>,
+}
+
+impl Args {
+    fn membership(&self, column: &str) -> CliResult<MoonbladeMembership> {
+        let (path, invert) = match (&self.flag_in, &self.flag_not_in) {
+            (Some(path), None) => (path.clone(), false),
+            (None, Some(path)) => (path.clone(), true),
+            _ => Err("--in and --not-in cannot be used at the same time!")?,
+        };
+
+        let ref_column = self.flag_ref_column.as_deref().unwrap_or(column);
+
+        let rconf = Config::new(&Some(path))
+            .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
+            .no_headers(self.flag_no_headers)
+            .select(SelectColumns::parse(ref_column)?);
+
+        let mut rdr = rconf.reader()?;
+        let column_index = rconf.single_selection(rdr.byte_headers()?)?;
+
+        let mut values = HashSet::new();
+        let mut record = csv::ByteRecord::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            values.insert(record[column_index].to_vec());
+        }
+
+        Ok(MoonbladeMembership {
+            column: SelectColumns::parse(column)?,
+            values,
+            invert,
+        })
+    }
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -93,18 +167,47 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("-l, --limit does not work when parallelizing!")?;
     }
 
+    let mut membership = None;
+
+    let expression = if args.flag_in.is_some() || args.flag_not_in.is_some() {
+        if args.flag_invert_match {
+            Err("-v, --invert-match cannot be used with --in or --not-in! Use the other flag instead.")?;
+        }
+
+        let column = args
+            .arg_column
+            .as_deref()
+            .or(args.arg_expression.as_deref())
+            .unwrap();
+
+        membership = Some(args.membership(column)?);
+
+        args.flag_where
+            .clone()
+            .unwrap_or_else(|| "true".to_string())
+    } else {
+        if args.flag_where.is_some() || args.flag_ref_column.is_some() {
+            Err("--where and --ref-column can only be used with --in or --not-in!")?;
+        }
+
+        args.arg_expression.clone().unwrap_or_default()
+    };
+
     let moonblade_args = MoonbladeCmdArgs {
         print_cheatsheet: args.flag_cheatsheet,
         print_functions: args.flag_functions,
-        map_expr: args.arg_expression,
+        map_expr: expression,
         input: args.arg_input,
         output: args.flag_output,
         no_headers: args.flag_no_headers,
         delimiter: args.flag_delimiter,
+        comment: args.flag_comment,
         parallelization,
         error_policy: MoonbladeErrorPolicy::try_from_restricted(&args.flag_errors)?,
         mode: MoonbladeMode::Filter(args.flag_invert_match),
         limit: args.flag_limit,
+        membership,
+        glob: args.flag_glob,
         ..Default::default()
     };
 
@@ -1,7 +1,11 @@
+use serde_json::Value;
+
 use crate::cmd::moonblade::{
     run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMode,
 };
-use crate::config::Delimiter;
+use crate::config::{Comment, Config, Delimiter};
+use crate::json::JSONFlattener;
+use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
@@ -55,12 +59,38 @@ Will produce the following result:
 name,age,surname
 Mary Sue,45,Sue
 
+Finally, the command is also able to normalize a column containing JSON
+objects using the --json flag, in which case no expression is required and
+the given column will be replaced by one column per key found in the objects,
+prefixed by the name of the column and using dots to represent nesting.
+
+For instance, given the following CSV file:
+
+name,meta
+John,"{""user"": {""id"": 1}, ""lang"": ""en""}"
+Mary,"{""user"": {""id"": 2, ""verified"": true}}"
+
+The following command:
+
+    $ xan flatmap --json meta
+
+Will produce the following result:
+
+name,meta.lang,meta.user.id,meta.user.verified
+John,en,1,
+Mary,,2,true
+
+Note that, since keys must be collected across all rows to produce a stable
+header, this mode needs to buffer the whole file into memory. Keys missing from
+some objects will be represented by empty cells.
+
 For a quick review of the capabilities of the script language, use
 the --cheatsheet flag.
 
 If you want to list available functions, use the --functions flag.
 
 Usage:
+    xan flatmap [options] --json <column> [<input>]
     xan flatmap [options] <expression> <column> [<input>]
     xan flatmap --cheatsheet
     xan flatmap --functions
@@ -68,6 +98,8 @@ Usage:
 
 flatmap options:
     -r, --replace <column>     Name of the column that will be replaced by the mapped values.
+    --json                     Flatten the JSON objects of the given column into new
+                               columns instead of evaluating an expression.
     -p, --parallel             Whether to use parallelization to speed up computations.
                                Will automatically select a suitable number of threads to use
                                based on your number of cores. Use -t, --threads if you want to
@@ -87,6 +119,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 "#;
 
 #[derive(Deserialize)]
@@ -99,14 +133,32 @@ struct Args {
     flag_functions: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
     flag_errors: String,
     flag_replace: Option<String>,
+    flag_json: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_json {
+        // NOTE: docopt may understand the column as being the expression.
+        if !args.arg_expression.is_empty() {
+            if args.arg_input.is_some() {
+                Err("cannot give an <expression> with --json!")?;
+            }
+
+            args.arg_input = Some(std::mem::replace(
+                &mut args.arg_column,
+                std::mem::take(&mut args.arg_expression),
+            ));
+        }
+
+        return flatten_json_column(args);
+    }
 
     let parallelization = match (args.flag_parallel, args.flag_threads) {
         (true, None) => Some(None),
@@ -124,6 +176,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         output: args.flag_output,
         no_headers: args.flag_no_headers,
         delimiter: args.flag_delimiter,
+        comment: args.flag_comment,
         parallelization,
         error_policy: MoonbladeErrorPolicy::try_from_restricted(&args.flag_errors)?,
         mode: MoonbladeMode::Flatmap,
@@ -132,3 +185,85 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     run_moonblade_cmd(moonblade_args)
 }
+
+fn flatten_json_column(args: Args) -> CliResult<()> {
+    if args.flag_replace.is_some() {
+        Err("-r/--replace cannot be used with --json!")?;
+    }
+
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
+        .no_headers(args.flag_no_headers)
+        .select(SelectColumns::parse(&args.arg_column)?);
+
+    let mut rdr = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    let headers = rdr.headers()?.clone();
+    let column_index = rconfig.single_selection(rdr.byte_headers()?)?;
+
+    let mut records = Vec::new();
+    let mut values = Vec::new();
+
+    for (i, result) in rdr.records().enumerate() {
+        let record = result?;
+        let cell = &record[column_index];
+
+        let value = if cell.is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_str::<Value>(cell) {
+                Ok(value @ Value::Object(_)) => value,
+                _ => Err(format!(
+                    "row n°{}: cell does not contain a JSON object: {}",
+                    i, cell
+                ))?,
+            }
+        };
+
+        records.push(record);
+        values.push(value);
+    }
+
+    let flattener = JSONFlattener::from_values(values.iter());
+
+    let prefix = if rconfig.no_headers {
+        column_index.to_string()
+    } else {
+        headers[column_index].to_string()
+    };
+
+    if !rconfig.no_headers {
+        let flattened_headers = flattener.headers();
+
+        wtr.write_record(
+            headers
+                .iter()
+                .take(column_index)
+                .map(|h| h.to_string())
+                .chain(
+                    flattened_headers
+                        .iter()
+                        .map(|h| format!("{}.{}", prefix, h)),
+                )
+                .chain(headers.iter().skip(column_index + 1).map(|h| h.to_string())),
+        )?;
+    }
+
+    let mut flattened = csv::StringRecord::new();
+
+    for (record, value) in records.iter().zip(values.iter()) {
+        flattener.fill_record(value, &mut flattened);
+
+        wtr.write_record(
+            record
+                .iter()
+                .take(column_index)
+                .chain(flattened.iter())
+                .chain(record.iter().skip(column_index + 1)),
+        )?;
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -1,6 +1,9 @@
 use std::fs;
+use std::io::Write;
 
-use crate::config::{Config, Delimiter};
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -21,6 +24,15 @@ pipe multiple xan commands together. However, you may want the final result to
 have a specific delimiter or record separator, and this is where 'xan fmt' is
 useful.
 
+The -a/--align flag can also be used to output aligned plain text instead
+of CSV data, which can be useful to produce human-readable fixed-width files.
+In this mode, cells are padded so that columns are aligned and separated
+by two spaces. Columns containing only numbers are aligned to the right, others
+to the left. Note that the first row is not taken into account when inferring
+whether a column is numeric, since it usually contains the headers. This mode
+needs to read the input twice, or to buffer it in memory when reading
+from stdin.
+
 Usage:
     xan fmt [options] [<input>]
 
@@ -38,12 +50,16 @@ fmt options:
                                produce invalid CSV data.
     --escape <arg>             The escape character to use. When not specified,
                                quotes are escaped by doubling them.
+    -a, --align                Output aligned plain text columns instead of CSV data.
+                               Other output formatting flags are ignored.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -56,10 +72,132 @@ struct Args {
     flag_tabs: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_quote: Delimiter,
     flag_quote_always: bool,
     flag_quote_never: bool,
     flag_escape: Option<Delimiter>,
+    flag_align: bool,
+}
+
+#[derive(Default)]
+struct ColumnLayout {
+    width: usize,
+    numeric: bool,
+    non_empty: usize,
+}
+
+// NOTE: line breaks & tabs are replaced by spaces before sanitizing, so that
+// they don't end up gluing words together.
+fn sanitize_cell(cell: &[u8]) -> String {
+    let cell = String::from_utf8_lossy(cell)
+        .replace("\r\n", " ")
+        .replace(['\r', '\n', '\t', '\x0c'], " ");
+
+    util::sanitize_text_for_single_line_printing(&cell)
+}
+
+fn align(args: &Args) -> CliResult<()> {
+    let rconfig = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
+        .no_headers(true)
+        .flexible(true);
+
+    let mut buffer: Option<Vec<csv::ByteRecord>> = if rconfig.is_std() {
+        Some(Vec::new())
+    } else {
+        None
+    };
+
+    let mut layout: Vec<ColumnLayout> = Vec::new();
+    let mut rdr = rconfig.reader()?;
+    let mut record = csv::ByteRecord::new();
+    let mut i: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        for (j, cell) in record.iter().enumerate() {
+            if j >= layout.len() {
+                layout.push(ColumnLayout {
+                    numeric: true,
+                    ..Default::default()
+                });
+            }
+
+            let column = &mut layout[j];
+            let cell = sanitize_cell(cell);
+
+            column.width = column.width.max(cell.width());
+
+            if i > 0 && !cell.trim().is_empty() {
+                column.non_empty += 1;
+
+                if column.numeric && cell.trim().parse::<f64>().is_err() {
+                    column.numeric = false;
+                }
+            }
+        }
+
+        if let Some(records) = buffer.as_mut() {
+            records.push(record.clone());
+        }
+
+        i += 1;
+    }
+
+    let mut wtr = Config::new(&args.flag_output).io_writer()?;
+    let mut line = String::new();
+
+    let mut write_record = |record: &csv::ByteRecord| -> CliResult<()> {
+        line.clear();
+
+        for (j, cell) in record.iter().enumerate() {
+            let column = &layout[j];
+            let cell = sanitize_cell(cell);
+            if j > 0 {
+                line.push_str("  ");
+            }
+
+            if column.numeric && column.non_empty > 0 {
+                line.push_str(&util::unicode_aware_pad(
+                    true,
+                    &cell,
+                    column.width,
+                    " ",
+                    None,
+                ));
+            } else {
+                line.push_str(&util::unicode_aware_pad(
+                    false,
+                    &cell,
+                    column.width,
+                    " ",
+                    None,
+                ));
+            }
+        }
+
+        writeln!(wtr, "{}", line.trim_end())?;
+
+        Ok(())
+    };
+
+    match buffer {
+        Some(records) => {
+            for record in records.iter() {
+                write_record(record)?;
+            }
+        }
+        None => {
+            let mut rdr = rconfig.reader()?;
+
+            while rdr.read_byte_record(&mut record)? {
+                write_record(&record)?;
+            }
+        }
+    }
+
+    Ok(wtr.flush()?)
 }
 
 impl Args {
@@ -98,8 +236,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
     let temp_file_guard_opt = args.resolve()?;
 
+    if args.flag_align {
+        align(&args)?;
+
+        if let Some(p) = &temp_file_guard_opt {
+            fs::rename(&p.0, args.arg_input.unwrap())?;
+        }
+
+        return Ok(());
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(true);
 
     let mut wconfig = Config::new(&args.flag_output)
@@ -1,8 +1,14 @@
+use std::io::{self, Write};
+use std::process::{Command, ExitStatus};
+
+use pariter::IteratorExt;
+
 use crate::cmd::moonblade::{
     run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMode,
 };
-use crate::config::Delimiter;
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
+use crate::CliError;
 use crate::CliResult;
 
 static USAGE: &str = r#"
@@ -11,6 +17,30 @@ but does not output anything except printing errors. Use the "map" command
 instead if you want to keep results. "foreach" should only be used when
 performing side-effects (writing files, copying files etc.).
 
+When performing IO-heavy side-effects, it can be useful to parallelize the
+evaluation using -p/--parallel or -t/--threads, which bounds the number of
+rows being processed concurrently. Note that without those flags, side-effects
+will be executed sequentially, in the order of the file's rows.
+
+When parallelizing, errors will still be handled according to -E/--errors
+in the order of the file's rows, and will always mention the index of
+the failing row.
+
+Alternatively, using the -c/--command flag, the given argument will be
+understood as a command template to execute through the shell for each row
+of the file, rather than as an expression. In this template, {column}
+placeholders will be replaced by the value of the relevant cell, properly
+escaped for the shell. Use {{ and }} if you need literal braces. Note that
+placeholders must be column indices instead when using -n/--no-headers.
+
+For instance, to convert a list of images:
+
+    $ xan foreach -c 'convert {path} {name}.png' images.csv
+
+Commands exiting with a non-zero status will be handled according to what
+was given to -E/--errors. Use --dry-run to print the commands instead of
+executing them.
+
 For a quick review of the capabilities of the script language, use
 the --cheatsheet flag.
 
@@ -23,6 +53,10 @@ Usage:
     xan foreach --help
 
 foreach options:
+    -c, --command              Treat <expression> as a shell command template
+                               with {column} placeholders to execute for each row.
+    --dry-run                  When using -c/--command, print the commands
+                               instead of executing them.
     -p, --parallel             Whether to use parallelization to speed up computations.
                                Will automatically select a suitable number of threads to use
                                based on your number of cores. Use -t, --threads if you want to
@@ -42,6 +76,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 "#;
 
 #[derive(Deserialize)]
@@ -53,9 +89,12 @@ struct Args {
     flag_cheatsheet: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
     flag_errors: String,
+    flag_command: bool,
+    flag_dry_run: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -67,6 +106,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         _ => None,
     };
 
+    if args.flag_dry_run && !args.flag_command {
+        Err("--dry-run can only be used with -c/--command!")?;
+    }
+
+    if args.flag_command {
+        return run_command_template(args, parallelization);
+    }
+
     let moonblade_args = MoonbladeCmdArgs {
         print_cheatsheet: args.flag_cheatsheet,
         print_functions: args.flag_functions,
@@ -75,6 +122,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         output: args.flag_output,
         no_headers: args.flag_no_headers,
         delimiter: args.flag_delimiter,
+        comment: args.flag_comment,
         parallelization,
         error_policy: MoonbladeErrorPolicy::try_from_restricted(&args.flag_errors)?,
         mode: MoonbladeMode::Foreach,
@@ -83,3 +131,197 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     run_moonblade_cmd(moonblade_args)
 }
+
+#[derive(Debug, Clone)]
+enum TemplatePart {
+    Literal(String),
+    Column(usize),
+}
+
+#[derive(Debug, Clone)]
+struct CommandTemplate {
+    parts: Vec<TemplatePart>,
+}
+
+impl CommandTemplate {
+    fn parse(template: &str, headers: &csv::ByteRecord, no_headers: bool) -> CliResult<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => {
+                                return Err(format!(
+                                    "unclosed placeholder in command template: {}",
+                                    template
+                                ))?
+                            }
+                        }
+                    }
+
+                    let index = if no_headers {
+                        name.trim()
+                            .parse::<usize>()
+                            .ok()
+                            .filter(|i| *i < headers.len())
+                    } else {
+                        headers.iter().position(|h| h == name.as_bytes())
+                    };
+
+                    match index {
+                        Some(i) => {
+                            if !literal.is_empty() {
+                                parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+                            }
+
+                            parts.push(TemplatePart::Column(i));
+                        }
+                        None => {
+                            return Err(format!("unknown column \"{}\" in command template", name))?
+                        }
+                    }
+                }
+                '}' => return Err(format!("unmatched '}}' in command template: {}", template))?,
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    fn render(&self, record: &csv::ByteRecord) -> String {
+        let mut command = String::new();
+
+        for part in self.parts.iter() {
+            match part {
+                TemplatePart::Literal(string) => command.push_str(string),
+                TemplatePart::Column(i) => {
+                    let cell = String::from_utf8_lossy(&record[*i]);
+                    command.push_str(&shell_escape(&cell));
+                }
+            }
+        }
+
+        command
+    }
+}
+
+fn shell_escape(value: &str) -> String {
+    let is_safe = !value.is_empty()
+        && value.chars().all(|c| {
+            c.is_ascii_alphanumeric()
+                || matches!(c, '_' | '-' | '.' | '/' | ',' | ':' | '=' | '+' | '@' | '%')
+        });
+
+    if is_safe {
+        return value.to_string();
+    }
+
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn execute_command(command: &str) -> io::Result<ExitStatus> {
+    Command::new("sh").arg("-c").arg(command).status()
+}
+
+fn handle_command_result(
+    policy: &MoonbladeErrorPolicy,
+    index: usize,
+    command: &str,
+    result: io::Result<ExitStatus>,
+) -> CliResult<()> {
+    let error = match result {
+        Ok(status) if status.success() => return Ok(()),
+        Ok(status) => format!("command \"{}\" failed with {}", command, status),
+        Err(err) => format!("could not execute command \"{}\": {}", command, err),
+    };
+
+    match policy {
+        MoonbladeErrorPolicy::Ignore => Ok(()),
+        MoonbladeErrorPolicy::Log => {
+            eprintln!("Row n°{}: {}", index, error);
+            Ok(())
+        }
+        _ => Err(CliError::Other(format!("Row n°{}: {}", index, error))),
+    }
+}
+
+fn run_command_template(args: Args, parallelization: Option<Option<usize>>) -> CliResult<()> {
+    let policy = MoonbladeErrorPolicy::try_from_restricted(&args.flag_errors)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
+        .no_headers(args.flag_no_headers);
+
+    let mut rdr = rconf.reader()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let template = CommandTemplate::parse(&args.arg_expression, &headers, args.flag_no_headers)?;
+
+    if args.flag_dry_run {
+        let mut wtr = Config::new(&args.flag_output).io_writer()?;
+
+        for result in rdr.byte_records() {
+            let record = result?;
+            writeln!(wtr, "{}", template.render(&record))?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    if let Some(threads) = parallelization {
+        rdr.into_byte_records()
+            .enumerate()
+            .parallel_map_custom(
+                |o| {
+                    if let Some(count) = threads {
+                        o.threads(count)
+                    } else {
+                        o
+                    }
+                },
+                move |(i, record)| -> CliResult<(usize, String, io::Result<ExitStatus>)> {
+                    let command = template.render(&record?);
+                    let result = execute_command(&command);
+
+                    Ok((i, command, result))
+                },
+            )
+            .try_for_each(|result| -> CliResult<()> {
+                let (i, command, status) = result?;
+                handle_command_result(&policy, i, &command, status)
+            })?;
+
+        return Ok(());
+    }
+
+    for (i, result) in rdr.byte_records().enumerate() {
+        let record = result?;
+        let command = template.render(&record);
+
+        handle_command_result(&policy, i, &command, execute_command(&command))?;
+    }
+
+    Ok(())
+}
@@ -1,14 +1,21 @@
+use std::cell::RefCell;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
 use bstr::ByteSlice;
 use csv::{self, ByteRecord};
+use rayon::prelude::*;
+use thread_local::ThreadLocal;
 
 use crate::collections::{ClusteredInsertHashmap, Counter};
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
-use crate::util;
+use crate::util::{self, ChunksIteratorExt};
 use crate::CliResult;
 
 type GroupKey = Vec<Vec<u8>>;
 type ValueKey = Vec<u8>;
+type GroupsToFieldsToCounter = ClusteredInsertHashmap<GroupKey, Vec<Counter<ValueKey>>>;
 
 static USAGE: &str = "
 Compute a frequency table on CSV data.
@@ -23,6 +30,11 @@ By default, there is a row for the N most frequent values for each field in the
 data. The number of values can be tweaked with --limit and --threshold flags
 respectively.
 
+Empty cells are counted as a category of their own, labelled \"<empty>\" (this
+label can be changed using --empty-label), which is subject to the limit and
+threshold given through --limit and --threshold like any other value. Use
+the -N, --no-extra flag to ignore them instead.
+
 Since this computes an exact frequency table, memory proportional to the
 cardinality of each selected column is required. If you expect this will overflow
 your memory, you can compute an approximate top-k using the -a, --approx flag.
@@ -54,8 +66,16 @@ frequency options:
     -t, --threshold <arg>  If set, won't return items having a count less than
                            this given threshold. It is combined with -l/--limit.
     -N, --no-extra         Don't include empty cells & remaining counts.
-    -p, --parallel         Allow sorting to be done in parallel. This is only
-                           useful with -l/--limit set to 0, or with -A, --all.
+    --empty-label <label>  Label used to count empty cells.
+                           [default: <empty>]
+    -p, --parallel         Count values in parallel, using one counter per thread
+                           that are merged at the end, and allow sorting to be done
+                           in parallel. Counting in parallel is not supported
+                           with -a, --approx. Note that the order of groups is
+                           not deterministic when used with -g, --groupby.
+    -c, --chunk-size <n>   Number of rows in a batch to send to a thread at once when
+                           using -p, --parallel.
+                           [default: 4096]
 
 Hidden options:
     --no-limit-we-reach-for-the-sky  Nothing to see here...
@@ -69,6 +89,8 @@ Common options:
                            names.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Clone, Deserialize)]
@@ -81,10 +103,13 @@ struct Args {
     flag_approx: bool,
     flag_threshold: Option<u64>,
     flag_no_extra: bool,
+    flag_empty_label: String,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
+    flag_chunk_size: NonZeroUsize,
     flag_groupby: Option<SelectColumns>,
     flag_no_limit_we_reach_for_the_sky: bool,
 }
@@ -97,6 +122,38 @@ impl Args {
     }
 }
 
+fn coerce_cell<'a>(cell: &'a [u8], no_extra: bool, empty_label: &'a [u8]) -> Option<&'a [u8]> {
+    if !no_extra {
+        if cell.is_empty() {
+            Some(empty_label)
+        } else {
+            Some(cell)
+        }
+    } else if cell.is_empty() {
+        None
+    } else {
+        Some(cell)
+    }
+}
+
+fn count_cell(
+    counter: &mut Counter<ValueKey>,
+    cell: &[u8],
+    sep: Option<&str>,
+    no_extra: bool,
+    empty_label: &[u8],
+) {
+    if let Some(sep) = sep {
+        for sub_cell in cell.split_str(sep) {
+            if let Some(sub_cell) = coerce_cell(sub_cell, no_extra, empty_label) {
+                counter.add(sub_cell.to_vec());
+            }
+        }
+    } else if let Some(cell) = coerce_cell(cell, no_extra, empty_label) {
+        counter.add(cell.to_vec());
+    }
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
     args.resolve();
@@ -119,6 +176,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
@@ -150,25 +208,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         sel.select(&headers).map(|h| h.to_vec()).collect()
     };
 
-    fn coerce_cell(cell: &[u8], no_extra: bool) -> Option<&[u8]> {
-        if !no_extra {
-            if cell.is_empty() {
-                Some(b"<empty>")
-            } else {
-                Some(cell)
-            }
-        } else if cell.is_empty() {
-            None
-        } else {
-            Some(cell)
-        }
-    }
+    let empty_label = args.flag_empty_label.as_bytes();
+    let sep = args.flag_sep.as_deref();
+    let no_extra = args.flag_no_extra;
+
+    // NOTE: approximate counters cannot be merged
+    let parallel_counting = args.flag_parallel && approx_k.is_none();
 
     if let Some(groupby_sel) = groupby_sel_opt {
-        let mut groups_to_fields_to_counter: ClusteredInsertHashmap<
-            GroupKey,
-            Vec<Counter<ValueKey>>,
-        > = ClusteredInsertHashmap::new();
+        let mut groups_to_fields_to_counter = GroupsToFieldsToCounter::new();
 
         let output_headers = {
             let mut r = ByteRecord::new();
@@ -185,44 +233,62 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
         wtr.write_byte_record(&output_headers)?;
 
-        let mut record = csv::ByteRecord::new();
-
-        // Aggregating
-        while rdr.read_byte_record(&mut record)? {
+        let count_record = |groups_to_fields_to_counter: &mut GroupsToFieldsToCounter,
+                            record: &ByteRecord| {
             let group: Vec<_> = groupby_sel
-                .select(&record)
+                .select(record)
                 .map(|cell| cell.to_vec())
                 .collect();
 
             let fields_to_counter = groups_to_fields_to_counter.insert_with(group, || {
-                let mut list = Vec::with_capacity(sel.len());
-
-                for _ in 0..sel.len() {
-                    list.push(Counter::new(approx_k));
-                }
-
-                list
+                (0..sel.len()).map(|_| Counter::new(approx_k)).collect()
             });
 
-            for (i, cell) in sel.select(&record).enumerate() {
-                if let Some(sep) = &args.flag_sep {
-                    for sub_cell in cell.split_str(sep) {
-                        let sub_cell = match coerce_cell(sub_cell, args.flag_no_extra) {
-                            Some(c) => c,
-                            None => continue,
-                        };
+            for (cell, counter) in sel.select(record).zip(fields_to_counter.iter_mut()) {
+                count_cell(counter, cell, sep, no_extra, empty_label);
+            }
+        };
+
+        let mut record = csv::ByteRecord::new();
 
-                        fields_to_counter[i].add(sub_cell.to_vec());
+        // Aggregating
+        if parallel_counting {
+            let local: Arc<ThreadLocal<RefCell<GroupsToFieldsToCounter>>> =
+                Arc::new(ThreadLocal::new());
+
+            rdr.into_byte_records()
+                .chunks(args.flag_chunk_size)
+                .par_bridge()
+                .try_for_each(|chunk| -> CliResult<()> {
+                    let mut local_groups = local
+                        .get_or(|| RefCell::new(ClusteredInsertHashmap::new()))
+                        .borrow_mut();
+
+                    for result in chunk {
+                        count_record(&mut local_groups, &result?);
                     }
-                } else {
-                    let cell = match coerce_cell(cell, args.flag_no_extra) {
-                        Some(c) => c,
-                        None => continue,
-                    };
 
-                    fields_to_counter[i].add(cell.to_vec());
+                    Ok(())
+                })?;
+
+            for local_groups in Arc::try_unwrap(local).ok().unwrap().into_iter() {
+                for (group, counters) in local_groups.into_inner().into_iter() {
+                    groups_to_fields_to_counter.insert_or_update_with(
+                        group,
+                        counters,
+                        |current_counters, other_counters| {
+                            for (counter, other) in current_counters.iter_mut().zip(other_counters)
+                            {
+                                counter.merge(other);
+                            }
+                        },
+                    );
                 }
             }
+        } else {
+            while rdr.read_byte_record(&mut record)? {
+                count_record(&mut groups_to_fields_to_counter, &record);
+            }
         }
 
         // Writing output
@@ -295,24 +361,40 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         let mut record = csv::ByteRecord::new();
 
         // Aggregating
-        while rdr.read_byte_record(&mut record)? {
-            for (cell, counter) in sel.select(&record).zip(fields.iter_mut()) {
-                if let Some(sep) = &args.flag_sep {
-                    for sub_cell in cell.split_str(sep) {
-                        let sub_cell = match coerce_cell(sub_cell, args.flag_no_extra) {
-                            Some(c) => c,
-                            None => continue,
-                        };
-
-                        counter.add(sub_cell.to_vec());
+        if parallel_counting {
+            let local: Arc<ThreadLocal<RefCell<Vec<Counter<ValueKey>>>>> =
+                Arc::new(ThreadLocal::new());
+
+            rdr.into_byte_records()
+                .chunks(args.flag_chunk_size)
+                .par_bridge()
+                .try_for_each(|chunk| -> CliResult<()> {
+                    let mut local_fields = local
+                        .get_or(|| {
+                            RefCell::new((0..sel.len()).map(|_| Counter::new(None)).collect())
+                        })
+                        .borrow_mut();
+
+                    for result in chunk {
+                        let record = result?;
+
+                        for (cell, counter) in sel.select(&record).zip(local_fields.iter_mut()) {
+                            count_cell(counter, cell, sep, no_extra, empty_label);
+                        }
                     }
-                } else {
-                    let cell = match coerce_cell(cell, args.flag_no_extra) {
-                        Some(c) => c,
-                        None => continue,
-                    };
 
-                    counter.add(cell.to_vec());
+                    Ok(())
+                })?;
+
+            for local_fields in Arc::try_unwrap(local).ok().unwrap().into_iter() {
+                for (counter, other) in fields.iter_mut().zip(local_fields.into_inner()) {
+                    counter.merge(other);
+                }
+            }
+        } else {
+            while rdr.read_byte_record(&mut record)? {
+                for (cell, counter) in sel.select(&record).zip(fields.iter_mut()) {
+                    count_cell(counter, cell, sep, no_extra, empty_label);
                 }
             }
         }
@@ -6,15 +6,38 @@ use std::{
 };
 
 use calamine::{open_workbook_auto_from_rs, Data, Reader};
+use glob::{glob, Pattern};
+use jiff::{civil, SignedDuration};
 use serde::de::{Deserialize, Deserializer, Error};
 use serde_json::{Map, Value};
 
 use crate::config::Config;
-use crate::json::for_each_json_value_as_csv_record;
+use crate::json::{for_each_json_value_as_csv_record, JSONFlattener};
 use crate::util;
 use crate::CliError;
 use crate::CliResult;
 
+// NOTE: excel stores datetimes as a number of days since 1899-12-30, and
+// considers, for legacy reasons, that 1900 was a leap year.
+fn format_excel_datetime(value: f64) -> String {
+    let days = if value >= 60.0 { value } else { value + 1.0 };
+    let ms = (days * 86_400_000.0).round() as i64;
+
+    match civil::date(1899, 12, 30)
+        .at(0, 0, 0, 0)
+        .checked_add(SignedDuration::from_millis(ms))
+    {
+        Ok(datetime) => {
+            if datetime.time() == civil::Time::midnight() {
+                datetime.date().to_string()
+            } else {
+                datetime.to_string()
+            }
+        }
+        Err(_) => value.to_string(),
+    }
+}
+
 // TODO: support json array value as paths or serialize them with a separator
 
 #[derive(Debug, Clone, Copy)]
@@ -22,6 +45,7 @@ enum SupportedFormat {
     Xls,
     NdJSON,
     JSONArray,
+    JSONDirectory,
     Text,
 }
 
@@ -31,12 +55,17 @@ impl SupportedFormat {
             "xls" | "xlsx" | "xlsb" | "ods" => Self::Xls,
             "jsonl" | "ndjson" => Self::NdJSON,
             "json" => Self::JSONArray,
+            "json-dir" => Self::JSONDirectory,
             "txt" => Self::Text,
             _ => return None,
         })
     }
 
     fn infer_from_extension(path: &str) -> Option<Self> {
+        if Path::new(path).is_dir() {
+            return Some(Self::JSONDirectory);
+        }
+
         Self::parse(
             Path::new(path)
                 .extension()
@@ -58,6 +87,12 @@ impl<'de> Deserialize<'de> for SupportedFormat {
 static USAGE: &str = "
 Convert a variety of data formats to CSV.
 
+When given a directory, every \"*.json\" file it contains will be read and
+flattened into a single row, the columns being the union of the keys found
+across all files (missing keys will yield empty cells):
+
+    $ xan from ./items/ > items.csv
+
 Usage:
     xan from [options] [<input>]
     xan from --help
@@ -72,6 +107,8 @@ Supported formats:
     ndjson  - Newline-delimited JSON
     jsonl   - Newline-delimited JSON
 
+    json-dir - Directory of JSON files, each one becoming a row
+
     txt - text lines
 
 from options:
@@ -81,7 +118,13 @@ from options:
                            work with.
 
 Excel/OpenOffice-related options:
-    -s, --sheet <name>     Name of the sheet to convert. [default: Sheet1]
+    -s, --sheet <sheet>    Name or zero-based index of the sheet to convert.
+                           Will default to the first sheet of the workbook.
+                           Date cells will be formatted as ISO 8601 strings.
+                           Note that only the top-left cell of merged cells
+                           will be filled, others remaining empty.
+    --list-sheets          Output the index and name of the sheets of the workbook
+                           instead of converting it.
 
 JSON options:
     --sample-size <n>      Number of records to sample before emitting headers.
@@ -91,6 +134,9 @@ JSON options:
     --value-column <name>  Name for the value column when parsing a JSON map.
                            [default: value]
 
+JSON directory options:
+    --recursive            Also read the JSON files found in subdirectories.
+
 Text lines options:
     -c, --column <name>    Name of the column to create.
                            [default: value]
@@ -103,13 +149,15 @@ Common options:
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
-    flag_sheet: String,
+    flag_sheet: Option<String>,
+    flag_list_sheets: bool,
     flag_format: Option<SupportedFormat>,
     flag_output: Option<String>,
     flag_sample_size: NonZeroUsize,
     flag_key_column: String,
     flag_value_column: String,
     flag_column: String,
+    flag_recursive: bool,
 }
 
 impl Args {
@@ -136,18 +184,46 @@ impl Args {
         let mut workbook = open_workbook_auto_from_rs(reader)?;
         let mut record = csv::StringRecord::new();
 
-        let range = workbook.worksheet_range(&self.flag_sheet);
+        let sheet_names = workbook.sheet_names();
 
-        match range {
-            Err(_) => {
-                let sheets = workbook.sheet_names().join(", ");
+        if self.flag_list_sheets {
+            wtr.write_record(["index", "name"])?;
 
+            for (i, name) in sheet_names.iter().enumerate() {
+                wtr.write_record([i.to_string().as_str(), name])?;
+            }
+
+            return Ok(wtr.flush()?);
+        }
+
+        // NOTE: a sheet name takes precedence over an index
+        let sheet_name = match &self.flag_sheet {
+            None => sheet_names.first().cloned(),
+            Some(sheet) => {
+                if sheet_names.contains(sheet) {
+                    Some(sheet.clone())
+                } else {
+                    sheet
+                        .parse::<usize>()
+                        .ok()
+                        .and_then(|i| sheet_names.get(i).cloned())
+                }
+            }
+        };
+
+        let range = sheet_name
+            .as_ref()
+            .map(|name| workbook.worksheet_range(name));
+
+        match range {
+            None | Some(Err(_)) => {
                 return Err(CliError::Other(format!(
                     "could not find the \"{}\" sheet\nshould be one of: {}",
-                    &self.flag_sheet, sheets
+                    self.flag_sheet.as_deref().unwrap_or("<first>"),
+                    sheet_names.join(", ")
                 )));
             }
-            Ok(range) => {
+            Some(Ok(range)) => {
                 for row in range.rows() {
                     record.clear();
 
@@ -161,7 +237,9 @@ impl Args {
                             }
                             Data::Int(value) => record.push_field(&value.to_string()),
                             Data::Float(value) => record.push_field(&value.to_string()),
-                            Data::DateTime(value) => record.push_field(&value.to_string()),
+                            Data::DateTime(value) => {
+                                record.push_field(&format_excel_datetime(value.as_f64()))
+                            }
                             Data::Error(err) => record.push_field(&err.to_string()),
                             Data::Empty => record.push_field(""),
                         }
@@ -243,6 +321,47 @@ impl Args {
         }
     }
 
+    fn convert_json_directory(&self) -> CliResult<()> {
+        let dir = match self.arg_input.as_ref() {
+            None => Err("json-dir format cannot work on stdin!")?,
+            Some(p) => p,
+        };
+
+        let pattern = Path::new(&Pattern::escape(dir)).join(if self.flag_recursive {
+            "**/*.json"
+        } else {
+            "*.json"
+        });
+
+        let mut values = Vec::new();
+
+        for entry in glob(&pattern.to_string_lossy())? {
+            let path = entry?;
+
+            let contents = fs::read_to_string(&path)?;
+
+            let value: Value = serde_json::from_str(&contents).map_err(|err| {
+                CliError::Other(format!("could not parse {}: {}", path.display(), err))
+            })?;
+
+            values.push(value);
+        }
+
+        let flattener = JSONFlattener::from_values_in_order(values.iter());
+
+        let mut wtr = self.writer()?;
+        wtr.write_record(&flattener.headers())?;
+
+        let mut record = csv::StringRecord::new();
+
+        for value in values.iter() {
+            flattener.fill_record(value, &mut record);
+            wtr.write_record(&record)?;
+        }
+
+        Ok(wtr.flush()?)
+    }
+
     fn convert_text_lines(&self) -> CliResult<()> {
         let rdr: Box<dyn BufRead> = match self.arg_input.as_ref() {
             None => Box::new(BufReader::new(io::stdin())),
@@ -294,10 +413,30 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         }
     };
 
+    if args.flag_list_sheets && !matches!(target_format, SupportedFormat::Xls) {
+        Err("--list-sheets only works with spreadsheet formats!")?;
+    }
+
     match target_format {
         SupportedFormat::Xls => args.convert_xls(),
         SupportedFormat::NdJSON => args.convert_ndjson(),
         SupportedFormat::JSONArray => args.convert_json_array(),
+        SupportedFormat::JSONDirectory => args.convert_json_directory(),
         SupportedFormat::Text => args.convert_text_lines(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_excel_datetime() {
+        assert_eq!(format_excel_datetime(25569.0), "1970-01-01");
+        assert_eq!(
+            format_excel_datetime(44484.7916666667),
+            "2021-10-15T19:00:00"
+        );
+        assert_eq!(format_excel_datetime(1.0), "1900-01-01");
+    }
+}
@@ -1,4 +1,8 @@
+use std::fs;
+use std::path::Path;
+
 use glob::glob;
+use jiff::Timestamp;
 
 use crate::config::Config;
 use crate::util;
@@ -8,12 +12,23 @@ static USAGE: &str = "
 Create a CSV file from the matches of a glob pattern. Matches will
 be stored in a \"path\" column.
 
+Using the --meta flag, you can also add columns containing the size
+in bytes of the matched files, their last modification time in ISO 8601
+format and their extension. Note that if metadata cannot be read for some
+path (e.g. a broken symlink), the relevant columns will be left empty.
+
+Example:
+
+    $ xan glob '**/*.jpg' --meta
+
 Usage:
     xan glob [options] <pattern>
     xan glob --help
 
 glob options:
     -a, --absolute         Yield absolute paths.
+    -m, --meta             Add \"size\", \"mtime\" & \"ext\" columns
+                           containing file metadata.
 
 Common options:
     -h, --help             Display this message
@@ -24,6 +39,7 @@ Common options:
 struct Args {
     arg_pattern: String,
     flag_absolute: bool,
+    flag_meta: bool,
     flag_output: Option<String>,
 }
 
@@ -34,6 +50,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut record = csv::ByteRecord::new();
     record.push_field(b"path");
 
+    if args.flag_meta {
+        record.push_field(b"size");
+        record.push_field(b"mtime");
+        record.push_field(b"ext");
+    }
+
     wtr.write_byte_record(&record)?;
 
     for entry in glob(&args.arg_pattern)? {
@@ -47,8 +69,41 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         record.clear();
         record.push_field(path.as_bytes());
 
+        if args.flag_meta {
+            push_metadata_fields(&mut record, &entry);
+        }
+
         wtr.write_byte_record(&record)?;
     }
 
     Ok(wtr.flush()?)
 }
+
+fn push_metadata_fields(record: &mut csv::ByteRecord, path: &Path) {
+    // NOTE: metadata can be missing, e.g. for broken symlinks
+    match fs::metadata(path) {
+        Ok(metadata) => {
+            record.push_field(metadata.len().to_string().as_bytes());
+            record.push_field(
+                metadata
+                    .modified()
+                    .ok()
+                    .and_then(|time| Timestamp::try_from(time).ok())
+                    .map(|timestamp| timestamp.to_string())
+                    .unwrap_or_default()
+                    .as_bytes(),
+            );
+        }
+        Err(_) => {
+            record.push_field(b"");
+            record.push_field(b"");
+        }
+    }
+
+    record.push_field(
+        path.extension()
+            .map(|ext| ext.to_string_lossy())
+            .unwrap_or_default()
+            .as_bytes(),
+    );
+}
@@ -1,6 +1,6 @@
 use std::io::Write;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -27,6 +27,14 @@ fn write_group(
     Ok(())
 }
 
+fn check_count_column<'a>(output_headers: impl Iterator<Item = &'a [u8]>) -> CliResult<()> {
+    if output_headers.filter(|h| *h == b"count").count() > 1 {
+        Err("-c, --with-count would produce a duplicate \"count\" column! Rename the other one using the 'as' syntax.")?;
+    }
+
+    Ok(())
+}
+
 static USAGE: &str = "
 Group a CSV file by values contained in a column selection then aggregate data per
 group using a custom aggregation expression.
@@ -54,6 +62,14 @@ You can group on multiple columns (read `xan select -h` for more information abo
 
     $ xan groupby name,surname 'sum(count)' file.csv
 
+If you only need to count the number of rows per group, the expression can be
+omitted when using the -c, --with-count flag:
+
+    $ xan groupby user_name --with-count file.csv
+
+Note that in this case, if you want to give an expression while reading from
+stdin, you will need to explicitly pass \"-\" as input.
+
 For a quick review of the capabilities of the script language, use
 the --cheatsheet flag.
 
@@ -62,6 +78,7 @@ For a list of available aggregation functions, use the --aggs flag.
 If you want to list available functions, use the --functions flag.
 
 Usage:
+    xan groupby [options] --with-count <column> [<input>]
     xan groupby [options] <column> <expression> [<input>]
     xan groupby --help
     xan groupby --cheatsheet
@@ -75,6 +92,9 @@ groupby options:
     -S, --sorted            Use this flag to indicate that the file is already sorted on the
                             group columns, in which case the command will be able to considerably
                             optimize memory usage.
+    -c, --with-count        Append a \"count\" column containing the number of rows
+                            in each group, after the other aggregations. Will
+                            error if another output column is already named \"count\".
     -e, --errors <policy>   What to do with evaluation errors. One of:
                               - \"panic\": exit on first error
                               - \"ignore\": ignore row altogether
@@ -91,21 +111,25 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_column: SelectColumns,
-    arg_expression: String,
+    arg_expression: Option<String>,
     arg_input: Option<String>,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_aggs: bool,
     flag_cheatsheet: bool,
     flag_functions: bool,
     flag_keep: Option<SelectColumns>,
     flag_sorted: bool,
+    flag_with_count: bool,
     flag_errors: String,
     flag_parallel: bool,
 }
@@ -132,6 +156,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_column);
 
@@ -141,6 +166,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let sel = rconf.selection(headers)?;
 
+    let mut expressions = Vec::new();
+
     // Lol, what a hack...
     if let Some(selection) = args.flag_keep.take() {
         let mut keep_sel = selection.selection(headers, !args.flag_no_headers)?;
@@ -161,16 +188,30 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             .join(", ");
 
         if !addendum.is_empty() {
-            args.arg_expression = addendum + ", " + &args.arg_expression;
+            expressions.push(addendum);
         }
     }
 
+    if let Some(expression) = args.arg_expression.take() {
+        expressions.push(expression);
+    }
+
+    if args.flag_with_count {
+        expressions.push("count() as count".to_string());
+    }
+
+    let expression = expressions.join(", ");
+
     let mut record = csv::ByteRecord::new();
 
     if args.flag_sorted {
-        let mut program = AggregationProgram::parse(&args.arg_expression, headers)?;
+        let mut program = AggregationProgram::parse(&expression, headers)?;
         let mut current: Option<Vec<Vec<u8>>> = None;
 
+        if args.flag_with_count && !args.flag_no_headers {
+            check_count_column(sel.select(headers).chain(program.headers()))?;
+        }
+
         write_group(
             &mut wtr,
             &sel.collect(headers),
@@ -215,7 +256,11 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             )?;
         }
     } else {
-        let mut program = GroupAggregationProgram::parse(&args.arg_expression, headers)?;
+        let mut program = GroupAggregationProgram::parse(&expression, headers)?;
+
+        if args.flag_with_count && !args.flag_no_headers {
+            check_count_column(sel.select(headers).chain(program.headers()))?;
+        }
 
         write_group(
             &mut wtr,
@@ -1,8 +1,9 @@
 use std::collections::BTreeMap;
+use std::io::Write;
 
 use colored::Colorize;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -19,12 +20,36 @@ fn find_duplicates(headers: &[String]) -> Vec<String> {
         .collect()
 }
 
+#[derive(Serialize)]
+struct JSONHeader<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a str>,
+    index: usize,
+    name: &'a str,
+}
+
 static USAGE: &str = "
 Print the headers of CSV files, with duplicated column names printed in yellow.
 
 When given multiple files, headers found across all files will be kept in white,
 while diverging headers will be printed in grey.
 
+When using the --diff flag with exactly two files, the command will instead
+report which header names can only be found in the first file, which can only
+be found in the second one, and which can be found in both, along with their
+respective positions when they differ. This can be useful to understand why
+some concatenation or join failed:
+
+    $ xan headers --diff a.csv b.csv
+
+When using the --json flag, the command will instead print a JSON array of
+objects containing the \"index\" and \"name\" of each column, so that the schema
+can be consumed by other tools. When given multiple files, each object will also
+contain the \"path\" of the file it belongs to.
+
+Note that header names that are not valid UTF-8 will be lossily decoded, and
+that a note will be printed to stderr if this is ever the case.
+
 Usage:
     xan headers [options] [<input>...]
     xan h [options] [<input>...]
@@ -32,13 +57,20 @@ Usage:
 headers options:
     -j, --just-names  Only show the header names (hide column index).
     --csv             Return headers as a CSV file, with file path as
-                      column names.
+                      column names. When used with --diff, return a CSV
+                      file with a row per column name, its status and
+                      its position in both files.
+    --diff            Compare the headers of two files.
+    --json            Return headers as a JSON array of objects with an
+                      \"index\" and a \"name\" key.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -46,28 +78,71 @@ struct Args {
     arg_input: Vec<String>,
     flag_just_names: bool,
     flag_csv: bool,
+    flag_diff: bool,
+    flag_json: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let configs = util::many_configs(&args.arg_input, args.flag_delimiter, true, None)?;
+    let configs = util::many_configs(
+        &args.arg_input,
+        args.flag_delimiter,
+        args.flag_comment,
+        true,
+        None,
+    )?;
 
     let mut headers_per_input: Vec<Vec<String>> = Vec::with_capacity(configs.len());
 
     let single_input = configs.len() == 1;
 
+    let mut lossy = false;
+
     for conf in configs.iter() {
         headers_per_input.push(
             conf.reader()?
-                .headers()?
+                .byte_headers()?
                 .iter()
-                .map(|h| h.to_string())
+                .map(|h| match std::str::from_utf8(h) {
+                    Ok(name) => name.to_string(),
+                    Err(_) => {
+                        lossy = true;
+                        String::from_utf8_lossy(h).into_owned()
+                    }
+                })
                 .collect(),
         );
     }
 
+    if lossy {
+        eprintln!("Some header names are not valid UTF-8 and were lossily decoded.");
+    }
+
+    if args.flag_json && (args.flag_csv || args.flag_diff) {
+        Err("--json cannot be used with --csv or --diff!")?;
+    }
+
+    if args.flag_diff {
+        if configs.len() != 2 {
+            Err("--diff expects exactly two files!")?;
+        }
+
+        let names = configs
+            .iter()
+            .map(|conf| {
+                conf.path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "<stdin>".to_string())
+            })
+            .collect::<Vec<_>>();
+
+        return diff_headers(&args, &names, &headers_per_input[0], &headers_per_input[1]);
+    }
+
     if args.flag_csv {
         let mut wtr = Config::new(&args.flag_output).writer()?;
 
@@ -100,6 +175,41 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         return Ok(wtr.flush()?);
     }
 
+    if args.flag_json {
+        let paths = configs
+            .iter()
+            .map(|conf| {
+                conf.path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "<stdin>".to_string())
+            })
+            .collect::<Vec<_>>();
+
+        let json_headers = headers_per_input
+            .iter()
+            .zip(paths.iter())
+            .flat_map(|(headers, path)| {
+                headers.iter().enumerate().map(|(index, name)| JSONHeader {
+                    path: if single_input {
+                        None
+                    } else {
+                        Some(path.as_str())
+                    },
+                    index,
+                    name,
+                })
+            })
+            .collect::<Vec<_>>();
+
+        let mut writer = Config::new(&args.flag_output).io_writer()?;
+
+        serde_json::to_writer_pretty(&mut writer, &json_headers)?;
+        writeln!(&mut writer)?;
+
+        return Ok(writer.flush()?);
+    }
+
     let mut name_counts = BTreeMap::<String, usize>::new();
 
     for headers in headers_per_input.iter() {
@@ -166,3 +276,112 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     Ok(())
 }
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum HeaderDiffStatus {
+    OnlyLeft,
+    OnlyRight,
+    Both,
+}
+
+impl HeaderDiffStatus {
+    fn as_str(&self) -> &str {
+        match self {
+            Self::OnlyLeft => "left",
+            Self::OnlyRight => "right",
+            Self::Both => "both",
+        }
+    }
+}
+
+// NOTE: duplicated names are matched by order of appearance.
+fn compute_header_diff(
+    left: &[String],
+    right: &[String],
+) -> Vec<(String, HeaderDiffStatus, Option<usize>, Option<usize>)> {
+    let mut used = vec![false; right.len()];
+    let mut diff = Vec::new();
+
+    for (i, name) in left.iter().enumerate() {
+        let found = right
+            .iter()
+            .enumerate()
+            .position(|(j, h)| !used[j] && h == name);
+
+        match found {
+            Some(j) => {
+                used[j] = true;
+                diff.push((name.clone(), HeaderDiffStatus::Both, Some(i), Some(j)));
+            }
+            None => diff.push((name.clone(), HeaderDiffStatus::OnlyLeft, Some(i), None)),
+        }
+    }
+
+    for (j, name) in right.iter().enumerate() {
+        if !used[j] {
+            diff.push((name.clone(), HeaderDiffStatus::OnlyRight, None, Some(j)));
+        }
+    }
+
+    diff
+}
+
+fn diff_headers(args: &Args, names: &[String], left: &[String], right: &[String]) -> CliResult<()> {
+    let diff = compute_header_diff(left, right);
+
+    if args.flag_csv {
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+
+        wtr.write_record(["column", "status", "left_index", "right_index"])?;
+
+        for (name, status, i, j) in diff.iter() {
+            let i = i.map(|i| i.to_string()).unwrap_or_default();
+            let j = j.map(|j| j.to_string()).unwrap_or_default();
+
+            wtr.write_record([name.as_str(), status.as_str(), i.as_str(), j.as_str()])?;
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    let print_section = |title: String, status: HeaderDiffStatus| {
+        let items = diff
+            .iter()
+            .filter(|(_, s, _, _)| *s == status)
+            .collect::<Vec<_>>();
+
+        if items.is_empty() {
+            return;
+        }
+
+        println!("{}", title.cyan());
+
+        for (name, _, i, j) in items {
+            let position = match (i, j) {
+                (Some(i), Some(j)) if i != j => format!("{} -> {}", i, j).yellow(),
+                (Some(i), _) | (None, Some(i)) => i.to_string().normal(),
+                _ => unreachable!(),
+            };
+
+            println!("  {} ({})", name, position);
+        }
+
+        println!();
+    };
+
+    print_section(format!("Only in {}:", names[0]), HeaderDiffStatus::OnlyLeft);
+    print_section(
+        format!("Only in {}:", names[1]),
+        HeaderDiffStatus::OnlyRight,
+    );
+    print_section("In both:".to_string(), HeaderDiffStatus::Both);
+
+    if diff
+        .iter()
+        .all(|(_, s, i, j)| *s == HeaderDiffStatus::Both && i == j)
+    {
+        println!("{}", "Both files have the same headers!".green());
+    }
+
+    Ok(())
+}
@@ -5,7 +5,7 @@ use numfmt::{Formatter, Precision};
 use serde::de::{Deserialize, Deserializer, Error};
 use unicode_width::UnicodeWidthStr;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::scales::{Extent, ExtentBuilder, GradientName, LinearScale};
 use crate::util;
 use crate::CliResult;
@@ -179,6 +179,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -195,6 +197,7 @@ struct Args {
     flag_force_colors: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_show_gradients: bool,
     flag_green_hills: bool,
 }
@@ -238,6 +241,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
     if args.flag_force_colors {
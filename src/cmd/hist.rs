@@ -5,7 +5,7 @@ use indexmap::{map::Entry, IndexMap};
 use jiff::{civil::Date, Unit};
 use unicode_width::UnicodeWidthStr;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::dates;
 use crate::scales::LinearScale;
 use crate::select::SelectColumns;
@@ -67,6 +67,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -74,6 +76,7 @@ struct Args {
     arg_input: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_field: SelectColumns,
     flag_label: SelectColumns,
     flag_value: SelectColumns,
@@ -93,6 +96,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
     if args.flag_force_colors {
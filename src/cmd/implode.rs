@@ -1,4 +1,6 @@
-use crate::config::{Config, Delimiter};
+use std::collections::HashSet;
+
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::{SelectColumns, Selection};
 use crate::util;
 use crate::CliResult;
@@ -8,7 +10,10 @@ Implode a CSV file by merging multiple consecutive rows into a single one, where
 diverging cells will be joined by the pipe character (\"|\") or any separator
 given to the --sep flag.
 
-This is the inverse of the \"explode\" command.
+This is the inverse of the \"explode\" command, meaning that imploding an
+exploded column back using the same separator will reproduce the original file.
+Note that empty cells are kept as empty values when joining, which is
+consistent with how \"explode\" handles them.
 
 For instance the following CSV:
 
@@ -29,6 +34,11 @@ name,color
 John,blue|yellow
 Mary,red
 
+Note that the file can be imploded on multiple columns at once, in which case
+their values will be accumulated in lockstep. Finally, the -U, --unique flag
+can be used to drop duplicate values (or duplicate combinations of values when
+imploding multiple columns) from the imploded cells.
+
 Usage:
     xan implode [options] <columns> [<input>]
     xan implode --help
@@ -36,6 +46,9 @@ Usage:
 implode options:
     --sep <sep>          Separator that will be used to join the diverging cells.
                          [default: |]
+    -U, --unique         Only keep the first occurrence of each value (or
+                         combination of values when imploding multiple columns)
+                         when merging rows.
     -P, --plural         Adding a final \"s\" to the imploded column names.
                          Does not work with -r, --rename.
     -r, --rename <name>  New name for the diverging column.
@@ -54,6 +67,8 @@ Common options:
                            as headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -61,12 +76,14 @@ struct Args {
     arg_columns: SelectColumns,
     arg_input: Option<String>,
     flag_sep: String,
+    flag_unique: bool,
     flag_plural: bool,
     flag_rename: Option<String>,
     flag_cmp: Option<SelectColumns>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 fn compare_but_for_sel(
@@ -94,8 +111,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("-P/--plural cannot work with -r/--rename!")?;
     }
 
+    if args.flag_sep.is_empty() {
+        Err("--sep cannot be empty!")?;
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_columns);
 
@@ -152,6 +174,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut previous: Option<csv::ByteRecord> = None;
     let mut accumulator: Vec<Vec<Vec<u8>>> = Vec::with_capacity(sel.len());
 
+    // NOTE: the set is only used to deduplicate values with -U, --unique,
+    // while the accumulator keeps their original order.
+    let mut seen: HashSet<Vec<Vec<u8>>> = HashSet::new();
+
     for result in rdr.into_byte_records() {
         let record = result?;
 
@@ -183,10 +209,19 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 wtr.write_byte_record(&imploded_record)?;
 
                 accumulator.clear();
+                seen.clear();
             }
         }
 
-        accumulator.push(sel.select(&record).map(|c| c.to_vec()).collect());
+        let values: Vec<Vec<u8>> = sel.select(&record).map(|c| c.to_vec()).collect();
+
+        if !args.flag_unique {
+            accumulator.push(values);
+        } else if !seen.contains(&values) {
+            seen.insert(values.clone());
+            accumulator.push(values);
+        }
+
         previous = Some(record);
     }
 
@@ -1,10 +1,12 @@
 use std::fs;
-use std::io;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 
 use csv_index::RandomAccessSimple;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
+use crate::index::create_key_index;
+use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
@@ -18,6 +20,12 @@ automatically used by commands that can benefit from it. If the original CSV
 data changes after the index is made, commands that try to use it will result
 in an error (you have to regenerate the index before it can be used again).
 
+When given --key, the command will additionally create a key index at
+'path/to/input.csv.kidx', mapping the values of the given column(s) to the
+positions of the rows having them. This key index is stored on disk as a
+sorted table that can be binary searched, so that commands such as
+`xan lookup` can fetch rows by key without reading the whole file.
+
 Usage:
     xan index [options] <input>
     xan index --help
@@ -27,18 +35,24 @@ index options:
                            Generally, this is not currently useful because
                            the only way to use an index is if it is specially
                            named <input>.idx.
+    -k, --key <column>     Also create a key index for the given column(s),
+                           written to <input>.kidx.
 
 Common options:
     -h, --help             Display this message
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: String,
     flag_output: Option<String>,
+    flag_key: Option<SelectColumns>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -49,9 +63,24 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Some(p) => PathBuf::from(&p),
     };
 
-    let rconfig = Config::new(&Some(args.arg_input)).delimiter(args.flag_delimiter);
+    let rconfig = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment);
     let mut rdr = rconfig.reader_file()?;
     let mut wtr = io::BufWriter::new(fs::File::create(pidx)?);
     RandomAccessSimple::create(&mut rdr, &mut wtr)?;
+    wtr.flush()?;
+
+    if let Some(key) = args.flag_key {
+        let rconfig = rconfig.select(key);
+        let mut rdr = rconfig.reader_file()?;
+        let sel = rconfig.selection(rdr.byte_headers()?)?;
+
+        let kidx = util::key_idx_path(Path::new(&args.arg_input));
+        let mut wtr = io::BufWriter::new(fs::File::create(kidx)?);
+        create_key_index(&mut rdr, &sel, &mut wtr)?;
+        wtr.flush()?;
+    }
+
     Ok(())
 }
@@ -1,4 +1,6 @@
-use crate::config::{Config, Delimiter};
+use std::io;
+
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -10,6 +12,11 @@ used in CSV data. This does not cover all possible types of CSV data. For
 example, some CSV files don't use '\"' for quotes or use different escaping
 styles.
 
+This command can also be used to rescue broken CSV data using the --repair
+flag. In this case, rows having less fields than the first one will be padded
+with empty cells, while rows having more fields will be dropped. The
+number of padded & dropped rows will be reported on stderr.
+
 Usage:
     xan input [options] [<input>]
 
@@ -18,12 +25,16 @@ input options:
     --escape <arg>         The escape character to use. When not specified,
                            quotes are escaped by doubling them.
     --no-quoting           Disable quoting completely.
+    --repair               Read the file permissively and re-emit rows with a
+                           consistent number of fields.
 
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -31,15 +42,18 @@ struct Args {
     arg_input: Option<String>,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_quote: Delimiter,
     flag_escape: Option<Delimiter>,
     flag_no_quoting: bool,
+    flag_repair: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
     let mut rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(true)
         .quote(args.flag_quote.as_byte());
     let wconfig = Config::new(&args.flag_output);
@@ -51,12 +65,56 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         rconfig = rconfig.quoting(false);
     }
 
+    if args.flag_repair {
+        rconfig = rconfig.flexible(true);
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = wconfig.writer()?;
     let mut row = csv::ByteRecord::new();
+
+    if args.flag_repair {
+        return repair(&mut rdr, &mut wtr);
+    }
+
     while rdr.read_byte_record(&mut row)? {
         wtr.write_record(&row)?;
     }
     wtr.flush()?;
     Ok(())
 }
+
+fn repair<R: io::Read, W: io::Write>(
+    rdr: &mut csv::Reader<R>,
+    wtr: &mut csv::Writer<W>,
+) -> CliResult<()> {
+    let mut row = csv::ByteRecord::new();
+    let mut expected_len: Option<usize> = None;
+    let mut padded: usize = 0;
+    let mut dropped: usize = 0;
+
+    while rdr.read_byte_record(&mut row)? {
+        let len = *expected_len.get_or_insert(row.len());
+
+        if row.len() > len {
+            dropped += 1;
+            continue;
+        }
+
+        if row.len() < len {
+            padded += 1;
+
+            for _ in row.len()..len {
+                row.push_field(b"");
+            }
+        }
+
+        wtr.write_byte_record(&row)?;
+    }
+
+    wtr.flush()?;
+
+    eprintln!("Padded {} rows, dropped {} rows.", padded, dropped);
+
+    Ok(())
+}
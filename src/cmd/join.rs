@@ -1,3 +1,4 @@
+use std::cmp::Ordering;
 use std::collections::hash_map::{Entry, HashMap};
 use std::io;
 use std::num::NonZeroUsize;
@@ -5,27 +6,43 @@ use std::num::NonZeroUsize;
 use bstr::ByteSlice;
 use csv::ByteRecord;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::{SelectColumns, Selection};
 use crate::util;
 use crate::CliResult;
 
 type IndexKey = Vec<Vec<u8>>;
 
-fn get_row_key(sel: &Selection, row: &ByteRecord, case_insensitive: bool) -> IndexKey {
-    sel.select(row)
-        .map(|v| transform(v, case_insensitive))
-        .collect()
+const BOTH: &[u8] = b"both";
+const LEFT_ONLY: &[u8] = b"left_only";
+const RIGHT_ONLY: &[u8] = b"right_only";
+
+// NOTE: normalization only applies to the keys used for matching, emitted
+// cells are left untouched.
+#[derive(Debug, Default, Clone, Copy)]
+struct KeyNormalization {
+    ignore_case: bool,
+    trim: bool,
 }
 
-fn transform(bs: &[u8], case_insensitive: bool) -> Vec<u8> {
-    if !case_insensitive {
-        bs.to_vec()
-    } else {
-        bs.to_lowercase()
+impl KeyNormalization {
+    fn normalize(&self, bs: &[u8]) -> Vec<u8> {
+        let bs = if self.trim { bs.trim() } else { bs };
+
+        if self.ignore_case {
+            bs.to_lowercase()
+        } else {
+            bs.to_vec()
+        }
     }
 }
 
+fn get_row_key(sel: &Selection, row: &ByteRecord, normalization: KeyNormalization) -> IndexKey {
+    sel.select(row)
+        .map(|v| normalization.normalize(v))
+        .collect()
+}
+
 fn build_headers(
     left_headers: &ByteRecord,
     right_headers: &ByteRecord,
@@ -80,16 +97,16 @@ impl IndexNode {
 // for the user and not aligned with usual affordances.
 #[derive(Debug)]
 struct Index {
-    case_insensitive: bool,
+    normalization: KeyNormalization,
     nulls: bool,
     map: HashMap<IndexKey, (usize, usize)>,
     nodes: Vec<IndexNode>,
 }
 
 impl Index {
-    fn new(case_insensitive: bool, nulls: bool) -> Self {
+    fn new(normalization: KeyNormalization, nulls: bool) -> Self {
         Self {
-            case_insensitive,
+            normalization,
             nulls,
             map: HashMap::new(),
             nodes: Vec::new(),
@@ -99,10 +116,10 @@ impl Index {
     fn from_csv_reader<R: io::Read>(
         reader: &mut csv::Reader<R>,
         sel: &Selection,
-        case_insensitive: bool,
+        normalization: KeyNormalization,
         nulls: bool,
     ) -> CliResult<Self> {
-        let mut index = Index::new(case_insensitive, nulls);
+        let mut index = Index::new(normalization, nulls);
 
         for result in reader.byte_records() {
             let record = result?;
@@ -114,7 +131,7 @@ impl Index {
     }
 
     fn add(&mut self, sel: &Selection, record: ByteRecord) {
-        let key = get_row_key(sel, &record, self.case_insensitive);
+        let key = get_row_key(sel, &record, self.normalization);
 
         if !self.nulls && key.iter().all(|c| c.is_empty()) {
             return;
@@ -146,7 +163,7 @@ impl Index {
     where
         F: FnMut(&mut IndexNode) -> Result<(), E>,
     {
-        let key = get_row_key(sel, record, self.case_insensitive);
+        let key = get_row_key(sel, record, self.normalization);
 
         if !self.nulls && key.iter().all(|c| c.is_empty()) {
             return Ok(());
@@ -189,6 +206,214 @@ impl Index {
     }
 }
 
+// NOTE: bounds are compared numerically when they can be parsed as numbers,
+// and lexicographically otherwise, which works fine with ISO 8601 dates.
+#[derive(Debug, Clone)]
+enum IntervalBound {
+    Number(f64),
+    Raw(Vec<u8>),
+}
+
+impl IntervalBound {
+    fn parse(cell: &[u8]) -> Option<Self> {
+        if cell.is_empty() {
+            return None;
+        }
+
+        match std::str::from_utf8(cell)
+            .ok()
+            .and_then(|s| s.parse::<f64>().ok())
+        {
+            Some(f) if !f.is_nan() => Some(Self::Number(f)),
+            _ => Some(Self::Raw(cell.to_vec())),
+        }
+    }
+}
+
+impl Ord for IntervalBound {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Raw(a), Self::Raw(b)) => a.cmp(b),
+            (Self::Number(_), Self::Raw(_)) => Ordering::Less,
+            (Self::Raw(_), Self::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+impl PartialOrd for IntervalBound {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for IntervalBound {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for IntervalBound {}
+
+// NOTE: a missing start or end means the interval is unbounded on this side.
+#[derive(Debug)]
+struct Interval {
+    start: Option<IntervalBound>,
+    end: Option<IntervalBound>,
+    record: ByteRecord,
+}
+
+// NOTE: intervals are sorted by start, and we keep the running maximum of
+// their ends so that we can stop looking for overlapping intervals as soon
+// as possible when searching backwards from the last eligible start.
+#[derive(Debug)]
+struct IntervalIndex {
+    intervals: Vec<Interval>,
+    max_ends: Vec<Option<IntervalBound>>,
+}
+
+impl IntervalIndex {
+    fn from_csv_reader<R: io::Read>(
+        reader: &mut csv::Reader<R>,
+        sel: &Selection,
+    ) -> CliResult<Self> {
+        let mut intervals = Vec::new();
+
+        for result in reader.byte_records() {
+            let record = result?;
+
+            let (start, end) = {
+                let mut bounds = sel.select(&record).map(IntervalBound::parse);
+                (bounds.next().unwrap(), bounds.next().unwrap())
+            };
+
+            intervals.push(Interval { start, end, record });
+        }
+
+        intervals.sort_by(|a, b| a.start.cmp(&b.start));
+
+        let mut max_ends = Vec::with_capacity(intervals.len());
+        let mut current_max_end: Option<Option<IntervalBound>> = None;
+
+        for interval in intervals.iter() {
+            let max_end = match (current_max_end, &interval.end) {
+                (None, end) => end.clone(),
+                (Some(None), _) | (_, None) => None,
+                (Some(Some(current)), Some(end)) => Some(current.max(end.clone())),
+            };
+
+            max_ends.push(max_end.clone());
+            current_max_end = Some(max_end);
+        }
+
+        Ok(Self {
+            intervals,
+            max_ends,
+        })
+    }
+
+    fn matches(&self, cell: &[u8]) -> Vec<&ByteRecord> {
+        let value = match IntervalBound::parse(cell) {
+            None => return vec![],
+            Some(value) => value,
+        };
+
+        let upper = self
+            .intervals
+            .partition_point(|interval| match &interval.start {
+                None => true,
+                Some(start) => *start <= value,
+            });
+
+        let mut matches = Vec::new();
+
+        for i in (0..upper).rev() {
+            if matches!(&self.max_ends[i], Some(max_end) if *max_end < value) {
+                break;
+            }
+
+            let interval = &self.intervals[i];
+
+            if matches!(&interval.end, Some(end) if *end < value) {
+                continue;
+            }
+
+            matches.push(&interval.record);
+        }
+
+        matches.reverse();
+        matches
+    }
+}
+
+// NOTE: this reader yields groups of consecutive records sharing a same key,
+// all while checking that keys are indeed sorted.
+struct SortedGroupReader {
+    reader: BoxedReader,
+    sel: Selection,
+    normalization: KeyNormalization,
+    name: &'static str,
+    next: Option<(IndexKey, ByteRecord)>,
+}
+
+impl SortedGroupReader {
+    fn new(
+        reader: BoxedReader,
+        sel: Selection,
+        normalization: KeyNormalization,
+        name: &'static str,
+    ) -> CliResult<Self> {
+        let mut group_reader = Self {
+            reader,
+            sel,
+            normalization,
+            name,
+            next: None,
+        };
+
+        group_reader.next = group_reader.read()?;
+
+        Ok(group_reader)
+    }
+
+    fn read(&mut self) -> CliResult<Option<(IndexKey, ByteRecord)>> {
+        let mut record = ByteRecord::new();
+
+        if !self.reader.read_byte_record(&mut record)? {
+            return Ok(None);
+        }
+
+        let key = get_row_key(&self.sel, &record, self.normalization);
+
+        Ok(Some((key, record)))
+    }
+
+    fn next_group(&mut self) -> CliResult<Option<(IndexKey, Vec<ByteRecord>)>> {
+        let (key, record) = match self.next.take() {
+            None => return Ok(None),
+            Some(next) => next,
+        };
+
+        let mut records = vec![record];
+
+        while let Some((next_key, next_record)) = self.read()? {
+            match next_key.cmp(&key) {
+                Ordering::Equal => records.push(next_record),
+                Ordering::Greater => {
+                    self.next = Some((next_key, next_record));
+                    break;
+                }
+                Ordering::Less => Err(format!(
+                    "{} file is not sorted on the joined columns!",
+                    self.name
+                ))?,
+            }
+        }
+
+        Ok(Some((key, records)))
+    }
+}
+
 static USAGE: &str = "
 Join two sets of CSV data on the specified columns.
 
@@ -199,7 +424,8 @@ a full outer join with --full and finally a cartesian product/cross join
 with --cross.
 
 By default, joins are done case sensitively, but this can be disabled using
-the -i, --ignore-case flag.
+the -i, --ignore-case flag. Leading and trailing whitespace can also be ignored
+when matching keys using the --trim flag.
 
 The column arguments specify the columns to join for each input. Columns can
 be selected using the same syntax as the \"xan select\" command. Both selections
@@ -228,6 +454,37 @@ gzipped files out of the box.
                     file is streamed. Prefer placing the smaller file
                     on the left.
 
+When both files are already sorted on their joined columns, the --sorted flag
+can be used to perform a merge join instead, streaming both files at once
+and only keeping in memory the records sharing the current key. Keys are
+compared lexicographically, i.e. files must be sorted as they would be by
+`xan sort -s`, without -N or -C (and on normalized keys if using -i, --ignore-case
+or --trim). The command will stop with an error if it finds out that some file
+is not properly sorted. Note that, as with the other joins, when a key appears multiple
+times on both sides, the full cross product of the matching rows will be
+emitted. The --sorted flag works with inner, left, right and full joins:
+
+    $ xan join --sorted --full id left.csv id right.csv
+
+The --interval flag can be used to perform a non-equi join instead, where each
+row of the first file will be matched with the rows of the second file whose
+interval contains its value. In this case, a single column must be selected
+in the first file, and two columns, representing the start & end of the
+intervals (both inclusive), must be selected in the second file. An empty start
+or end means the interval is unbounded on this side. Values are compared
+numerically if they can be parsed as numbers, and lexicographically otherwise,
+which means ISO 8601 dates will also work. Only inner and left joins are
+supported, and the second file will be held in memory. For instance, to
+match some events with the time periods they belong to:
+
+    $ xan join --interval ts events.csv start,end periods.csv
+
+When the second file is very large and has been indexed beforehand on the
+joined columns using `xan index --key`, the --index flag can be used with inner
+and left joins so that its matching rows are found by searching the key index
+on disk, then read back from the file, instead of being held in memory. This
+keeps memory usage low at the cost of throughput (a few disk seeks per row).
+
 Usage:
     xan join [options] <columns1> <input1> <columns2> <input2>
     xan join [options] --cross <input1> <input2>
@@ -252,7 +509,23 @@ join options:
                                  files. The number of rows emitted will be equal to N * M,
                                  where N and M correspond to the number of rows in the given
                                  data sets, respectively.
-    -i, --ignore-case            When set, joins are done case insensitively.
+    --sorted                     Perform a merge join, streaming both files, which
+                                 must already be sorted on the joined columns.
+                                 Only works with inner, left, right & full joins.
+    --interval                   Join rows of the first file with the rows of the
+                                 second one whose interval, represented by two
+                                 columns, contains their value. Only works with
+                                 inner and left joins, and does not support the
+                                 ignore case, trim and nulls options.
+    --index                      Use the key index of the second file, as created
+                                 by `xan index --key`, to find its matching rows
+                                 on disk instead of holding them in memory. Only
+                                 works with inner and left joins, and does not
+                                 support the ignore case and trim options.
+    -i, --ignore-case            When set, joins are done case insensitively, using
+                                 Unicode lowercasing.
+    --trim                       When set, leading and trailing whitespace will be
+                                 ignored when matching keys.
     --nulls                      When set, joins will work on empty fields.
                                  Otherwise, empty keys are completely ignored, i.e. when
                                  column selection yield only empty cells.
@@ -260,6 +533,11 @@ join options:
                                  first dataset.
     -R, --prefix-right <prefix>  Add a prefix to the names of the columns in the
                                  second dataset.
+    --indicator <name>           Add a column with given name indicating whether
+                                 each row was matched in \"both\" files, or was only
+                                 found in the left file (\"left_only\") or in the
+                                 right one (\"right_only\"). Only works with outer
+                                 joins, i.e. --left, --right & --full.
 
 Common options:
     -h, --help                  Display this message
@@ -269,6 +547,8 @@ Common options:
                                 sliced, etc.)
     -d, --delimiter <arg>       The field delimiter for reading CSV data.
                                 Must be a single character.
+    --comment <char>            Skip lines starting with <char> when reading CSV
+                                data.
 ";
 
 #[derive(Deserialize)]
@@ -281,31 +561,45 @@ struct Args {
     flag_right: bool,
     flag_full: bool,
     flag_cross: bool,
+    flag_sorted: bool,
+    flag_interval: bool,
+    flag_index: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_ignore_case: bool,
+    flag_trim: bool,
     flag_nulls: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_prefix_left: Option<String>,
     flag_prefix_right: Option<String>,
+    flag_indicator: Option<String>,
 }
 
 type BoxedReader = csv::Reader<Box<dyn io::Read + Send>>;
 
 impl Args {
-    fn readers_and_selections(
-        &self,
-    ) -> CliResult<((BoxedReader, Selection), (BoxedReader, Selection))> {
+    fn configs(&self) -> (Config, Config) {
         let left = Config::new(&Some(self.arg_input1.clone()))
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
             .select(self.arg_columns1.clone());
 
         let right = Config::new(&Some(self.arg_input2.clone()))
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
             .select(self.arg_columns2.clone());
 
+        (left, right)
+    }
+
+    fn readers_and_selections(
+        &self,
+    ) -> CliResult<((BoxedReader, Selection), (BoxedReader, Selection))> {
+        let (left, right) = self.configs();
+
         let mut left_reader = left.reader()?;
         let mut right_reader = right.reader()?;
 
@@ -323,8 +617,15 @@ impl Args {
         Config::new(&self.flag_output)
     }
 
+    fn normalization(&self) -> KeyNormalization {
+        KeyNormalization {
+            ignore_case: self.flag_ignore_case,
+            trim: self.flag_trim,
+        }
+    }
+
     fn index(&self, reader: &mut BoxedReader, sel: &Selection) -> CliResult<Index> {
-        Index::from_csv_reader(reader, sel, self.flag_ignore_case, self.flag_nulls)
+        Index::from_csv_reader(reader, sel, self.normalization(), self.flag_nulls)
     }
 
     fn write_headers<W: io::Write>(
@@ -334,17 +635,27 @@ impl Args {
         right_headers: &ByteRecord,
     ) -> CliResult<()> {
         if !self.flag_no_headers {
-            writer.write_byte_record(&build_headers(
+            let mut headers = build_headers(
                 left_headers,
                 right_headers,
                 &self.flag_prefix_left,
                 &self.flag_prefix_right,
-            ))?;
+            );
+
+            if let Some(name) = &self.flag_indicator {
+                headers.push_field(name.as_bytes());
+            }
+
+            writer.write_byte_record(&headers)?;
         }
 
         Ok(())
     }
 
+    fn indicator(&self, status: &'static [u8]) -> Option<&'static [u8]> {
+        self.flag_indicator.as_ref().map(|_| status)
+    }
+
     fn inner_join(self) -> CliResult<()> {
         let ((mut left_reader, left_sel), (mut right_reader, right_sel)) =
             self.readers_and_selections()?;
@@ -394,16 +705,32 @@ impl Args {
             index.for_each_node_mut(&right_sel, &right_record, |left_node| {
                 something_was_written = true;
                 left_node.written = true;
-                writer.write_record(left_node.record.iter().chain(right_record.iter()))
+                writer.write_record(
+                    left_node
+                        .record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(BOTH)),
+                )
             })?;
 
             if !something_was_written {
-                writer.write_record(left_padding.iter().chain(right_record.iter()))?;
+                writer.write_record(
+                    left_padding
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(RIGHT_ONLY)),
+                )?;
             }
         }
 
         for left_record in index.records_not_written() {
-            writer.write_record(left_record.iter().chain(right_padding.iter()))?;
+            writer.write_record(
+                left_record
+                    .iter()
+                    .chain(right_padding.iter())
+                    .chain(self.indicator(LEFT_ONLY)),
+            )?;
         }
 
         Ok(writer.flush()?)
@@ -431,11 +758,21 @@ impl Args {
 
             index.for_each_record(&left_sel, &left_record, |right_record| {
                 something_was_written = true;
-                writer.write_record(left_record.iter().chain(right_record.iter()))
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(BOTH)),
+                )
             })?;
 
             if !something_was_written {
-                writer.write_record(left_record.iter().chain(right_padding.iter()))?;
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_padding.iter())
+                        .chain(self.indicator(LEFT_ONLY)),
+                )?;
             }
         }
 
@@ -464,11 +801,255 @@ impl Args {
 
             index.for_each_record(&right_sel, &right_record, |left_record| {
                 something_was_written = true;
-                writer.write_record(left_record.iter().chain(right_record.iter()))
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(BOTH)),
+                )
             })?;
 
             if !something_was_written {
-                writer.write_record(left_padding.iter().chain(right_record.iter()))?;
+                writer.write_record(
+                    left_padding
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(RIGHT_ONLY)),
+                )?;
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn sorted_join(self) -> CliResult<()> {
+        let ((mut left_reader, left_sel), (mut right_reader, right_sel)) =
+            self.readers_and_selections()?;
+
+        let mut writer = self.wconf().writer()?;
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
+
+        let left_padding = get_padding(&left_headers);
+        let right_padding = get_padding(&right_headers);
+
+        self.write_headers(&mut writer, &left_headers, &right_headers)?;
+
+        let keep_left = self.flag_left || self.flag_full;
+        let keep_right = self.flag_right || self.flag_full;
+
+        let mut left_groups =
+            SortedGroupReader::new(left_reader, left_sel, self.normalization(), "left")?;
+        let mut right_groups =
+            SortedGroupReader::new(right_reader, right_sel, self.normalization(), "right")?;
+
+        let mut left_group = left_groups.next_group()?;
+        let mut right_group = right_groups.next_group()?;
+
+        loop {
+            let ordering = match (&left_group, &right_group) {
+                (None, None) => break,
+                (Some(_), None) if !keep_left => break,
+                (None, Some(_)) if !keep_right => break,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some((left_key, _)), Some((right_key, _))) => {
+                    match left_key.cmp(right_key) {
+                        // NOTE: empty keys sort first and never match, so we
+                        // can just consider them as lesser on the left.
+                        Ordering::Equal
+                            if !self.flag_nulls && left_key.iter().all(|c| c.is_empty()) =>
+                        {
+                            Ordering::Less
+                        }
+                        ordering => ordering,
+                    }
+                }
+            };
+
+            match ordering {
+                Ordering::Less => {
+                    if keep_left {
+                        for left_record in left_group.as_ref().unwrap().1.iter() {
+                            writer.write_record(
+                                left_record
+                                    .iter()
+                                    .chain(right_padding.iter())
+                                    .chain(self.indicator(LEFT_ONLY)),
+                            )?;
+                        }
+                    }
+
+                    left_group = left_groups.next_group()?;
+                }
+                Ordering::Greater => {
+                    if keep_right {
+                        for right_record in right_group.as_ref().unwrap().1.iter() {
+                            writer.write_record(
+                                left_padding
+                                    .iter()
+                                    .chain(right_record.iter())
+                                    .chain(self.indicator(RIGHT_ONLY)),
+                            )?;
+                        }
+                    }
+
+                    right_group = right_groups.next_group()?;
+                }
+                Ordering::Equal => {
+                    for left_record in left_group.as_ref().unwrap().1.iter() {
+                        for right_record in right_group.as_ref().unwrap().1.iter() {
+                            writer.write_record(
+                                left_record
+                                    .iter()
+                                    .chain(right_record.iter())
+                                    .chain(self.indicator(BOTH)),
+                            )?;
+                        }
+                    }
+
+                    left_group = left_groups.next_group()?;
+                    right_group = right_groups.next_group()?;
+                }
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn indexed_join(self) -> CliResult<()> {
+        let (left, right) = self.configs();
+
+        let mut left_reader = left.reader()?;
+
+        let mut right_key_index = match right.key_indexed()? {
+            Some(key_index) => key_index,
+            None => Err(format!(
+                "could not find a key index for {}. Please run `xan index --key` on it first!",
+                self.arg_input2
+            ))?,
+        };
+
+        let mut right_indexed = match right.indexed()? {
+            Some(indexed) => indexed,
+            None => Err(format!(
+                "could not find an index for {}. Please run `xan index --key` on it first!",
+                self.arg_input2
+            ))?,
+        };
+
+        let left_sel = left.selection(left_reader.byte_headers()?)?;
+        let right_sel = right.selection(right_indexed.byte_headers()?)?;
+
+        if left_sel.len() != right_sel.len() {
+            Err("not the same number of columns selected on left & right!")?;
+        }
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_indexed.byte_headers()?.clone();
+
+        if right_sel
+            .select(&right_headers)
+            .ne(right_key_index.headers().iter().map(|h| h.as_slice()))
+        {
+            Err(format!(
+                "the key index of {} was not built on the selected column(s)!",
+                self.arg_input2
+            ))?;
+        }
+
+        let mut writer = self.wconf().writer()?;
+
+        let right_padding = get_padding(&right_headers);
+
+        self.write_headers(&mut writer, &left_headers, &right_headers)?;
+
+        let mut left_record = csv::ByteRecord::new();
+        let mut right_record = csv::ByteRecord::new();
+
+        while left_reader.read_byte_record(&mut left_record)? {
+            let key = left_sel.collect(&left_record);
+
+            let positions = if !self.flag_nulls && key.iter().all(|c| c.is_empty()) {
+                Vec::new()
+            } else {
+                right_key_index.get(&key)?
+            };
+
+            for position in positions.iter() {
+                right_indexed.seek(*position)?;
+                right_indexed.read_byte_record(&mut right_record)?;
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(BOTH)),
+                )?;
+            }
+
+            if positions.is_empty() && self.flag_left {
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_padding.iter())
+                        .chain(self.indicator(LEFT_ONLY)),
+                )?;
+            }
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    fn interval_join(self) -> CliResult<()> {
+        let (left, right) = self.configs();
+
+        let mut left_reader = left.reader()?;
+        let mut right_reader = right.reader()?;
+
+        let left_sel = left.selection(left_reader.byte_headers()?)?;
+        let right_sel = right.selection(right_reader.byte_headers()?)?;
+
+        if left_sel.len() != 1 {
+            Err("--interval expects a single column to be selected on left!")?;
+        }
+
+        if right_sel.len() != 2 {
+            Err("--interval expects two columns, start & end, to be selected on right!")?;
+        }
+
+        let mut writer = self.wconf().writer()?;
+
+        let left_headers = left_reader.byte_headers()?.clone();
+        let right_headers = right_reader.byte_headers()?.clone();
+
+        let right_padding = get_padding(&right_headers);
+
+        self.write_headers(&mut writer, &left_headers, &right_headers)?;
+
+        let index = IntervalIndex::from_csv_reader(&mut right_reader, &right_sel)?;
+
+        let mut left_record = csv::ByteRecord::new();
+
+        while left_reader.read_byte_record(&mut left_record)? {
+            let matches = index.matches(&left_record[left_sel[0]]);
+
+            for right_record in matches.iter() {
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_record.iter())
+                        .chain(self.indicator(BOTH)),
+                )?;
+            }
+
+            if matches.is_empty() && self.flag_left {
+                writer.write_record(
+                    left_record
+                        .iter()
+                        .chain(right_padding.iter())
+                        .chain(self.indicator(LEFT_ONLY)),
+                )?;
             }
         }
 
@@ -519,7 +1100,37 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("Please pick exactly one join operation.")?;
     }
 
-    if args.flag_left {
+    if args.flag_indicator.is_some() && !(args.flag_left || args.flag_right || args.flag_full) {
+        Err("--indicator only works with outer joins (--left, --right & --full).")?;
+    }
+
+    if args.flag_sorted {
+        if args.flag_cross || args.flag_interval || args.flag_index {
+            Err("--sorted only works with inner, left, right and full joins.")?;
+        }
+
+        args.sorted_join()
+    } else if args.flag_interval {
+        if args.flag_right || args.flag_full || args.flag_cross || args.flag_index {
+            Err("--interval only works with inner and left joins.")?;
+        }
+
+        if args.flag_ignore_case || args.flag_trim || args.flag_nulls {
+            Err("--interval cannot be used with -i/--ignore-case, --trim nor --nulls.")?;
+        }
+
+        args.interval_join()
+    } else if args.flag_index {
+        if args.flag_right || args.flag_full || args.flag_cross {
+            Err("--index only works with inner and left joins.")?;
+        }
+
+        if args.flag_ignore_case || args.flag_trim {
+            Err("--index cannot be used with -i/--ignore-case nor --trim.")?;
+        }
+
+        args.indexed_join()
+    } else if args.flag_left {
         args.left_join()
     } else if args.flag_right {
         args.right_join()
@@ -562,7 +1173,7 @@ mod tests {
 
     #[test]
     fn test_index_linked_lists() {
-        let mut index = Index::new(false, false);
+        let mut index = Index::new(KeyNormalization::default(), false);
         let sel = Selection::full(1);
 
         index.add(&sel, ByteRecord::from(rec(&["a", "one"])));
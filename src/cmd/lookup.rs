@@ -0,0 +1,110 @@
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Print the rows of a CSV file whose key column is equal to one of the given
+values, in the order of said values.
+
+This command relies on a key index that must have been created beforehand
+using `xan index --key`, so that each value can be found in logarithmic time
+by seeking into the file instead of scanning it entirely.
+
+For instance, to fetch the rows having 42 or 99 as id:
+
+    $ xan index --key id file.csv
+    $ xan lookup id 42,99 file.csv
+
+Keys that cannot be found will be reported on stderr, unless --ignore-missing
+is given.
+
+Usage:
+    xan lookup [options] <column> <values> <input>
+    xan lookup --help
+
+lookup options:
+    --ignore-missing       Don't report keys that could not be found.
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
+";
+
+#[derive(Deserialize)]
+struct Args {
+    arg_column: SelectColumns,
+    arg_values: String,
+    arg_input: String,
+    flag_ignore_missing: bool,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let rconf = Config::new(&Some(args.arg_input.clone()))
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
+        .select(args.arg_column);
+
+    let mut key_index = match rconf.key_indexed()? {
+        Some(key_index) => key_index,
+        None => Err(format!(
+            "could not find a key index for {}. Please run `xan index --key` on it first!",
+            args.arg_input
+        ))?,
+    };
+
+    let mut indexed = match rconf.indexed()? {
+        Some(indexed) => indexed,
+        None => Err(format!(
+            "could not find an index for {}. Please run `xan index --key` on it first!",
+            args.arg_input
+        ))?,
+    };
+
+    let headers = indexed.byte_headers()?.clone();
+    let sel = rconf.selection(&headers)?;
+
+    if sel.len() != 1 {
+        Err("xan lookup only works with a single key column!")?;
+    }
+
+    if sel
+        .select(&headers)
+        .ne(key_index.headers().iter().map(|h| h.as_slice()))
+    {
+        Err(format!(
+            "the key index of {} was not built on the selected column(s)!",
+            args.arg_input
+        ))?;
+    }
+
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    wtr.write_byte_record(&headers)?;
+
+    let mut record = csv::ByteRecord::new();
+
+    for value in args.arg_values.split(',') {
+        let positions = key_index.get(&[value.as_bytes().to_vec()])?;
+
+        if positions.is_empty() && !args.flag_ignore_missing {
+            eprintln!("could not find key: {}", value);
+        }
+
+        for position in positions {
+            indexed.seek(position)?;
+            indexed.read_byte_record(&mut record)?;
+            wtr.write_byte_record(&record)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
@@ -3,7 +3,8 @@ use std::convert::TryFrom;
 use crate::cmd::moonblade::{
     run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMode,
 };
-use crate::config::Delimiter;
+use crate::config::{Comment, Delimiter};
+use crate::moonblade::are_all_expressions_named;
 use crate::util;
 use crate::CliResult;
 
@@ -28,6 +29,21 @@ a,b,c
 1,4,5
 5,2,7
 
+You can also create multiple columns at once by omitting the column name and
+giving a comma-separated list of expressions, each one named using the "as"
+keyword, like with the agg command:
+
+    $ xan map 'a + b as c, a * b as d' file.csv > result.csv
+
+Will produce the following result:
+
+a,b,c,d
+1,4,5,4
+5,2,7,10
+
+This is more efficient than chaining multiple map calls since the file is
+only read and parsed once.
+
 For a quick review of the capabilities of the script language, use
 the --cheatsheet flag.
 
@@ -43,8 +59,16 @@ Miscellaneous tricks:
 
     $ xan map '"john"' from file.csv > result.csv
 
+3. Normalizing a column using an aggregate computed in a first pass:
+
+    $ xan map --prelude 'max(value) as cmax' 'value / cmax' normalized file.csv
+
+4. Processing multiple files at once while keeping track of their path:
+
+    $ xan map --glob '*.csv' 'filename() as file'
+
 Usage:
-    xan map [options] <expression> <column> [<input>]
+    xan map [options] <expression> [<column>] [<input>]
     xan map --cheatsheet
     xan map --functions
     xan map --help
@@ -61,10 +85,28 @@ map options:
                                  - "report": add a column containing error
                                  - "ignore": coerce result for row to null
                                  - "log": print error to stderr
-                               [default: panic].
+                               Defaults to "panic".
     --error-column <name>      Name of the column containing errors if -E/--errors
                                is set to "report".
                                [default: xan_error].
+    --errors-to <path>         Write evaluation errors, along with the index of
+                               the offending row, as an "index,error" CSV file
+                               at <path>. Note that this index starts at 0, like
+                               the one given by "xan enum", while logged errors
+                               report row numbers starting at 1. Implies
+                               "-E/--errors ignore" so that the main output is
+                               not polluted, and therefore cannot be used
+                               with -E/--errors.
+    --prelude <aggs>           Aggregations, using the same syntax as the "agg"
+                               command, to compute over the whole file in a first
+                               pass, e.g. 'max(value) as cmax'. Their results can
+                               then be referenced by name in the expression, as if
+                               they were columns, and must therefore not be named
+                               like an existing column. Does not work with stdin.
+    --glob <pattern>           Read every file matching given glob pattern instead of
+                               a single input. Files must all have the same headers.
+                               Use the filename() function to know which file a row
+                               comes from.
 
 Common options:
     -h, --help               Display this message
@@ -73,11 +115,13 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 "#;
 
 #[derive(Deserialize)]
 struct Args {
-    arg_column: String,
+    arg_column: Option<String>,
     arg_expression: String,
     arg_input: Option<String>,
     flag_output: Option<String>,
@@ -85,14 +129,30 @@ struct Args {
     flag_cheatsheet: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
-    flag_errors: String,
+    flag_errors: Option<String>,
     flag_error_column: String,
+    flag_errors_to: Option<String>,
+    flag_prelude: Option<String>,
+    flag_glob: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    // NOTE: when the expression is a list of named expressions, the second
+    // positional argument is actually the input.
+    if are_all_expressions_named(&args.arg_expression) {
+        if args.arg_input.is_some() {
+            Err("cannot give a column name when all expressions are already named!")?;
+        }
+
+        args.arg_input = args.arg_column.take();
+    } else if args.arg_column.is_none() && !args.flag_cheatsheet && !args.flag_functions {
+        Err("missing <column> name. Or did you forget to name your expressions using \"as\"?")?;
+    }
 
     let parallelization = match (args.flag_parallel, args.flag_threads) {
         (true, None) => Some(None),
@@ -100,19 +160,31 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         _ => None,
     };
 
+    let error_policy = match (&args.flag_errors_to, args.flag_errors) {
+        (Some(_), Some(_)) => Err("--errors-to cannot be used with -E/--errors!")?,
+        (Some(_), None) => MoonbladeErrorPolicy::Ignore,
+        (None, policy) => {
+            MoonbladeErrorPolicy::try_from(policy.unwrap_or_else(|| "panic".to_string()))?
+        }
+    };
+
     let moonblade_args = MoonbladeCmdArgs {
         print_cheatsheet: args.flag_cheatsheet,
         print_functions: args.flag_functions,
-        target_column: Some(args.arg_column),
+        target_column: args.arg_column,
         map_expr: args.arg_expression,
         input: args.arg_input,
         output: args.flag_output,
         no_headers: args.flag_no_headers,
         delimiter: args.flag_delimiter,
+        comment: args.flag_comment,
         parallelization,
-        error_policy: MoonbladeErrorPolicy::try_from(args.flag_errors)?,
+        error_policy,
         error_column_name: Some(args.flag_error_column),
         mode: MoonbladeMode::Map,
+        errors_to: args.flag_errors_to,
+        prelude: args.flag_prelude,
+        glob: args.flag_glob,
         ..Default::default()
     };
 
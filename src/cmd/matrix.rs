@@ -1,4 +1,4 @@
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::moonblade::agg::CovarianceWelford;
 use crate::select::SelectColumns;
 use crate::util;
@@ -27,6 +27,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter foDirectedr reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize, Debug)]
@@ -36,6 +38,7 @@ struct Args {
     flag_fill_diagonal: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
 }
 
@@ -43,6 +46,7 @@ impl Args {
     fn correlation(&self) -> CliResult<()> {
         let rconf = Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
             .select(self.flag_select.clone());
 
@@ -4,7 +4,7 @@ use std::collections::BinaryHeap;
 use colored::Colorize;
 
 use crate::cmd::sort::{ComparableByteRecord, NumericallyComparableByteRecord};
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -70,6 +70,8 @@ Common options:
                            concatenating columns.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(PartialEq, PartialOrd, Ord, Eq)]
@@ -82,6 +84,7 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_numeric: bool,
     flag_reverse: bool,
     flag_uniq: bool,
@@ -241,6 +244,7 @@ impl Args {
 
                     Ok(Config::new(&Some(path))
                         .delimiter(self.flag_delimiter)
+                        .comment(self.flag_comment)
                         .no_headers(self.flag_no_headers)
                         .select(self.flag_select.clone()))
                 })
@@ -250,6 +254,7 @@ impl Args {
         util::many_configs(
             &self.arg_inputs,
             self.flag_delimiter,
+            self.flag_comment,
             self.flag_no_headers,
             Some(&self.flag_select),
         )
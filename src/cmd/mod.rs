@@ -30,6 +30,7 @@ pub mod implode;
 pub mod index;
 pub mod input;
 pub mod join;
+pub mod lookup;
 pub mod map;
 pub mod matrix;
 pub mod merge;
@@ -50,6 +51,7 @@ pub mod shuffle;
 pub mod slice;
 pub mod sort;
 pub mod split;
+pub mod sql;
 pub mod stats;
 pub mod to;
 pub mod tokenize;
@@ -1,13 +1,15 @@
 use std::borrow::Cow;
+use std::collections::HashSet;
 use std::convert::TryFrom;
 
 use colored::Colorize;
+use glob::glob;
 use lazy_static::lazy_static;
 use pariter::IteratorExt;
 use regex::{Captures, Regex};
 
-use crate::config::{Config, Delimiter};
-use crate::moonblade::{DynamicValue, Program, SpecifiedEvaluationError};
+use crate::config::{Comment, Config, Delimiter};
+use crate::moonblade::{AggregationProgram, DynamicValue, Program, SpecifiedEvaluationError};
 use crate::select::SelectColumns;
 use crate::util::ImmutableRecordHelpers;
 use crate::CliError;
@@ -276,15 +278,39 @@ use the operators in the previous section.
     - ceil(x) -> number
         Return the smallest integer greater than or equal to x.
 
+    - ceil_to(x, multiple) -> number
+        Return the smallest multiple of the given number greater than or equal
+        to x, e.g. ceil_to(x, 0.25). The multiple must be greater than 0.
+
+    - clamp(x, lo, hi) -> number
+        Return x clamped between lo and hi (lo must not be greater than hi).
+
     - div(x, y, *n) -> number
         Divide two or more numbers.
 
     - floor(x) -> number
         Return the smallest integer lower than or equal to x.
 
+    - floor_to(x, multiple) -> number
+        Return the largest multiple of the given number lower than or equal
+        to x, e.g. floor_to(timestamp, 3600) to bucket timestamps by hour.
+        The multiple must be greater than 0.
+
+    - gcd(x, y) -> number
+        Return the greatest common divisor of two integers.
+
     - idiv(x, y) -> number
         Integer division of two numbers.
 
+    - is_finite(x) -> bool
+        Return whether x is a finite number, i.e. neither NaN nor infinite.
+
+    - is_nan(x) -> bool
+        Return whether x is NaN (not a number).
+
+    - lcm(x, y) -> number
+        Return the least common multiple of two integers.
+
     - log(x) -> number
         Return the natural logarithm of x.
 
@@ -302,6 +328,10 @@ use the operators in the previous section.
     - mul(x, y, *n) -> number
         Multiply two or more numbers.
 
+    - nan_to_null(x) -> number?
+        Return null if x is NaN or infinite, else return x. Useful to avoid
+        a single invalid value poisoning an aggregation such as sum or mean.
+
     - neg(x) -> number
         Return -x.
 
@@ -311,6 +341,13 @@ use the operators in the previous section.
     - round(x) -> number
         Return x rounded to the nearest integer.
 
+    - round_to(x, multiple) -> number
+        Return x rounded to the nearest multiple of the given number,
+        e.g. round_to(price, 0.05). The multiple must be greater than 0.
+
+    - sign(x) -> number
+        Return -1 if x is negative, 1 if x is positive and 0 if x is zero.
+
     - sqrt(x) -> number
         Return the square root of x.
 
@@ -329,6 +366,12 @@ use the operators in the previous section.
         Evaluate condition and switch to correct branch.
         Will actually short-circuit. Contrary to \"or\" and \"and\".
 
+    - try(expr, default?) -> T
+        Evaluate given expression and return its result, or return the default
+        value (null if not given) if its evaluation raised an error. This
+        is useful to handle occasionally malformed values without having to
+        rely on a command-wide error policy, e.g. try(datetime(x), \"unknown\").
+
     - unless(cond, then, else?) -> T
         Shorthand for `if(not(cond), then, else?)`.
 
@@ -360,6 +403,11 @@ use the operators in the previous section.
 
 ## String & sequence helpers
 
+    - center(string, width, fill?) -> string
+        Pad string on both sides using the fill character (defaults to a
+        space) so that it reaches the given width, counted in characters.
+        When padding is uneven, the extra character goes on the right.
+
     - compact(list) -> list
         Drop all falsey values from given list.
 
@@ -377,12 +425,19 @@ use the operators in the previous section.
     - endswith(string, pattern) -> bool
         Test if string ends with pattern.
 
+    - enumerate(list) -> list
+        Return a list of [index, value] pairs for given list.
+
     - escape_regex(string) -> string
         Escape a string so it can be used safely in a regular expression.
 
     - first(seq) -> T
         Get first element of sequence.
 
+    - fixed(number, decimals) -> string
+        Format a number using exactly the given number of decimals, rounding
+        half to even. E.g. `fixed(3.14159, 2)` will return \"3.14\".
+
     - fmt(string, *replacements) -> string:
         Format a string by replacing \"{}\" occurrences by subsequent
         arguments.
@@ -396,6 +451,12 @@ use the operators in the previous section.
         Returns nothing if index or key is not found or alternatively the provided
         default value.
 
+    - get_path(target, path, default?) -> T
+        Traverse nested maps & lists using a dot-separated path of keys and
+        numerical indices, e.g. \"user.addresses.0.city\". Returns nothing if
+        any step of the path is not found or alternatively the provided default
+        value. Use get() with a list path if keys contain dots.
+
     - join(seq, sep) -> string
         Join sequence by separator.
 
@@ -418,6 +479,17 @@ use the operators in the previous section.
     - numfmt(number) -> string:
         Format a number with thousands separator and proper significance.
 
+    - range(start, end, step?) -> list
+        Return the list of integers going from start (included) to
+        end (excluded), using the given step (defaults to 1).
+
+    - percent(number, decimals?) -> string
+        Format a ratio as a percentage using the given number of decimals
+        (defaults to 1). E.g. `percent(0.42)` will return \"42.0%\".
+
+    - repeat(string, n) -> string
+        Repeat string n times.
+
     - replace(string, pattern, replacement) -> string
         Replace pattern in string. Can use a regex.
 
@@ -444,6 +516,11 @@ use the operators in the previous section.
     - upper(string) -> string
         Uppercase string.
 
+    - zip(list1, list2, pad=?) -> list
+        Return a list of [item1, item2] pairs from given lists. Stops at
+        the shortest list, unless pad is truthy, in which case the shortest
+        list will be padded with null values.
+
 ## Dates
 
     - datetime(string, format=?, timezone=?) -> datetime
@@ -511,6 +588,12 @@ use the operators in the previous section.
     - mean(numbers) -> number?
         Return the means of the given numbers.
 
+    - product(numbers) -> number
+        Return the product of the given numbers, or 1 if the list is empty.
+
+    - sum(numbers) -> number
+        Return the sum of the given numbers, or 0 if the list is empty.
+
 ## Fuzzy matching & information retrieval
 
     - fingerprint(string) -> string
@@ -521,19 +604,44 @@ use the operators in the previous section.
     - carry_stemmer(string) -> string
         Apply the \"Carry\" stemmer targeting the French language.
 
+    - jaro_winkler(string1, string2) -> float
+        Return the Jaro-Winkler similarity of the given strings, between
+        0 (completely different) and 1 (identical).
+
+    - levenshtein(string1, string2) -> int
+        Return the Levenshtein edit distance between the given strings,
+        counted in characters.
+
+    - metaphone(string) -> string
+        Return the Metaphone phonetic key of the given string, targeting
+        the English language. Non-alphabetic characters are ignored.
+
     - s_stemmer(string) -> string
         Apply a very simple stemmer removing common plural inflexions in
         some languages.
 
+    - soundex(string) -> string
+        Return the 4 characters long Soundex phonetic key of the given
+        string, e.g. \"R163\" for \"Robert\". Non-alphabetic characters
+        are ignored, and an empty string is returned if none remains.
+
 ## Utils
 
     - coalesce(*args) -> T
-        Return first truthy value.
+        Return first truthy value. Note that this means falsey values such
+        as 0 or false will be skipped. Use `first_non_empty` instead if you
+        only need to skip null values & empty strings.
 
     - col(name_or_pos, nth?) -> string
         Return value of cell for given column, by name, by position or by
         name & nth, in case of duplicate header names.
 
+    - col_exists(name_or_pos, nth?) -> bool
+        Return whether given column exists, by name, by position or by
+        name & nth, in case of duplicate header names. Useful to write
+        expressions working across files with slightly different schemas,
+        e.g. if(col_exists(\"price\"), price?, 0).
+
     - cols(from_name_or_pos?, to_name_or_pos?) -> list
         Return list of cell values from the given colum by name or position
         to another given column by name or position, inclusive.
@@ -543,6 +651,17 @@ use the operators in the previous section.
     - err(msg) -> error
         Make the expression return a custom error.
 
+    - filename() -> string?
+        Return the path of the file currently being read, if applicable
+        (e.g. when using the --glob flag).
+
+    - first_non_empty(*args) -> T
+    - coalesce_empty(*args) -> T
+        Return first value that is neither null nor an empty string. Contrary
+        to `coalesce`, values such as 0 or false will be returned, which makes
+        it suitable to consolidate partially-filled numerical columns, e.g.
+        `first_non_empty(count_a, count_b, count_c)`.
+
     - headers(from_name_or_pos?, to_name_or_pos?) -> list
         Return list of header names from the given colum by name or position
         to another given column by name or position, inclusive.
@@ -552,18 +671,64 @@ use the operators in the previous section.
     - index() -> integer?
         Return the row's index, if applicable.
 
+    - json_stringify(value, pretty=?) -> string
+        Serialize the given value as JSON. Map keys are sorted and the
+        output is indented if pretty is truthy.
+
     - mime_ext(string) -> string
         Return the extension related to given mime type.
 
+    - ncols() -> integer
+        Return the number of columns of the file. Note that there is no
+        nrows() counterpart, since the number of rows cannot be known
+        when streaming the file. With `map` or `transform`, you can compute it
+        beforehand using --prelude 'count() as nrows' instead.
+
     - parse_dataurl(string) -> [string, bytes]
         Parse the given data url and return its mime type and decoded binary data.
 
     - parse_json(string) -> any
         Parse the given string as JSON.
 
+    - row() -> map
+        Return a map of the current row, whose keys are the header names, or
+        the column indices when working without headers. Can be used, for
+        instance, to serialize whole rows using json_stringify(row()).
+
     - typeof(value) -> string
         Return type of value.
 
+## Urls
+
+    - domain(string) -> string?
+        Return the registrable domain of the given url, e.g. \"bbc.co.uk\"
+        for \"https://news.bbc.co.uk/sport\". Note that this relies on a
+        heuristic handling common second-level domains of country code
+        top-level domains, and not on the full public suffix list. Will
+        return null if the url is invalid.
+
+    - normalize_url(string) -> string?
+        Normalize the given url by lowercasing its scheme & host, dropping
+        its default port, fragment and known tracking query parameters
+        (e.g. \"utm_source\", \"fbclid\"). Will return null if the url is
+        invalid.
+
+    - tld(string) -> string?
+        Return the top-level domain of the given url, if any.
+
+    - url_decode(string) -> string
+        Decode the percent-encoded given string.
+
+    - url_encode(string) -> string
+        Percent-encode the given string so it can be safely used
+        as an url component.
+
+    - url_parse(string, strict=?) -> map?
+        Parse the given url and return a map containing its \"scheme\",
+        \"host\", \"port\", \"path\", \"query\" & \"fragment\". Will return null if
+        the url is invalid, or raise an error if strict is truthy.
+        For instance, to extract domains: get(url_parse(link), \"host\").
+
 ## IO & path wrangling
 
     - abspath(string) -> string
@@ -589,6 +754,11 @@ use the operators in the previous section.
         Move a source to target path. Will create necessary directories
         on the way. Returns target path as a convenience.
 
+    - parse_bytes(string) -> int
+        Parse a human-readable number of bytes (e.g. \"1.5GB\", \"2 KiB\")
+        and return it as an integer. Inverse of `bytesize`. Will raise
+        an error if the string cannot be parsed.
+
     - pjoin(string, *strings) -> string
     - pathjoin(string, *strings) -> string
         Join multiple paths correctly.
@@ -621,6 +791,11 @@ use the operators in the previous section.
     - uuid() -> string
         Return a uuid v4.
 
+    Note that both random() and uuid() can be seeded by setting the XAN_SEED
+    environment variable to some integer, in which case they will always
+    yield the same sequence of values (as long as evaluation is not
+    parallelized).
+
 ";
 
     colorize_functions_help(help)
@@ -642,6 +817,11 @@ consider empty values or make them avoid non-numerical values altogether.
 For instance, considering null values when computing a mean is as easy
 as `mean(number || 0)`.
 
+Conditional functions such as `sum_where` or `mean_where` only consider
+values from rows where their predicate is truthy, which means null or empty
+predicates count as false. Values from rows failing the predicate are never
+cast to numbers and therefore cannot yield an error.
+
 Finally, note that expressions returning lists will be understood as multiplexed rows.
 This means that computing `cardinality([source, target])`, for instance, will return
 the number of nodes in a graph represented by a CSV edge list.
@@ -656,9 +836,16 @@ the number of nodes in a graph represented by a CSV edge list.
         Returns the approximate cardinality of the set of values returned by given
         expression using the HyperLogLog+ algorithm.
 
+    - approx_median(<expr>) -> number
+        Returns an approximation of the median of values returned by given
+        expression. Same as `approx_quantile(<expr>, 0.5)`.
+
     - approx_quantile(<expr>, p) -> number
         Returns an approximation of the desired quantile of values returned by given
-        expression using t-digests.
+        expression using t-digests. Contrary to `quantile`, this does not need to
+        buffer all the values in memory and can be merged efficiently when using
+        parallelization. The rank of the returned value is typically within 1% of
+        the requested one, and is even more accurate for extreme quantiles.
 
     - argmin(<expr>, <expr>?) -> any
         Return the index of the row where the first expression is minimized, or
@@ -679,6 +866,10 @@ the number of nodes in a graph represented by a CSV edge list.
     - avg(<expr>) -> number
         Average of numerical values. Same as `mean`.
 
+    - avg_where(<predicate>, <expr>) -> number
+        Average of numerical values returned by the second expression, only
+        for rows where the predicate is truthy. Same as `mean_where`.
+
     - cardinality(<expr>) -> number
         Number of distinct values returned by given expression.
 
@@ -690,6 +881,14 @@ the number of nodes in a graph represented by a CSV edge list.
         Count the number of truthy values returned by given expression.
         Expression can also be omitted to count all rows.
 
+    - count_distinct_where(<predicate>, <expr>) -> number
+        Count the number of distinct values returned by the second
+        expression, only for rows where the predicate is truthy.
+
+    - count_where(<predicate>) -> number
+        Count the number of rows where the predicate is truthy. Same as
+        `count(<predicate>)`, except the predicate cannot be omitted.
+
     - count_seconds(<expr>) -> number
         Count the number of seconds between earliest and latest datetime
         returned by given expression.
@@ -728,6 +927,10 @@ the number of nodes in a graph represented by a CSV edge list.
     - first(<expr>) -> string
         Return first seen non empty element of the values returned by the given expression.
 
+    - iqr(<expr>) -> number
+        Interquartile range of numerical values, i.e. the difference
+        between the third and the first quartiles.
+
     - latest(<expr>) -> datetime
         Latest datetime returned by given expression.
 
@@ -740,6 +943,11 @@ the number of nodes in a graph represented by a CSV edge list.
     - lex_last(<expr>) -> string
         Return last string in lexicographical order.
 
+    - mad(<expr>) -> number
+        Median absolute deviation of numerical values, i.e. the median
+        of absolute deviations from their median. A robust alternative
+        to `stddev`.
+
     - min(<expr>) -> number | string
         Minimum numerical value.
 
@@ -749,6 +957,10 @@ the number of nodes in a graph represented by a CSV edge list.
     - mean(<expr>) -> number
         Mean of numerical values. Same as `avg`.
 
+    - mean_where(<predicate>, <expr>) -> number
+        Mean of numerical values returned by the second expression, only
+        for rows where the predicate is truthy. Same as `avg_where`.
+
     - median(<expr>) -> number
         Median of numerical values, interpolating on even counts.
 
@@ -789,6 +1001,9 @@ the number of nodes in a graph represented by a CSV edge list.
     - ratio(<expr>) -> number
         Return the ratio of truthy values returned by expression.
 
+    - span(<expr>) -> number
+        Difference between the maximum and the minimum numerical values.
+
     - stddev(<expr>) -> number
         Population standard deviation. Same as `stddev_pop`.
 
@@ -802,6 +1017,10 @@ the number of nodes in a graph represented by a CSV edge list.
         Sum of numerical values. Will return nothing if the sum overflows.
         Uses the Kahan-Babuska routine for precise float summation.
 
+    - sum_where(<predicate>, <expr>) -> number
+        Sum of numerical values returned by the second expression, only
+        for rows where the predicate is truthy.
+
     - top(k, <expr>, separator?) -> any
         Find the top k values returned by the expression and join
         them by a pipe character ('|') or by the provided separator.
@@ -944,6 +1163,20 @@ impl TryFrom<String> for MoonbladeErrorPolicy {
     }
 }
 
+// NOTE: used by filter to only keep rows whose key is (or is not) found in
+// a reference set of values, before the expression is even evaluated.
+pub struct MoonbladeMembership {
+    pub column: SelectColumns,
+    pub values: HashSet<Vec<u8>>,
+    pub invert: bool,
+}
+
+impl MoonbladeMembership {
+    fn matches(&self, index: usize, record: &csv::ByteRecord) -> bool {
+        self.values.contains(&record[index]) != self.invert
+    }
+}
+
 #[derive(Default)]
 pub struct MoonbladeCmdArgs {
     pub print_cheatsheet: bool,
@@ -955,11 +1188,32 @@ pub struct MoonbladeCmdArgs {
     pub output: Option<String>,
     pub no_headers: bool,
     pub delimiter: Option<Delimiter>,
+    pub comment: Option<Comment>,
     pub parallelization: Option<Option<usize>>,
     pub error_policy: MoonbladeErrorPolicy,
     pub error_column_name: Option<String>,
     pub mode: MoonbladeMode,
     pub limit: Option<usize>,
+    pub membership: Option<MoonbladeMembership>,
+    pub errors_to: Option<String>,
+    pub prelude: Option<String>,
+    pub glob: Option<String>,
+}
+
+type ErrorsWriter = csv::Writer<Box<dyn std::io::Write + Send + 'static>>;
+
+// NOTE: used to write evaluation errors, along with the index of the
+// offending row, into a side file instead of the main output.
+fn write_error_to_side_file(
+    errors_wtr: Option<&mut ErrorsWriter>,
+    index: usize,
+    eval_result: &Result<DynamicValue, SpecifiedEvaluationError>,
+) -> CliResult<()> {
+    if let (Some(errors_wtr), Err(err)) = (errors_wtr, eval_result) {
+        errors_wtr.write_record([index.to_string().as_bytes(), err.to_string().as_bytes()])?;
+    }
+
+    Ok(())
 }
 
 pub fn handle_eval_result<'b>(
@@ -968,6 +1222,7 @@ pub fn handle_eval_result<'b>(
     record: &'b mut csv::ByteRecord,
     eval_result: Result<DynamicValue, SpecifiedEvaluationError>,
     replace: Option<usize>,
+    map_arity: Option<usize>,
 ) -> Result<Vec<Cow<'b, csv::ByteRecord>>, String> {
     let mut records_to_emit: Vec<Cow<csv::ByteRecord>> = Vec::new();
 
@@ -985,7 +1240,17 @@ pub fn handle_eval_result<'b>(
                 }
             }
             MoonbladeMode::Map => {
-                record.push_field(&value.serialize_as_bytes());
+                match map_arity {
+                    None => record.push_field(&value.serialize_as_bytes()),
+                    Some(_) => match value {
+                        DynamicValue::List(values) => {
+                            for item in values.iter() {
+                                record.push_field(&item.serialize_as_bytes());
+                            }
+                        }
+                        _ => unreachable!(),
+                    },
+                }
 
                 if args.error_policy.will_report() {
                     record.push_field(b"");
@@ -1024,7 +1289,9 @@ pub fn handle_eval_result<'b>(
         Err(err) => match args.error_policy {
             MoonbladeErrorPolicy::Ignore => {
                 if args.mode.is_map() {
-                    record.push_field(b"");
+                    for _ in 0..map_arity.unwrap_or(1) {
+                        record.push_field(b"");
+                    }
                     records_to_emit.push(Cow::Borrowed(record));
                 } else if args.mode.is_transform() {
                     let record = record.replace_at(replace.unwrap(), b"");
@@ -1037,7 +1304,9 @@ pub fn handle_eval_result<'b>(
                 }
 
                 if args.mode.is_map() {
-                    record.push_field(b"");
+                    for _ in 0..map_arity.unwrap_or(1) {
+                        record.push_field(b"");
+                    }
                     record.push_field(err.to_string().as_bytes());
                     records_to_emit.push(Cow::Borrowed(record));
                 } else if args.mode.is_transform() {
@@ -1050,7 +1319,9 @@ pub fn handle_eval_result<'b>(
                 eprintln!("Row n°{}: {}", index + 1, err);
 
                 if args.mode.is_map() {
-                    record.push_field(b"");
+                    for _ in 0..map_arity.unwrap_or(1) {
+                        record.push_field(b"");
+                    }
                     records_to_emit.push(Cow::Borrowed(record));
                 } else if args.mode.is_transform() {
                     let record = record.replace_at(replace.unwrap(), b"");
@@ -1066,6 +1337,42 @@ pub fn handle_eval_result<'b>(
     Ok(records_to_emit)
 }
 
+// NOTE: the prelude is a list of aggregations computed over the whole file in
+// a first pass, whose results are then bound as constants in the evaluated
+// expression, during the second pass.
+fn run_prelude(
+    code: &str,
+    rconfig: &Config,
+    no_headers: bool,
+) -> CliResult<(csv::ByteRecord, csv::ByteRecord)> {
+    if rconfig.is_std() {
+        Err("--prelude cannot work with stdin since the file must be read twice!")?;
+    }
+
+    let mut rdr = rconfig.reader()?;
+
+    let headers = if no_headers {
+        csv::ByteRecord::new()
+    } else {
+        rdr.byte_headers()?.clone()
+    };
+
+    let mut program = AggregationProgram::parse(code, &headers)?;
+
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        program.run_with_record(index, &record)?;
+        index += 1;
+    }
+
+    let names = program.headers().collect::<csv::ByteRecord>();
+    let values = program.finalize(false)?;
+
+    Ok((names, values))
+}
+
 pub fn run_moonblade_cmd(args: MoonbladeCmdArgs) -> CliResult<()> {
     if args.print_cheatsheet {
         println!("{}", get_moonblade_cheatsheet());
@@ -1077,8 +1384,37 @@ pub fn run_moonblade_cmd(args: MoonbladeCmdArgs) -> CliResult<()> {
         return Ok(());
     }
 
-    let mut rconfig = Config::new(&args.input)
+    let inputs = match &args.glob {
+        None => vec![args.input.clone()],
+        Some(pattern) => {
+            if args.input.is_some() {
+                Err("--glob cannot be used with an <input> path!")?;
+            }
+
+            if args.prelude.is_some() {
+                Err("--glob cannot be used with --prelude!")?;
+            }
+
+            let mut paths = Vec::new();
+
+            for entry in glob(pattern)? {
+                paths.push(Some(entry?.to_string_lossy().into_owned()));
+            }
+
+            if paths.is_empty() {
+                Err(format!(
+                    "glob pattern \"{}\" did not match any file!",
+                    pattern
+                ))?;
+            }
+
+            paths
+        }
+    };
+
+    let mut rconfig = Config::new(&inputs[0])
         .delimiter(args.delimiter)
+        .comment(args.comment)
         .no_headers(args.no_headers);
 
     let mut rdr = rconfig.reader()?;
@@ -1092,6 +1428,52 @@ pub fn run_moonblade_cmd(args: MoonbladeCmdArgs) -> CliResult<()> {
 
     if !args.no_headers {
         headers = rdr.byte_headers()?.clone();
+    }
+
+    let prelude = args
+        .prelude
+        .as_ref()
+        .map(|code| run_prelude(code, &rconfig, args.no_headers))
+        .transpose()?;
+
+    // NOTE: prelude results are parsed as additional columns, before being
+    // replaced by their values.
+    let mut parsing_headers = headers.clone();
+
+    if let Some((names, _)) = &prelude {
+        for name in names.iter() {
+            if headers.iter().any(|h| h == name) {
+                Err(format!(
+                    "--prelude aggregation \"{}\" has the same name as an existing column!",
+                    String::from_utf8_lossy(name)
+                ))?;
+            }
+        }
+
+        parsing_headers.extend(names.iter());
+    }
+
+    // NOTE: when map is not given a target column, its expression is
+    // understood as a list of named expressions, each one producing a column.
+    let named_map_program = if args.mode.is_map() && args.target_column.is_none() {
+        Some(Program::parse_named(&map_expr, &parsing_headers)?)
+    } else {
+        None
+    };
+
+    let map_arity = named_map_program.as_ref().map(|(_, names)| names.len());
+
+    let membership = match &args.membership {
+        Some(membership) => Some((
+            membership,
+            membership
+                .column
+                .single_selection(rdr.byte_headers()?, !args.no_headers)?,
+        )),
+        None => None,
+    };
+
+    if !args.no_headers {
         modified_headers = headers.clone();
 
         if !headers.is_empty() {
@@ -1100,6 +1482,10 @@ pub fn run_moonblade_cmd(args: MoonbladeCmdArgs) -> CliResult<()> {
             if args.mode.is_map() {
                 if let Some(target_column) = &args.target_column {
                     modified_headers.push_field(target_column.as_bytes());
+                } else if let Some((_, names)) = &named_map_program {
+                    for name in names {
+                        modified_headers.push_field(name.as_bytes());
+                    }
                 }
             } else if args.mode.is_transform() {
                 if let Some(name) = &args.target_column {
@@ -1138,72 +1524,156 @@ pub fn run_moonblade_cmd(args: MoonbladeCmdArgs) -> CliResult<()> {
         }
     }
 
-    let program = Program::parse(&map_expr, &headers)?;
+    let mut program = match named_map_program {
+        Some((program, _)) => program,
+        None => Program::parse(&map_expr, &parsing_headers)?,
+    };
+
+    if let Some((_, values)) = &prelude {
+        program.bind_constants(&headers, values);
+    }
 
     if must_write_headers {
         wtr.write_byte_record(&modified_headers)?;
     }
 
-    if let Some(threads) = args.parallelization {
-        rdr.into_byte_records()
-            .enumerate()
-            .parallel_map_custom(
-                |o| {
-                    if let Some(count) = threads {
-                        o.threads(count)
-                    } else {
-                        o
-                    }
-                },
-                move |(i, record)| -> CliResult<(
-                    usize,
-                    csv::ByteRecord,
-                    Result<DynamicValue, SpecifiedEvaluationError>,
-                )> {
-                    let record = record?;
-
-                    let eval_result = program.run_with_record(i, &record);
-
-                    Ok((i, record, eval_result))
-                },
-            )
-            .try_for_each(|result| -> CliResult<()> {
-                let (i, mut record, eval_result) = result?;
-                let records_to_emit =
-                    handle_eval_result(&args, i, &mut record, eval_result, column_to_replace)?;
-
-                for record_to_emit in records_to_emit {
-                    wtr.write_byte_record(&record_to_emit)?;
-                }
-                Ok(())
-            })?;
-
-        return Ok(wtr.flush()?);
-    }
+    let mut errors_wtr = args
+        .errors_to
+        .as_ref()
+        .map(|path| -> CliResult<ErrorsWriter> {
+            let mut errors_wtr = Config::new(&Some(path.clone())).writer()?;
+            errors_wtr.write_record(["index", "error"])?;
+            Ok(errors_wtr)
+        })
+        .transpose()?;
 
-    let mut record = csv::ByteRecord::new();
+    let mut first_rdr = Some(rdr);
     let mut i: usize = 0;
     let mut emitted: usize = 0;
 
-    while rdr.read_byte_record(&mut record)? {
-        let eval_result = program.run_with_record(i, &record);
+    'inputs: for input in inputs.iter() {
+        let mut rdr = match first_rdr.take() {
+            Some(rdr) => rdr,
+            None => {
+                let mut rdr = Config::new(input)
+                    .delimiter(args.delimiter)
+                    .comment(args.comment)
+                    .no_headers(args.no_headers)
+                    .reader()?;
+
+                if !args.no_headers && rdr.byte_headers()? != &headers {
+                    Err(format!(
+                        "{} does not have the same headers as {}!",
+                        input.as_deref().unwrap_or("<stdin>"),
+                        inputs[0].as_deref().unwrap_or("<stdin>")
+                    ))?;
+                }
+
+                rdr
+            }
+        };
+
+        program.set_filename(input.clone());
+
+        if let Some(threads) = args.parallelization {
+            let program = program.clone();
+            let offset = i;
+
+            rdr.into_byte_records()
+                .enumerate()
+                .map(move |(j, record)| (offset + j, record))
+                .parallel_map_custom(
+                    |o| {
+                        if let Some(count) = threads {
+                            o.threads(count)
+                        } else {
+                            o
+                        }
+                    },
+                    move |(i, record)| -> CliResult<(
+                        usize,
+                        csv::ByteRecord,
+                        Result<DynamicValue, SpecifiedEvaluationError>,
+                    )> {
+                        let record = record?;
+
+                        let eval_result = program.run_with_record(i, &record);
+
+                        Ok((i, record, eval_result))
+                    },
+                )
+                .try_for_each(|result| -> CliResult<()> {
+                    let (index, mut record, eval_result) = result?;
+                    i += 1;
+
+                    // NOTE: membership is checked here because the iterator given
+                    // to pariter cannot borrow it.
+                    if let Some((membership, idx)) = membership {
+                        if !membership.matches(idx, &record) {
+                            return Ok(());
+                        }
+                    }
 
-        let records_to_emit =
-            handle_eval_result(&args, i, &mut record, eval_result, column_to_replace)?;
+                    write_error_to_side_file(errors_wtr.as_mut(), index, &eval_result)?;
 
-        for record_to_emit in records_to_emit {
-            emitted += 1;
-            wtr.write_byte_record(&record_to_emit)?;
+                    let records_to_emit = handle_eval_result(
+                        &args,
+                        index,
+                        &mut record,
+                        eval_result,
+                        column_to_replace,
+                        map_arity,
+                    )?;
+
+                    for record_to_emit in records_to_emit {
+                        wtr.write_byte_record(&record_to_emit)?;
+                    }
+                    Ok(())
+                })?;
+
+            continue;
         }
 
-        i += 1;
+        let mut record = csv::ByteRecord::new();
 
-        if let Some(limit) = args.limit {
-            if emitted >= limit {
-                break;
+        while rdr.read_byte_record(&mut record)? {
+            if let Some((membership, idx)) = membership {
+                if !membership.matches(idx, &record) {
+                    i += 1;
+                    continue;
+                }
+            }
+
+            let eval_result = program.run_with_record(i, &record);
+            write_error_to_side_file(errors_wtr.as_mut(), i, &eval_result)?;
+
+            let records_to_emit = handle_eval_result(
+                &args,
+                i,
+                &mut record,
+                eval_result,
+                column_to_replace,
+                map_arity,
+            )?;
+
+            for record_to_emit in records_to_emit {
+                emitted += 1;
+                wtr.write_byte_record(&record_to_emit)?;
+            }
+
+            i += 1;
+
+            if let Some(limit) = args.limit {
+                if emitted >= limit {
+                    break 'inputs;
+                }
             }
         }
     }
 
+    if let Some(mut errors_wtr) = errors_wtr {
+        errors_wtr.flush()?;
+    }
+
     Ok(wtr.flush()?)
 }
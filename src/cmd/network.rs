@@ -1,7 +1,7 @@
 use colored::Colorize;
 
 use crate::collections::IncrementalId;
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::graph::GraphBuilder;
 use crate::json::{Attributes, JSONEmptyMode, JSONTypeInferrenceBuffer};
 use crate::select::{SelectColumns, Selection};
@@ -59,6 +59,8 @@ Common options:
                            headers.
     -d, --delimiter <arg>  The field delimiter foDirectedr reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize, Debug)]
@@ -80,6 +82,7 @@ struct Args {
     flag_disjoint_keys: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
 }
 
@@ -87,6 +90,7 @@ impl Args {
     fn edgelist(&self) -> CliResult<GraphBuilder> {
         let edges_rconf = Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers);
 
         let mut graph_builder = GraphBuilder::new(self.flag_largest_component);
@@ -96,6 +100,7 @@ impl Args {
         if let Some(nodes_path) = &self.flag_nodes {
             let nodes_rconf = Config::new(&Some(nodes_path.clone()))
                 .delimiter(self.flag_delimiter)
+                .comment(self.flag_comment)
                 .no_headers(self.flag_no_headers);
 
             let mut node_reader = nodes_rconf.reader()?;
@@ -206,6 +211,7 @@ impl Args {
     fn bipartite(&self) -> CliResult<GraphBuilder> {
         let rconf = Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers);
 
         let mut graph_builder = GraphBuilder::new(self.flag_largest_component);
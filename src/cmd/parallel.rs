@@ -12,7 +12,7 @@ use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
 use rayon::{prelude::*, ThreadPoolBuilder};
 
 use crate::cmd::progress::get_progress_style;
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::moonblade::{AggregationProgram, GroupAggregationProgram, Stats};
 use crate::select::SelectColumns;
 use crate::util;
@@ -436,6 +436,8 @@ Common options:
                            concatenating columns.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -466,6 +468,7 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 type Reader = csv::Reader<Box<dyn io::Read + Send>>;
@@ -515,6 +518,7 @@ impl Args {
 
             let config = Config::empty()
                 .delimiter(self.flag_delimiter)
+                .comment(self.flag_comment)
                 .no_headers(self.flag_no_headers);
 
             let shell = env::var("SHELL").expect("$SHELL is not set!");
@@ -583,6 +587,7 @@ impl Args {
 
             let config = Config::empty()
                 .delimiter(self.flag_delimiter)
+                .comment(self.flag_comment)
                 .no_headers(self.flag_no_headers);
 
             (
@@ -599,6 +604,7 @@ impl Args {
         } else {
             let config = Config::new(&Some(path.to_string()))
                 .delimiter(self.flag_delimiter)
+                .comment(self.flag_comment)
                 .no_headers(self.flag_no_headers);
 
             (config.reader()?, None)
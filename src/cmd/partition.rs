@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
 use std::fs;
@@ -6,7 +7,8 @@ use std::path::Path;
 
 use regex::Regex;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
+use crate::moonblade::Program;
 use crate::select::SelectColumns;
 use crate::util::{self, FilenameTemplate, ImmutableRecordHelpers};
 use crate::CliResult;
@@ -21,6 +23,12 @@ Note that most operating systems avoid opening more than 1024 files at once,
 so if you know the cardinality of the paritioned column is very high, please
 sort the file on this column beforehand and use the -S/--sorted flag.
 
+Rows can also be partitioned using the result of an expression evaluated on
+each row, instead of the value of a column, using the -e/--evaluate flag. For
+instance, to partition a file by year:
+
+    $ xan partition -e 'year(date)' years file.csv
+
 Usage:
     xan partition [options] <column> <outdir> [<input>]
     xan partition --help
@@ -34,11 +42,20 @@ partition options:
     -p, --prefix-length <n>  Truncate the partition column after the
                              specified number of bytes when creating the
                              output file.
+    -e, --evaluate           Toggle expression evaluation rather than using the
+                             value of a column to partition the rows. See
+                             `xan map --cheatsheet` and `xan map --functions`
+                             for the documentation of the expression language.
     -S, --sorted             Use this flag if you know the file is sorted
                              on the partition column in advance, so the command
                              can run faster and with less memory and resources
                              opened.
-    --drop                   Drop the partition column from results.
+    --drop                   Drop the partition column from results. Does not
+                             work with -e/--evaluate.
+    --manifest <path>        Write a CSV file at <path> listing, for each
+                             partition, its value, the path of the produced
+                             file and its number of rows, with the following
+                             columns: value,path,rows.
 
 Common options:
     -h, --help             Display this message
@@ -47,23 +64,33 @@ Common options:
                            appear in all chunks as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Clone, Deserialize)]
 struct Args {
-    arg_column: SelectColumns,
+    arg_column: String,
     arg_input: Option<String>,
     arg_outdir: String,
     flag_filename: FilenameTemplate,
     flag_prefix_length: Option<usize>,
     flag_drop: bool,
+    flag_evaluate: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_sorted: bool,
+    flag_manifest: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_evaluate && args.flag_drop {
+        Err("--drop does not work with -e/--evaluate!")?;
+    }
+
     fs::create_dir_all(&args.arg_outdir)?;
 
     // It would be nice to support efficient parallel partitions, but doing
@@ -78,15 +105,24 @@ impl Args {
     fn rconfig(&self) -> Config {
         Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
-            .select(self.arg_column.clone())
     }
 
-    /// Get the column to use as a key.
-    fn key_column(&self, rconfig: &Config, headers: &csv::ByteRecord) -> CliResult<usize> {
-        let select_cols = rconfig.selection(headers)?;
+    /// Get the column or the expression to use as a key.
+    fn partition_key(&self, headers: &csv::ByteRecord) -> CliResult<PartitionKey> {
+        if self.flag_evaluate {
+            return Ok(PartitionKey::Expression(Program::parse(
+                &self.arg_column,
+                headers,
+            )?));
+        }
+
+        let select_cols =
+            SelectColumns::parse(&self.arg_column)?.selection(headers, !self.flag_no_headers)?;
+
         if select_cols.len() == 1 {
-            Ok(select_cols[0])
+            Ok(PartitionKey::Column(select_cols[0]))
         } else {
             Err("can only partition on one column")?
         }
@@ -97,86 +133,173 @@ impl Args {
         let rconfig = self.rconfig();
         let mut rdr = rconfig.reader()?;
         let mut headers = rdr.byte_headers()?.clone();
-        let key_col = self.key_column(&rconfig, &headers)?;
+        let partition_key = self.partition_key(&headers)?;
         let mut gen = WriterGenerator::new(self.flag_filename.clone());
 
-        if self.flag_drop {
+        let drop_col = match partition_key {
+            PartitionKey::Column(key_col) if self.flag_drop => Some(key_col),
+            _ => None,
+        };
+
+        if let Some(key_col) = drop_col {
             headers = headers.remove(key_col);
         }
 
+        let mut manifest = self
+            .flag_manifest
+            .as_ref()
+            .map(|path| -> CliResult<_> {
+                let mut wtr = Config::new(&Some(path.clone())).writer()?;
+                wtr.write_record(["value", "path", "rows"])?;
+                Ok(wtr)
+            })
+            .transpose()?;
+
         let mut row = csv::ByteRecord::new();
+        let mut index: usize = 0;
 
         if self.flag_sorted {
-            let mut current: Option<(Vec<u8>, BoxedWriter)> = None;
+            let mut current: Option<Partition> = None;
 
             while rdr.read_byte_record(&mut row)? {
                 // Decide what file to put this in.
-                let column = &row[key_col];
+                let column = partition_key.get(index, &row)?;
                 let key = match self.flag_prefix_length {
                     // We exceed --prefix-length, so ignore the extra bytes.
                     Some(len) if len < column.len() => &column[0..len],
-                    _ => column,
+                    _ => &column[..],
                 };
 
+                index += 1;
+
                 match current {
-                    Some((ref k, _)) if k == key => {}
+                    Some(ref partition) if partition.key == key => {}
                     _ => {
-                        let mut wtr = gen.writer(&self.arg_outdir, key)?;
+                        let partition = gen.partition(&self.arg_outdir, key)?;
 
-                        if !rconfig.no_headers {
-                            wtr.write_record(&headers)?;
+                        if let Some(previous) = current.replace(partition) {
+                            previous.close(manifest.as_mut())?;
                         }
 
-                        current = Some((key.to_vec(), wtr));
+                        if !rconfig.no_headers {
+                            current.as_mut().unwrap().wtr.write_record(&headers)?;
+                        }
                     }
                 };
 
-                let wtr = &mut current.as_mut().unwrap().1;
+                let partition = current.as_mut().unwrap();
 
-                if self.flag_drop {
-                    wtr.write_record(&row.remove(key_col))?;
+                if let Some(key_col) = drop_col {
+                    partition.write_record(&row.remove(key_col))?;
                 } else {
-                    wtr.write_byte_record(&row)?;
+                    partition.write_record(&row)?;
                 }
             }
+
+            if let Some(partition) = current {
+                partition.close(manifest.as_mut())?;
+            }
         } else {
-            let mut writers: HashMap<Vec<u8>, BoxedWriter> = HashMap::new();
+            let mut writers: HashMap<Vec<u8>, usize> = HashMap::new();
+            let mut partitions: Vec<Partition> = Vec::new();
 
             while rdr.read_byte_record(&mut row)? {
                 // Decide what file to put this in.
-                let column = &row[key_col];
+                let column = partition_key.get(index, &row)?;
                 let key = match self.flag_prefix_length {
                     // We exceed --prefix-length, so ignore the extra bytes.
                     Some(len) if len < column.len() => &column[0..len],
-                    _ => column,
+                    _ => &column[..],
                 };
 
-                let mut entry = writers.entry(key.to_vec());
-                let wtr = match entry {
-                    Entry::Occupied(ref mut occupied) => occupied.get_mut(),
+                index += 1;
+
+                let partition = match writers.entry(key.to_vec()) {
+                    Entry::Occupied(occupied) => &mut partitions[*occupied.get()],
                     Entry::Vacant(vacant) => {
                         // We have a new key, so make a new writer.
-                        let mut wtr = gen.writer(&self.arg_outdir, key)?;
+                        let mut partition = gen.partition(&self.arg_outdir, key)?;
                         if !rconfig.no_headers {
-                            wtr.write_record(&headers)?;
+                            partition.wtr.write_record(&headers)?;
                         }
-                        vacant.insert(wtr)
+                        vacant.insert(partitions.len());
+                        partitions.push(partition);
+                        partitions.last_mut().unwrap()
                     }
                 };
 
-                if self.flag_drop {
-                    wtr.write_record(&row.remove(key_col))?;
+                if let Some(key_col) = drop_col {
+                    partition.write_record(&row.remove(key_col))?;
                 } else {
-                    wtr.write_byte_record(&row)?;
+                    partition.write_record(&row)?;
                 }
             }
+
+            for partition in partitions {
+                partition.close(manifest.as_mut())?;
+            }
+        }
+
+        if let Some(mut wtr) = manifest {
+            wtr.flush()?;
         }
 
         Ok(())
     }
 }
 
+enum PartitionKey {
+    Column(usize),
+    Expression(Program),
+}
+
+impl PartitionKey {
+    fn get<'a>(&self, index: usize, record: &'a csv::ByteRecord) -> CliResult<Cow<'a, [u8]>> {
+        Ok(match self {
+            Self::Column(key_col) => Cow::Borrowed(&record[*key_col]),
+            Self::Expression(program) => Cow::Owned(
+                program
+                    .run_with_record(index, record)?
+                    .serialize_as_bytes()
+                    .into_owned(),
+            ),
+        })
+    }
+}
+
 type BoxedWriter = csv::Writer<Box<dyn io::Write + 'static>>;
+type ManifestWriter = csv::Writer<Box<dyn io::Write + Send + 'static>>;
+
+/// A single partition, i.e. an output file and the number of rows written
+/// into it.
+struct Partition {
+    key: Vec<u8>,
+    path: String,
+    rows: usize,
+    wtr: BoxedWriter,
+}
+
+impl Partition {
+    fn write_record(&mut self, record: &csv::ByteRecord) -> csv::Result<()> {
+        self.rows += 1;
+        self.wtr.write_byte_record(record)
+    }
+
+    /// Flush the partition's writer and report it in the manifest, if any.
+    fn close(mut self, manifest: Option<&mut ManifestWriter>) -> CliResult<()> {
+        self.wtr.flush()?;
+
+        if let Some(manifest) = manifest {
+            manifest.write_record([
+                &self.key,
+                self.path.as_bytes(),
+                self.rows.to_string().as_bytes(),
+            ])?;
+        }
+
+        Ok(())
+    }
+}
 
 /// Generates unique filenames based on CSV values.
 struct WriterGenerator {
@@ -196,13 +319,25 @@ impl WriterGenerator {
         }
     }
 
-    /// Create a CSV writer for `key`.  Does not add headers.
-    fn writer<P>(&mut self, path: P, key: &[u8]) -> io::Result<BoxedWriter>
+    /// Create a partition, along with its CSV writer, for `key`.  Does not
+    /// add headers.
+    fn partition<P>(&mut self, path: P, key: &[u8]) -> io::Result<Partition>
     where
         P: AsRef<Path>,
     {
         let unique_value = self.unique_value(key);
-        self.template.writer(path.as_ref(), &unique_value)
+        let wtr = self.template.writer(path.as_ref(), &unique_value)?;
+
+        Ok(Partition {
+            key: key.to_vec(),
+            path: path
+                .as_ref()
+                .join(self.template.filename(&unique_value))
+                .display()
+                .to_string(),
+            rows: 0,
+            wtr,
+        })
     }
 
     /// Generate a unique value for `key`, suitable for use in a
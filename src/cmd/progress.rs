@@ -6,7 +6,7 @@ use std::time::Duration;
 use bytesize::MB;
 use indicatif::{HumanCount, ProgressBar, ProgressStyle};
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -129,7 +129,13 @@ Display a progress bar while reading the rows of a CSV file.
 
 The command will try and buffer some of the ingested file to find
 the total number of rows automatically. If you know the total
-beforehand, you can also use the --total flag.
+beforehand, you can also use the --total flag. Note that if the file
+has been indexed using `xan index`, the total will be read from the
+index instead.
+
+When the total is known, the bar will display a percentage and an
+ETA. Else it will fall back to a spinner displaying the number of rows
+read so far and the throughput.
 
 Usage:
     xan progress [options] [<input>]
@@ -153,6 +159,8 @@ Common options:
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -164,6 +172,7 @@ struct Args {
     flag_total: Option<u64>,
     flag_smooth: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_output: Option<String>,
     flag_no_headers: bool,
 }
@@ -207,15 +216,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let conf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
+    let mut total = args.flag_total;
+
+    if total.is_none() {
+        if let Some(idx) = conf.indexed()? {
+            total = Some(idx.count());
+        }
+    }
+
     let mut rdr = conf.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     conf.write_headers(&mut rdr, &mut wtr)?;
 
     let mut record = csv::ByteRecord::new();
-    let mut total = args.flag_total;
 
     let mut buffer: Vec<csv::ByteRecord> = Vec::new();
 
@@ -1,6 +1,6 @@
 use crate::config::Config;
 use crate::util;
-use crate::CliResult;
+use crate::{CliError, CliResult};
 
 static USAGE: &str = "
 Create a CSV file with one column representing a numerical range. This is mostly
@@ -13,18 +13,33 @@ the -c, --column-name flag.
 Note that like in most programming language, the end of the range is exclusive,
 but can be included with -i, --inclusive.
 
-Example:
+Start, end & step can be floats, in which case the range will be computed so
+that no floating point drift is accumulated along the way (e.g. you will get
+0.3 and not 0.30000000000000004).
+
+Instead of a step, you can also give -C, --count to generate exactly this
+number of evenly spaced values between start & end.
+
+Examples:
 
     Creating a range of urls files by piping `range` into `transform`:
         $ xan range 100 | xan select -e '\"https://somewebsite.com?id=\".n as url'
 
+    Creating a range of floats:
+        $ xan range -i 1 --step 0.1
+
+    Creating 5 evenly spaced values between 0 and 1:
+        $ xan range -i 1 --count 5
+
 Usage:
     xan range [options] <end> [<input>]
     xan range --help
 
 range options:
     -s, --start <n>           Start of the range. [default: 0]
-    --step <n>                Step of the range. [default: 1]
+    --step <n>                Step of the range. Defaults to 1.
+    -C, --count <n>           Number of evenly spaced values to generate, instead
+                              of relying on a step.
     -c, --column-name <name>  Name of the column containing the range.
                               [default: n]
     -i, --inclusive           Include the end bound.
@@ -36,17 +51,41 @@ Common options:
 
 #[derive(Deserialize)]
 struct Args {
-    arg_end: usize,
+    arg_end: String,
     flag_output: Option<String>,
-    flag_start: usize,
-    flag_step: usize,
+    flag_start: String,
+    flag_step: Option<String>,
+    flag_count: Option<usize>,
     flag_column_name: String,
     flag_inclusive: bool,
 }
 
+fn parse_number(string: &str) -> CliResult<f64> {
+    string
+        .parse::<f64>()
+        .map_err(|_| CliError::Other(format!("could not parse \"{}\" as a number!", string)))
+}
+
+fn count_decimals(string: &str) -> u32 {
+    string
+        .split_once('.')
+        .map(|(_, decimals)| decimals.len() as u32)
+        .unwrap_or(0)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    if args.flag_count.is_some() && args.flag_step.is_some() {
+        Err("--step cannot be used with -C, --count!")?;
+    }
+
+    let step_string = args.flag_step.as_deref().unwrap_or("1");
+
+    let start = parse_number(&args.flag_start)?;
+    let end = parse_number(&args.arg_end)?;
+    let step = parse_number(step_string)?;
+
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     let mut record = csv::ByteRecord::new();
@@ -54,19 +93,72 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     wtr.write_byte_record(&record)?;
 
-    let mut i = args.flag_start;
+    macro_rules! write_value {
+        ($value:expr) => {
+            record.clear();
+            record.push_field($value.to_string().as_bytes());
+            wtr.write_byte_record(&record)?;
+            wtr.flush()?;
+        };
+    }
+
+    if let Some(count) = args.flag_count {
+        let divisor = if args.flag_inclusive {
+            count.saturating_sub(1).max(1)
+        } else {
+            count
+        } as f64;
+
+        for k in 0..count {
+            // NOTE: dividing last so that the result is correctly rounded
+            write_value!(start + (end - start) * k as f64 / divisor);
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    if step == 0.0 {
+        Err("--step cannot be 0!")?;
+    }
+
+    // NOTE: to avoid float drift, we iterate over integers scaled using the
+    // max number of decimals of the given arguments.
+    let decimals = [&args.flag_start, &args.arg_end, step_string]
+        .iter()
+        .map(|string| count_decimals(string))
+        .max()
+        .unwrap();
+
+    let scale = 10i64
+        .checked_pow(decimals)
+        .ok_or("too many decimals in start, end or step!")?;
+    let scaled = |n: f64| (n * scale as f64).round() as i64;
+
+    let (scaled_end, scaled_step) = (scaled(end), scaled(step));
+
+    // NOTE: this can happen when the step is written using exponent notation,
+    // e.g. 1e-5, since its decimals cannot be counted.
+    if scaled_step == 0 {
+        Err(format!(
+            "--step {} is too small to be used with given start & end! Try writing it without exponent notation.",
+            step_string
+        ))?;
+    }
+    let mut i = scaled(start);
 
-    while if args.flag_inclusive {
-        i <= args.arg_end
-    } else {
-        i < args.arg_end
+    while match (scaled_step > 0, args.flag_inclusive) {
+        (true, true) => i <= scaled_end,
+        (true, false) => i < scaled_end,
+        (false, true) => i >= scaled_end,
+        (false, false) => i > scaled_end,
     } {
-        record.clear();
-        record.push_field((i).to_string().as_bytes());
-        wtr.write_byte_record(&record)?;
-        wtr.flush()?;
+        if decimals == 0 {
+            write_value!(i);
+        } else {
+            write_value!(i as f64 / scale as f64);
+        }
 
-        i += args.flag_step;
+        i += scaled_step;
     }
 
     Ok(wtr.flush()?)
@@ -3,7 +3,7 @@ use std::str;
 
 use pariter::IteratorExt;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
@@ -119,6 +119,8 @@ Common options:
                                 sliced, etc.)
     -d, --delimiter <arg>       The field delimiter for reading CSV data.
                                 Must be a single character.
+    --comment <char>            Skip lines starting with <char> when reading CSV
+                                data.
 ";
 
 #[derive(Deserialize)]
@@ -132,6 +134,7 @@ struct Args {
     flag_no_headers: bool,
     flag_ignore_case: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_prefix_left: Option<String>,
     flag_prefix_right: Option<String>,
     flag_parallel: bool,
@@ -151,6 +154,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let patterns_rconf = Config::new(&Some(args.arg_patterns_input.clone()))
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_pattern_col);
 
@@ -166,6 +170,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconf = Config::new(&Some(args.arg_input.clone()))
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_columns);
 
@@ -1,6 +1,6 @@
 use std::io;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -18,6 +18,10 @@ the possibility to randomly access data, e.g. a file on disk, but not a piped st
 Others sources need to be read using --in-memory flag and will need to load full
 data into memory unfortunately.
 
+The command is also able to reverse the order of columns instead, using
+the --columns flag, in which case data is streamed and no memory is consumed.
+Use both --columns & --rows to reverse both the columns and the rows.
+
 Usage:
     xan reverse [options] [<input>]
 
@@ -25,6 +29,11 @@ reverse options:
     -m, --in-memory        Load all CSV data in memory before reversing it. Can
                            be useful for streamed inputs such as stdin but at the
                            expense of memory.
+    --columns              Reverse the order of columns, including in the header row,
+                           instead of the order of rows.
+    --rows                 Reverse the order of rows. This is the default, unless
+                           reversing columns, in which case giving this flag will
+                           reverse both columns and rows.
 
 Common options:
     -h, --help             Display this message
@@ -35,6 +44,8 @@ Common options:
                            appear as the header row in the output.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -43,7 +54,14 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_in_memory: bool,
+    flag_columns: bool,
+    flag_rows: bool,
+}
+
+fn reverse_columns(record: &csv::ByteRecord) -> csv::ByteRecord {
+    record.iter().rev().collect()
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -51,8 +69,17 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconfig = &mut Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(true);
 
+    if args.flag_columns && !args.flag_rows {
+        if args.flag_in_memory {
+            Err("-m/--in-memory is pointless when only reversing columns!")?;
+        }
+
+        return run_on_columns_only(rconfig, args);
+    }
+
     if args.flag_in_memory {
         run_without_memory_efficiency(rconfig, args)
     } else {
@@ -89,15 +116,29 @@ fn run_with_memory_efficiency(rconfig: &mut Config, args: Args) -> CliResult<()>
 
             if !args.flag_no_headers && headers_size > 0 {
                 let headers = config_csv_reader.byte_headers()?;
-                wtr.write_byte_record(headers)?;
+
+                if args.flag_columns {
+                    wtr.write_byte_record(&reverse_columns(headers))?;
+                } else {
+                    wtr.write_byte_record(headers)?;
+                }
             }
 
             for record in reverse_csv_reader.byte_records().flatten() {
-                let new_record: Vec<Vec<u8>> = record
-                    .iter()
-                    .rev()
-                    .map(|b| b.iter().rev().copied().collect())
-                    .collect();
+                // NOTE: fields are read backwards, so they are already in
+                // reverse order if we also need to reverse the columns.
+                let new_record: Vec<Vec<u8>> = if args.flag_columns {
+                    record
+                        .iter()
+                        .map(|b| b.iter().rev().copied().collect())
+                        .collect()
+                } else {
+                    record
+                        .iter()
+                        .rev()
+                        .map(|b| b.iter().rev().copied().collect())
+                        .collect()
+                };
 
                 wtr.write_record(new_record)?;
             }
@@ -115,10 +156,48 @@ fn run_without_memory_efficiency(rconfig: &mut Config, args: Args) -> CliResult<
     all.reverse();
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
-    rconfig.write_headers(&mut reader, &mut wtr)?;
+
+    if !args.flag_no_headers {
+        let headers = reader.byte_headers()?;
+
+        if !headers.is_empty() {
+            if args.flag_columns {
+                wtr.write_byte_record(&reverse_columns(headers))?;
+            } else {
+                wtr.write_byte_record(headers)?;
+            }
+        }
+    }
 
     for r in all.into_iter() {
-        wtr.write_byte_record(&r)?;
+        if args.flag_columns {
+            wtr.write_byte_record(&reverse_columns(&r))?;
+        } else {
+            wtr.write_byte_record(&r)?;
+        }
+    }
+
+    Ok(wtr.flush()?)
+}
+
+fn run_on_columns_only(rconfig: &mut Config, args: Args) -> CliResult<()> {
+    rconfig.no_headers = args.flag_no_headers;
+
+    let mut reader = rconfig.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+
+    if !args.flag_no_headers {
+        let headers = reader.byte_headers()?;
+
+        if !headers.is_empty() {
+            wtr.write_byte_record(&reverse_columns(headers))?;
+        }
+    }
+
+    let mut record = csv::ByteRecord::new();
+
+    while reader.read_byte_record(&mut record)? {
+        wtr.write_byte_record(&reverse_columns(&record))?;
     }
 
     Ok(wtr.flush()?)
@@ -6,7 +6,7 @@ use rand::seq::SliceRandom;
 use rand::Rng;
 
 use crate::collections::ClusteredInsertHashmap;
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::index::Indexed;
 use crate::select::{SelectColumns, Selection};
 use crate::util;
@@ -33,14 +33,26 @@ limit the number of records visited, use the 'xan slice' command to pipe into
 The command can also extract a biased sample based on a numeric column representing
 row weights, using the --weight flag.
 
+Finally, instead of a fixed sample size, the --fraction flag can be used to
+keep each row independently with the given probability. The resulting sample
+size is therefore only approximate, but the command will stream the file using
+constant memory:
+
+    $ xan sample --fraction 0.1 --seed 42 huge.csv
+
 Usage:
     xan sample [options] <sample-size> [<input>]
+    xan sample [options] --fraction <p> [<input>]
     xan sample --help
 
 sample options:
-    --seed <number>        RNG seed.
+    --seed <number>        RNG seed. Will default to the XAN_SEED env
+                           variable if set.
     -w, --weight <column>  Column containing weights to bias the sample.
     -g, --groupby <cols>   Return a sample per group.
+    --fraction <p>         Keep each row with probability <p>, which must be
+                           in (0, 1]. Cannot be used with -w/--weight nor with
+                           grouping using -g/--groupby.
 
 Common options:
     -h, --help             Display this message
@@ -51,31 +63,77 @@ Common options:
                            in the output.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
-    arg_sample_size: u64,
+    arg_sample_size: Option<String>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_seed: Option<usize>,
     flag_weight: Option<SelectColumns>,
     flag_groupby: Option<SelectColumns>,
+    flag_fraction: Option<f64>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    // NOTE: when using --fraction, docopt will understand the input path as
+    // being the sample size.
+    if args.flag_fraction.is_some() && args.arg_sample_size.is_some() {
+        if args.arg_input.is_some() {
+            Err("cannot give both a <sample-size> and --fraction!")?;
+        }
+
+        args.arg_input = args.arg_sample_size.take();
+    }
+
     let mut rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
     if let Some(weight_column_selection) = args.flag_weight.clone() {
         rconfig = rconfig.select(weight_column_selection);
     }
 
-    let sample_size = args.arg_sample_size;
+    if let Some(fraction) = args.flag_fraction {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            Err("--fraction must be in (0, 1]!")?;
+        }
+
+        if args.flag_weight.is_some() || args.flag_groupby.is_some() {
+            Err("--fraction cannot be used with -w/--weight nor -g/--groupby!")?;
+        }
+
+        let mut rdr = rconfig.reader()?;
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+        rconfig.write_headers(&mut rdr, &mut wtr)?;
+
+        let mut rng = util::acquire_rng(args.flag_seed);
+        let mut record = csv::ByteRecord::new();
+
+        while rdr.read_byte_record(&mut record)? {
+            if rng.random::<f64>() < fraction {
+                wtr.write_byte_record(&record)?;
+            }
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    let sample_size = args
+        .arg_sample_size
+        .as_ref()
+        .unwrap()
+        .parse::<u64>()
+        .map_err(|_| "<sample-size> should be a positive integer!")?;
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let sampled = match rconfig.indexed()? {
@@ -1,11 +1,13 @@
 use std::collections::HashSet;
+use std::io::{self, IsTerminal};
 use std::num::NonZeroUsize;
 
 use aho_corasick::AhoCorasick;
 use bstr::ByteSlice;
+use colored::Colorize;
 use regex::bytes::{RegexBuilder, RegexSetBuilder};
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util;
 use crate::CliError;
@@ -105,6 +107,69 @@ impl Matcher {
             }
         }
     }
+
+    // NOTE: regex sets cannot report match spans, and lowercasing may change
+    // the byte length of some cells, in which case no span is returned.
+    fn spans(&self, cell: &[u8]) -> Vec<(usize, usize)> {
+        match self {
+            Self::Empty | Self::NonEmpty | Self::ManyRegex(_) => vec![],
+            Self::Substring(pattern, case_insensitive) => {
+                if *case_insensitive {
+                    let lowercased = cell.to_lowercase();
+
+                    if lowercased.len() != cell.len() {
+                        return vec![];
+                    }
+
+                    pattern
+                        .find_iter(&lowercased)
+                        .map(|m| (m.start(), m.end()))
+                        .collect()
+                } else {
+                    pattern
+                        .find_iter(cell)
+                        .map(|m| (m.start(), m.end()))
+                        .collect()
+                }
+            }
+            Self::Regex(pattern) => pattern
+                .find_iter(cell)
+                .map(|m| (m.start(), m.end()))
+                .collect(),
+            Self::Exact(_, _) | Self::ManyExact(_, _) => {
+                if self.is_match(cell) {
+                    vec![(0, cell.len())]
+                } else {
+                    vec![]
+                }
+            }
+        }
+    }
+
+    fn highlight(&self, cell: &[u8]) -> Vec<u8> {
+        let mut highlighted = Vec::with_capacity(cell.len());
+        let mut last: usize = 0;
+
+        for (start, end) in self.spans(cell) {
+            if start == end {
+                continue;
+            }
+
+            highlighted.extend_from_slice(&cell[last..start]);
+            highlighted.extend_from_slice(
+                String::from_utf8_lossy(&cell[start..end])
+                    .red()
+                    .bold()
+                    .to_string()
+                    .as_bytes(),
+            );
+
+            last = end;
+        }
+
+        highlighted.extend_from_slice(&cell[last..]);
+        highlighted
+    }
 }
 
 // NOTE: a -U, --unbuffered flag that flushes on each match does not solve
@@ -136,6 +201,26 @@ To restrict the columns that will be searched you can use the -s, --select flag.
 
 All search modes can also be case-insensitive using -i, --ignore-case.
 
+By default, each selected cell is searched independently. But you can also use
+the --join flag to concatenate the selected cells of each row, using the given
+separator, before searching them, so that a pattern can span multiple columns:
+
+    $ xan search -r 'John Smith' -s name,surname --join ' ' file.csv
+
+When using a regular expression, note that \".\" does not match newlines by
+default. The --dotall flag can be used to change this, which can be useful
+when searching multiline cells. Note finally that cells are always searched
+as raw bytes, so that cells containing invalid UTF-8 will never cause errors
+(although such bytes will never be matched by \".\").
+
+When printing to a terminal, the --highlight flag can be used to color the
+matched portions of the selected cells, which is handy when reviewing results:
+
+    $ xan search -r '\\bfran[cç]' --highlight file.csv | less -R
+
+Note that highlighting is disabled when the output is not a terminal, or when
+the NO_COLOR environment variable is set.
+
 Finally, this command is also able to search for multiple patterns at once.
 To do so, you must give a text file with one pattern per line to the --patterns
 flag, or a CSV file containing a column of to indicate using --pattern-column.
@@ -175,6 +260,11 @@ search options:
     --pattern-column <name>  When given a column name, --patterns file will be considered a CSV
                              and patterns to search will be extracted from the given column.
     -i, --ignore-case        Case insensitive search.
+    --dotall                 Allow \".\" to match newlines when using -r, --regex.
+    --join <sep>             Search the concatenation of the selected cells of each
+                             row, joined by the given separator, instead of
+                             searching each cell independently. Does not work
+                             with --all, --empty or --non-empty.
     -s, --select <arg>       Select the columns to search. See 'xan select -h'
                              for the full syntax.
     -v, --invert-match       Select only rows that did not match
@@ -185,6 +275,9 @@ search options:
                              count the total number of pattern matches per
                              row and report it in a new column with given name.
                              Does not work with -v/--invert-match.
+    --highlight              Highlight matched portions of the selected cells
+                             when printing to a terminal. Has no effect when
+                             using -r/--regex with --patterns.
     -l, --limit <n>          Maximum of number rows to return. Useful to avoid downstream
                              buffering some times (e.g. when searching for very few
                              rows in a big file before piping to `view` or `flatten`).
@@ -197,6 +290,8 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -207,15 +302,19 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_invert_match: bool,
     flag_all: bool,
     flag_ignore_case: bool,
+    flag_dotall: bool,
+    flag_join: Option<String>,
     flag_empty: bool,
     flag_non_empty: bool,
     flag_exact: bool,
     flag_regex: bool,
     flag_count: Option<String>,
     flag_limit: Option<NonZeroUsize>,
+    flag_highlight: bool,
     flag_patterns: Option<String>,
     flag_patterns_column: Option<SelectColumns>,
 }
@@ -244,6 +343,7 @@ impl Args {
                     Matcher::Regex(
                         RegexBuilder::new(pattern)
                             .case_insensitive(self.flag_ignore_case)
+                            .dot_matches_new_line(self.flag_dotall)
                             .build()?,
                     )
                 } else {
@@ -260,6 +360,7 @@ impl Args {
             Some(_) => {
                 let patterns = Config::new(&self.flag_patterns)
                     .delimiter(self.flag_delimiter)
+                    .comment(self.flag_comment)
                     .lines(&self.flag_patterns_column)?;
 
                 let mut set: HashSet<Vec<u8>> = HashSet::new();
@@ -285,6 +386,7 @@ impl Args {
                     Matcher::ManyRegex(
                         RegexSetBuilder::new(&list)
                             .case_insensitive(self.flag_ignore_case)
+                            .dot_matches_new_line(self.flag_dotall)
                             .build()?,
                     )
                 } else {
@@ -311,9 +413,27 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         Err("-c/--count does not work with -v/--invert-match!")?;
     }
 
+    if args.flag_join.is_some() && (args.flag_all || args.flag_empty || args.flag_non_empty) {
+        Err("--join does not work with -A/--all, -E/--empty or -N/--non-empty!")?;
+    }
+
+    if args.flag_dotall && !args.flag_regex {
+        Err("--dotall only works with -r/--regex!")?;
+    }
+
+    let highlight = args.flag_highlight
+        && args.flag_output.is_none()
+        && io::stdout().is_terminal()
+        && std::env::var_os("NO_COLOR").is_none();
+
+    if highlight {
+        colored::control::set_override(true);
+    }
+
     let matcher = args.build_matcher()?;
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
 
@@ -332,13 +452,30 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     }
 
     let mut record = csv::ByteRecord::new();
+    let mut joined: Vec<u8> = Vec::new();
     let mut i: usize = 0;
 
     while rdr.read_byte_record(&mut record)? {
         let mut is_match: bool = false;
 
+        if let Some(sep) = &args.flag_join {
+            joined.clear();
+
+            for (j, cell) in sel.select(&record).enumerate() {
+                if j > 0 {
+                    joined.extend_from_slice(sep.as_bytes());
+                }
+
+                joined.extend_from_slice(cell);
+            }
+        }
+
         if args.flag_count.is_some() {
-            let count: usize = sel.select(&record).map(|cell| matcher.count(cell)).sum();
+            let count: usize = if args.flag_join.is_some() {
+                matcher.count(&joined)
+            } else {
+                sel.select(&record).map(|cell| matcher.count(cell)).sum()
+            };
 
             if count > 0 {
                 is_match = true;
@@ -347,7 +484,9 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             record.push_field(count.to_string().as_bytes());
             wtr.write_byte_record(&record)?;
         } else {
-            is_match = if args.flag_all {
+            is_match = if args.flag_join.is_some() {
+                matcher.is_match(&joined)
+            } else if args.flag_all {
                 sel.select(&record).all(|cell| matcher.is_match(cell))
             } else {
                 sel.select(&record).any(|cell| matcher.is_match(cell))
@@ -358,7 +497,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             }
 
             if is_match {
-                wtr.write_byte_record(&record)?;
+                if highlight && !args.flag_invert_match {
+                    let highlighted = record
+                        .iter()
+                        .enumerate()
+                        .map(|(j, cell)| {
+                            if sel.contains(j) {
+                                matcher.highlight(cell)
+                            } else {
+                                cell.to_vec()
+                            }
+                        })
+                        .collect::<csv::ByteRecord>();
+
+                    wtr.write_byte_record(&highlighted)?;
+                } else {
+                    wtr.write_byte_record(&record)?;
+                }
             }
         }
 
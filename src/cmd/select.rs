@@ -1,5 +1,7 @@
-use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
+use regex::bytes::Regex;
+
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
 use crate::util;
 use crate::CliResult;
 
@@ -85,6 +87,34 @@ Examples:
   Select all columns ending by \"_count\":
     $ xan select '*_count'
 
+# Selecting by regex
+
+Using the -r/--regex flag, the selection will instead be interpreted as
+a regular expression and all the columns whose name matches it will be
+selected, in header order. The -v/--invert flag can be used to select
+the columns that do not match instead.
+
+  Select all columns whose name starts with \"value_\":
+    $ xan select -r '^value_'
+
+  Drop all columns whose name starts with an underscore:
+    $ xan select -rv '^_'
+
+Note that the command will error if the regular expression does not match
+any column, unless the --allow-empty flag is given.
+
+# Selecting by content
+
+Using the --non-empty flag, only the columns containing at least one
+non-empty value will be kept. Using the --drop-constant flag, the columns
+where every value is identical will be dropped. Both flags can be combined
+and the names of the dropped columns will be reported on stderr.
+
+Note that those flags need to read the file twice and therefore cannot
+work on stdin:
+
+  $ xan select --non-empty --drop-constant data.csv
+
 # Evaluating a expression
 
 Using a SQLish syntax that is the same as for the `map`, `agg`, `filter` etc.
@@ -104,6 +134,8 @@ If you want to list available functions, use the --functions flag.
 
 Usage:
     xan select [options] [--] <selection> [<input>]
+    xan select [options] --non-empty [<input>]
+    xan select [options] --drop-constant [<input>]
     xan select --help
     xan select --cheatsheet
     xan select --functions
@@ -118,6 +150,14 @@ select options:
                              - \"ignore\": ignore row altogether
                              - \"log\": print error to stderr
                            [default: panic].
+    -r, --regex            Interpret the selection as a regular expression
+                           matched against column names.
+    -v, --invert           When using -r/--regex, select the columns that
+                           do not match instead.
+    --allow-empty          When using -r/--regex, do not error if no column
+                           matched.
+    --non-empty            Only keep columns having at least one non-empty value.
+    --drop-constant        Drop columns where every value is identical.
 
 Common options:
     -h, --help             Display this message
@@ -127,6 +167,8 @@ Common options:
                            sliced, etc.)
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -137,14 +179,20 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_cheatsheet: bool,
     flag_functions: bool,
     flag_evaluate: bool,
     flag_errors: String,
+    flag_regex: bool,
+    flag_invert: bool,
+    flag_allow_empty: bool,
+    flag_non_empty: bool,
+    flag_drop_constant: bool,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
 
     if args.flag_cheatsheet {
         println!("{}", get_moonblade_cheatsheet());
@@ -156,10 +204,45 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         return Ok(());
     }
 
+    if args.flag_regex && args.flag_evaluate {
+        Err("-r/--regex cannot be used with -e/--evaluate!")?;
+    }
+
+    if (args.flag_invert || args.flag_allow_empty) && !args.flag_regex {
+        Err("-v/--invert & --allow-empty only work with -r/--regex!")?;
+    }
+
+    if args.flag_regex && args.flag_no_headers {
+        Err("-r/--regex cannot be used with -n/--no-headers!")?;
+    }
+
+    let by_contents = args.flag_non_empty || args.flag_drop_constant;
+
+    if by_contents {
+        if args.flag_regex || args.flag_evaluate || args.flag_append {
+            Err("--non-empty & --drop-constant cannot be used with -r, -e nor -A!")?;
+        }
+
+        // NOTE: when using --non-empty or --drop-constant, docopt will
+        // understand the input path as being the selection.
+        if !args.arg_selection.is_empty() {
+            if args.arg_input.is_some() {
+                Err("cannot give a <selection> with --non-empty or --drop-constant!")?;
+            }
+
+            args.arg_input = Some(std::mem::take(&mut args.arg_selection));
+        }
+    }
+
     let mut rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
 
+    if by_contents && rconfig.is_std() {
+        Err("--non-empty & --drop-constant cannot work on stdin!")?;
+    }
+
     let mut rdr = rconfig.reader()?;
     let mut wtr = Config::new(&args.flag_output).writer()?;
     let mut record = csv::ByteRecord::new();
@@ -167,10 +250,33 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let headers = rdr.byte_headers()?.clone();
 
     if !args.flag_evaluate {
-        let parsed_selection = SelectColumns::parse(&args.arg_selection)?;
-        rconfig = rconfig.select(parsed_selection);
+        let sel = if by_contents {
+            select_by_contents(&args, &rconfig, &headers)?
+        } else if args.flag_regex {
+            let pattern = Regex::new(&args.arg_selection)?;
+
+            let sel = Selection::from_indices(
+                headers
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(i, h)| (pattern.is_match(h) != args.flag_invert).then_some(i))
+                    .collect(),
+            );
+
+            if sel.is_empty() && !args.flag_allow_empty {
+                Err(format!(
+                    "regex \"{}\" did not select any column! Use --allow-empty to allow this.",
+                    args.arg_selection
+                ))?;
+            }
+
+            sel
+        } else {
+            let parsed_selection = SelectColumns::parse(&args.arg_selection)?;
+            rconfig = rconfig.select(parsed_selection);
 
-        let sel = rconfig.selection(&headers)?;
+            rconfig.selection(&headers)?
+        };
 
         if !rconfig.no_headers {
             let headers_to_write = sel.select(&headers);
@@ -216,3 +322,58 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     Ok(wtr.flush()?)
 }
+
+fn select_by_contents(
+    args: &Args,
+    rconfig: &Config,
+    headers: &csv::ByteRecord,
+) -> CliResult<Selection> {
+    let mut rdr = rconfig.reader()?;
+    let mut record = csv::ByteRecord::new();
+
+    let mut non_empty = vec![false; headers.len()];
+    let mut constant = vec![true; headers.len()];
+    let mut first_record: Option<csv::ByteRecord> = None;
+
+    while rdr.read_byte_record(&mut record)? {
+        for (i, cell) in record.iter().enumerate() {
+            if !cell.is_empty() {
+                non_empty[i] = true;
+            }
+        }
+
+        match &first_record {
+            None => {
+                first_record = Some(record.clone());
+            }
+            Some(first) => {
+                for (i, cell) in record.iter().enumerate() {
+                    if cell != &first[i] {
+                        constant[i] = false;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut kept = Vec::new();
+    let mut dropped = Vec::new();
+
+    for (i, header) in headers.iter().enumerate() {
+        if (args.flag_non_empty && !non_empty[i]) || (args.flag_drop_constant && constant[i]) {
+            dropped.push(if rconfig.no_headers {
+                i.to_string()
+            } else {
+                String::from_utf8_lossy(header).into_owned()
+            });
+        } else {
+            kept.push(i);
+        }
+    }
+
+    if !dropped.is_empty() {
+        eprintln!("Dropped columns: {}", dropped.join(", "));
+    }
+
+    Ok(Selection::from_indices(kept))
+}
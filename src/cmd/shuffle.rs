@@ -1,7 +1,8 @@
 use rand::seq::SliceRandom;
+use rand::Rng;
 use std::io::SeekFrom;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -16,15 +17,25 @@ unless --in-memory is set.
 Also, since this command needs random access in the input file, it
 does not work with stdin or piping (unless --in-memory) is set.
 
+If you only need a shuffled sample of the file, use -l, --limit to only
+keep N rows, chosen uniformly using reservoir sampling, before shuffling them.
+This only requires memory proportional to N and works with stdin.
+
+Finally, note that given a same --seed, the command will always emit the same
+permutation of a same file.
+
 Usage:
     xan shuffle [options] [<input>]
     xan shuffle --help
 
 shuffle options:
-    --seed <number>        RNG seed.
+    --seed <number>        RNG seed. Will default to the XAN_SEED env
+                           variable if set.
     -m, --in-memory        Load all CSV data in memory before shuffling it. Can
                            be useful for streamed inputs such as stdin but of
                            course costs more memory.
+    -l, --limit <n>        Only emit a shuffled sample of at most n rows, using
+                           memory proportional to n.
 
 Common options:
     -h, --help             Display this message
@@ -33,6 +44,8 @@ Common options:
                            the count.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -41,13 +54,16 @@ struct Args {
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_seed: Option<usize>,
     flag_in_memory: bool,
+    flag_limit: Option<usize>,
 }
 
 fn run_random_access(args: Args) -> CliResult<()> {
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
     let wconf = Config::new(&args.flag_output);
 
@@ -110,6 +126,7 @@ fn run_random_access(args: Args) -> CliResult<()> {
 fn run_in_memory(args: Args) -> CliResult<()> {
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers);
     let wconf = Config::new(&args.flag_output);
 
@@ -135,10 +152,52 @@ fn run_in_memory(args: Args) -> CliResult<()> {
     Ok(wtr.flush()?)
 }
 
+fn run_reservoir(args: Args, limit: usize) -> CliResult<()> {
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
+        .no_headers(args.flag_no_headers);
+    let wconf = Config::new(&args.flag_output);
+
+    // Seeding rng
+    let mut rng = util::acquire_rng(args.flag_seed);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = wconf.writer()?;
+    rconf.write_headers(&mut rdr, &mut wtr)?;
+
+    let mut reservoir: Vec<csv::ByteRecord> = Vec::with_capacity(limit);
+
+    for (i, result) in rdr.into_byte_records().enumerate() {
+        let record = result?;
+
+        if i < limit {
+            reservoir.push(record);
+            continue;
+        }
+
+        let random = rng.random_range(0..i + 1);
+
+        if random < limit {
+            reservoir[random] = record;
+        }
+    }
+
+    reservoir.shuffle(&mut rng);
+
+    for record in reservoir {
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
-    if args.flag_in_memory {
+    if let Some(limit) = args.flag_limit {
+        run_reservoir(args, limit)
+    } else if args.flag_in_memory {
         run_in_memory(args)
     } else {
         run_random_access(args)
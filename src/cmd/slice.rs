@@ -1,7 +1,8 @@
+use std::collections::HashMap;
 use std::fs;
 use std::io::{Read, SeekFrom};
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::index::Indexed;
 use crate::util;
 use crate::CliResult;
@@ -39,6 +40,13 @@ slice options:
                            You can also provide multiples indices separated by
                            commas, e.g. \"1,4,67,89\". Note that selected records
                            will be emitted in file order.
+    -I, --indices <list>   Slice a list of records and/or inclusive ranges of
+                           records, separated by commas, e.g. \"0,5,10-12,99\".
+                           Contrary to -i/--index, selected records will be
+                           emitted in the given order, and an index requested
+                           multiple times will emit its record multiple times.
+                           Without an index, records will be buffered in memory
+                           when they are not requested in file order.
     -B, --byte-offset <b>  Byte offset to seek to in the sliced file. This can
                            be useful to access a particular slice of records in
                            constant time, without needing to read preceding bytes.
@@ -53,6 +61,8 @@ Common options:
                            appear in the output as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -63,10 +73,12 @@ struct Args {
     flag_end: Option<usize>,
     flag_len: Option<usize>,
     flag_index: Option<String>,
+    flag_indices: Option<String>,
     flag_byte_offset: Option<usize>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 impl Args {
@@ -81,6 +93,38 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
     args.resolve();
 
+    if args.flag_indices.is_some() {
+        if args.flag_start.is_some()
+            || args.flag_end.is_some()
+            || args.flag_len.is_some()
+            || args.flag_index.is_some()
+        {
+            Err("-I/--indices cannot be used with --start, --end, --len or --index")?;
+        }
+
+        return match args.rconfig().indexed()? {
+            None => {
+                let rconf = args.rconfig();
+
+                if let Some(offset) = args.flag_byte_offset {
+                    let inner = rconf.io_reader_for_random_access()?;
+                    let mut rdr = rconf.csv_reader_from_reader(inner);
+
+                    let mut pos = csv::Position::new();
+                    pos.set_byte(offset as u64);
+
+                    rdr.seek_raw(SeekFrom::Start(offset as u64), pos)?;
+
+                    args.no_index_spans(rdr)
+                } else {
+                    let rdr = rconf.reader()?;
+                    args.no_index_spans(rdr)
+                }
+            }
+            Some(idx) => args.with_index_spans(idx),
+        };
+    }
+
     match &args.flag_index {
         Some(indices) if indices.contains(',') => {
             return match args.rconfig().indexed()? {
@@ -220,6 +264,95 @@ impl Args {
         Ok(wtr.flush()?)
     }
 
+    fn no_index_spans<R: Read>(&self, mut rdr: csv::Reader<R>) -> CliResult<()> {
+        let mut wtr = self.wconfig().writer()?;
+        self.rconfig().write_headers(&mut rdr, &mut wtr)?;
+
+        let spans = self.spans()?;
+
+        let last = match spans.iter().map(|(_, end)| *end).max() {
+            None => return Ok(wtr.flush()?),
+            Some(end) => end,
+        };
+
+        let mut record = csv::ByteRecord::new();
+        let mut i: usize = 0;
+
+        // Fast path: spans are requested in file order and do not overlap,
+        // so we can stream the records without buffering them
+        if spans.windows(2).all(|w| w[0].1 <= w[1].0) {
+            let mut spans_iter = spans.iter().peekable();
+
+            while rdr.read_byte_record(&mut record)? {
+                while let Some((_, end)) = spans_iter.peek() {
+                    if i >= *end {
+                        spans_iter.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                match spans_iter.peek() {
+                    None => break,
+                    Some((start, _)) => {
+                        if i >= *start {
+                            wtr.write_byte_record(&record)?;
+                        }
+                    }
+                }
+
+                i += 1;
+            }
+
+            return Ok(wtr.flush()?);
+        }
+
+        let mut buffer: HashMap<usize, csv::ByteRecord> = HashMap::new();
+
+        while i < last && rdr.read_byte_record(&mut record)? {
+            if spans.iter().any(|(start, end)| (*start..*end).contains(&i)) {
+                buffer.insert(i, record.clone());
+            }
+
+            i += 1;
+        }
+
+        for (start, end) in spans {
+            for j in start..end {
+                if let Some(r) = buffer.get(&j) {
+                    wtr.write_byte_record(r)?;
+                }
+            }
+        }
+
+        Ok(wtr.flush()?)
+    }
+
+    fn with_index_spans(&self, mut idx: Indexed<fs::File, fs::File>) -> CliResult<()> {
+        if self.flag_byte_offset.is_some() {
+            Err("-B/--byte-offset is pointless with indexed files!")?;
+        }
+
+        let mut wtr = self.wconfig().writer()?;
+        self.rconfig().write_headers(&mut *idx, &mut wtr)?;
+
+        let count = idx.count();
+
+        for (start, end) in self.spans()? {
+            if start as u64 >= count {
+                continue;
+            }
+
+            idx.seek(start as u64)?;
+
+            for r in idx.byte_records().take(end - start) {
+                wtr.write_byte_record(&r?)?;
+            }
+        }
+
+        Ok(wtr.flush()?)
+    }
+
     fn range(&self) -> Result<(usize, usize), String> {
         let index: Option<usize> = self
             .flag_index
@@ -249,9 +382,45 @@ impl Args {
             })
     }
 
+    // NOTE: spans are half-open intervals, kept in the order given by the user
+    fn spans(&self) -> Result<Vec<(usize, usize)>, String> {
+        let parse = |string: &str| {
+            string
+                .trim()
+                .parse::<usize>()
+                .map_err(|_| format!("could not parse \"{}\" in -I/--indices!", string))
+        };
+
+        self.flag_indices
+            .as_ref()
+            .unwrap()
+            .split(',')
+            .map(|part| match part.split_once('-') {
+                None => {
+                    let i = parse(part)?;
+                    Ok((i, i + 1))
+                }
+                Some((start, end)) => {
+                    let start = parse(start)?;
+                    let end = parse(end)?;
+
+                    if start > end {
+                        return Err(format!(
+                            "invalid range \"{}\" in -I/--indices: end is less than start!",
+                            part
+                        ));
+                    }
+
+                    Ok((start, end + 1))
+                }
+            })
+            .collect()
+    }
+
     fn rconfig(&self) -> Config {
         Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
     }
 
@@ -1,11 +1,13 @@
 use std::cmp;
-use std::path::Path;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 
 use bytesize::MB;
 use ext_sort::{buffer::mem::MemoryLimitedBufferBuilder, ExternalSorter, ExternalSorterBuilder};
 use rayon::slice::ParallelSliceMut;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::{SelectColumns, Selection};
 use crate::util::{self, DeepSizedByteRecord};
 use crate::CliResult;
@@ -13,28 +15,19 @@ use crate::CliResult;
 use self::Number::{Float, Int};
 
 macro_rules! sort_by {
-    ($target:ident, $fn:ident, $sel:ident, $numeric:ident, $reverse:ident) => {
-        match ($numeric, $reverse) {
-            (false, false) => $target.$fn(|r1, r2| {
+    ($target:ident, $fn:ident, $sel:ident, $comparison:ident, $reverse:ident) => {
+        if $reverse {
+            $target.$fn(|r1, r2| {
                 let a = $sel.select(r1);
                 let b = $sel.select(r2);
-                iter_cmp(a, b)
-            }),
-            (true, false) => $target.$fn(|r1, r2| {
-                let a = $sel.select(r1);
-                let b = $sel.select(r2);
-                iter_cmp_num(a, b)
-            }),
-            (false, true) => $target.$fn(|r1, r2| {
-                let a = $sel.select(r1);
-                let b = $sel.select(r2);
-                iter_cmp(b, a)
-            }),
-            (true, true) => $target.$fn(|r1, r2| {
+                $comparison.compare(b, a)
+            })
+        } else {
+            $target.$fn(|r1, r2| {
                 let a = $sel.select(r1);
                 let b = $sel.select(r2);
-                iter_cmp_num(b, a)
-            }),
+                $comparison.compare(a, b)
+            })
         }
     };
 }
@@ -46,14 +39,52 @@ Note that this requires reading all of the CSV data into memory, unless
 you use the -e/--external flag, which will be slower and fallback
 to using disk space.
 
+When sorting externally, sorted chunks of at most -m/--memory-limit megabytes
+are written to a temporary directory created in --tmp-dir, before being merged.
+This directory is removed when the command ends, even if it is interrupted
+using ctrl+c. Note that rows are compared exactly the same way, whether sorting
+in memory or externally.
+
+The --columns flag can be used to sort the columns of the file, instead of its
+rows, by header name. This can be useful to canonicalize the column order of
+differently-ordered files before diffing or concatenating them:
+
+    $ xan sort --columns data.csv > canonical.csv
+
+The --by-key flag can be used to sort on multiple columns at once, each one
+with its own direction and its own way of comparing values. Keys are given as
+a comma-separated list of \"column[:asc|desc][:numeric|collated]\" specs,
+e.g. to sort by country, then by descending population:
+
+    $ xan sort --by-key 'country,population:desc:numeric' data.csv
+
+Direction defaults to ascending and values are compared lexicographically
+unless told otherwise.
+
 Usage:
     xan sort [options] [<input>]
 
 sort options:
-    --check                   Verify whether the file is already sorted.
+    --check                   Verify whether the file is already sorted, using the
+                              same comparison as the actual sort would. Nothing
+                              will be written to stdout and the command will exit
+                              with a non-zero code if the file is not sorted.
+    --stable-if-sorted        Read the file once beforehand to check whether it is
+                              already sorted, in which case it will be streamed
+                              to the output without being buffered in memory.
+                              Cannot work on stdin.
+    --columns                 Sort the columns by header name instead of sorting
+                              the rows. Can be combined with -N, -C & -R.
+    --by-key <specs>          Sort using multiple keys, each with its own direction
+                              and comparison. See above for the spec format.
+                              Cannot be used with -s, -N, -C nor -R.
     -s, --select <arg>        Select a subset of columns to sort.
                               See 'xan select --help' for the format details.
     -N, --numeric             Compare according to string numerical value
+    -C, --collator            Compare strings in a Unicode-aware fashion, so that
+                              accented characters are sorted near their unaccented
+                              counterparts (e.g. \"é\" near \"e\"), and case
+                              is ignored. Slower than the default byte order.
     -R, --reverse             Reverse order
     -c, --count <name>        Number of times the line was consecutively duplicated.
                               Needs a column name. Can only be used with --uniq.
@@ -67,7 +98,8 @@ sort options:
                               Will default to the sorted file's directory or \"./\" if
                               sorting an incoming stream.
     -m, --memory-limit <arg>  Maximum allowed memory when using external sorting, in
-                              megabytes. [default: 512].
+                              megabytes. Giving it implies -e, --external.
+                              Will default to 512.
 
 Common options:
     -h, --help             Display this message
@@ -78,33 +110,67 @@ Common options:
                            appear as the header row in the output.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
     flag_check: bool,
+    flag_stable_if_sorted: bool,
+    flag_columns: bool,
+    flag_by_key: Option<String>,
     flag_select: SelectColumns,
     flag_numeric: bool,
+    flag_collator: bool,
     flag_reverse: bool,
     flag_count: Option<String>,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_uniq: bool,
     flag_unstable: bool,
     flag_parallel: bool,
     flag_external: bool,
     flag_tmp_dir: Option<String>,
-    flag_memory_limit: u64,
+    flag_memory_limit: Option<u64>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
-    let numeric = args.flag_numeric;
+
+    if args.flag_numeric && args.flag_collator {
+        Err("-N/--numeric cannot be used with -C/--collator!")?;
+    }
+
+    let comparison = if args.flag_numeric {
+        Comparison::Numeric
+    } else if args.flag_collator {
+        Comparison::Collated
+    } else {
+        Comparison::Lexicographic
+    };
     let reverse = args.flag_reverse;
+
+    if args.flag_by_key.is_some() {
+        if args.flag_columns {
+            Err("--by-key cannot be used with --columns!")?;
+        }
+
+        if !args.flag_select.is_empty()
+            || args.flag_numeric
+            || args.flag_collator
+            || args.flag_reverse
+        {
+            Err("--by-key cannot be used with -s/--select, -N/--numeric, -C/--collator nor -R/--reverse!")?;
+        }
+    }
+
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select);
     let count = &args.flag_count;
@@ -116,54 +182,63 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut rdr = rconfig.reader()?;
 
     let mut headers = rdr.byte_headers()?.clone();
+
+    if args.flag_columns {
+        if args.flag_no_headers {
+            Err("--columns cannot work with -n, --no-headers!")?;
+        }
+
+        if args.flag_check || args.flag_uniq || args.flag_stable_if_sorted {
+            Err("--columns cannot be used with --check, -u, --uniq nor --stable-if-sorted!")?;
+        }
+
+        let mut wtr = Config::new(&args.flag_output).writer()?;
+
+        return sort_columns(&mut rdr, &mut wtr, &headers, comparison, reverse);
+    }
+
     let sel = rconfig.selection(&headers)?;
+    let keys = args
+        .flag_by_key
+        .as_ref()
+        .map(|specs| parse_sort_keys(specs, &headers, !rconfig.no_headers))
+        .transpose()?;
 
     if args.flag_check {
-        let mut record = csv::ByteRecord::new();
+        let sorted = match &keys {
+            Some(keys) => is_sorted_by_keys(&mut rdr, keys)?,
+            None => is_sorted(&mut rdr, &sel, comparison, reverse)?,
+        };
 
-        let mut last: Option<Vec<Vec<u8>>> = None;
+        if !sorted {
+            Err("file is NOT sorted!")?;
+        }
 
-        while rdr.read_byte_record(&mut record)? {
-            let current_sel = sel
-                .select(&record)
-                .map(|part| part.to_vec())
-                .collect::<Vec<_>>();
+        return Ok(());
+    }
 
-            match last {
-                None => {
-                    last = Some(current_sel);
-                }
-                Some(ref last_sel) => {
-                    let ordering = match (args.flag_reverse, args.flag_numeric) {
-                        (false, false) => iter_cmp(current_sel.iter(), last_sel.iter()),
-                        (true, false) => iter_cmp(last_sel.iter(), current_sel.iter()),
-                        (false, true) => iter_cmp_num(
-                            current_sel.iter().map(|r| r.as_slice()),
-                            last_sel.iter().map(|r| r.as_slice()),
-                        ),
-                        (true, true) => iter_cmp_num(
-                            last_sel.iter().map(|r| r.as_slice()),
-                            current_sel.iter().map(|r| r.as_slice()),
-                        ),
-                    };
-
-                    match ordering {
-                        cmp::Ordering::Less => {
-                            Err("file is NOT sorted!")?;
-                        }
-                        cmp::Ordering::Equal => continue,
-                        _ => last = Some(current_sel),
-                    }
-                }
-            };
+    let mut already_sorted = false;
+
+    if args.flag_stable_if_sorted {
+        if rconfig.is_std() {
+            Err("--stable-if-sorted cannot work on stdin!")?;
         }
 
-        println!("file is correctly sorted!");
+        already_sorted = match &keys {
+            Some(keys) => is_sorted_by_keys(&mut rdr, keys)?,
+            None => is_sorted(&mut rdr, &sel, comparison, reverse)?,
+        };
 
-        return Ok(());
+        // NOTE: we need to read the file again from the start
+        rdr = rconfig.reader()?;
     }
 
-    let all: Box<dyn Iterator<Item = csv::ByteRecord>> = if args.flag_external {
+    // NOTE: the guard must outlive the sorted iterator below
+    let mut chunks_dir: Option<ChunksDirGuard> = None;
+
+    let all: Box<dyn Iterator<Item = Result<csv::ByteRecord, csv::Error>>> = if already_sorted {
+        Box::new(rdr.into_byte_records())
+    } else if args.flag_external || args.flag_memory_limit.is_some() {
         let tmp_dir = args.flag_tmp_dir.unwrap_or(match args.arg_input {
             None => "./".to_string(),
             Some(p) => Path::new(&p)
@@ -174,9 +249,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                 .to_string(),
         });
 
-        let mut sorter_builder = ExternalSorterBuilder::new()
-            .with_tmp_dir(Path::new(&tmp_dir))
-            .with_buffer(MemoryLimitedBufferBuilder::new(args.flag_memory_limit * MB));
+        // NOTE: we create our own directory so that we can clean it up
+        // in case of voluntary interruption.
+        let dir = Path::new(&tmp_dir).join(format!("xan-sort-{}", std::process::id()));
+        fs::create_dir_all(&dir)?;
+        chunks_dir = Some(ChunksDirGuard(dir.clone()));
+
+        let handle = dir.clone();
+
+        ctrlc::set_handler(move || {
+            let _ = fs::remove_dir_all(&handle);
+            std::process::exit(1);
+        })
+        .expect("Could not setup ctrl+c handler!");
+
+        let mut sorter_builder = ExternalSorterBuilder::new().with_tmp_dir(&dir).with_buffer(
+            MemoryLimitedBufferBuilder::new(args.flag_memory_limit.unwrap_or(512) * MB),
+        );
 
         if args.flag_parallel {
             sorter_builder = sorter_builder.with_threads_number(num_cpus::get_physical());
@@ -187,44 +276,58 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             csv::Error,
             MemoryLimitedBufferBuilder,
             util::CsvExternalChunk,
-        > = sorter_builder.build().unwrap();
+        > = sorter_builder
+            .build()
+            .map_err(|err| format!("could not setup external sorter: {}", err))?;
 
         let sorted = sorter
             .sort_by(
                 rdr.byte_records()
                     .map(|result| result.map(DeepSizedByteRecord)),
                 |r1, r2| {
+                    if let Some(keys) = &keys {
+                        return compare_by_keys(keys, r1.as_ref(), r2.as_ref());
+                    }
+
                     let a = sel.select(r1.as_ref());
                     let b = sel.select(r2.as_ref());
 
-                    match (numeric, reverse) {
-                        (false, false) => iter_cmp(a, b),
-                        (true, false) => iter_cmp_num(a, b),
-                        (false, true) => iter_cmp(b, a),
-                        (true, true) => iter_cmp_num(b, a),
+                    if reverse {
+                        comparison.compare(b, a)
+                    } else {
+                        comparison.compare(a, b)
                     }
                 },
             )
-            .unwrap()
-            .map(|result| result.unwrap().into_inner());
+            .map_err(|err| format!("error while sorting externally: {}", err))?
+            .map(|result| result.map(DeepSizedByteRecord::into_inner));
 
         Box::new(sorted)
     } else {
         let mut all = rdr.byte_records().collect::<Result<Vec<_>, _>>()?;
 
-        if args.flag_unstable {
+        if let Some(keys) = &keys {
+            let cmp = |r1: &csv::ByteRecord, r2: &csv::ByteRecord| compare_by_keys(keys, r1, r2);
+
+            match (args.flag_unstable, args.flag_parallel) {
+                (true, true) => all.par_sort_unstable_by(cmp),
+                (true, false) => all.sort_unstable_by(cmp),
+                (false, true) => all.par_sort_by(cmp),
+                (false, false) => all.sort_by(cmp),
+            }
+        } else if args.flag_unstable {
             if args.flag_parallel {
-                sort_by!(all, par_sort_unstable_by, sel, numeric, reverse);
+                sort_by!(all, par_sort_unstable_by, sel, comparison, reverse);
             } else {
-                sort_by!(all, sort_unstable_by, sel, numeric, reverse);
+                sort_by!(all, sort_unstable_by, sel, comparison, reverse);
             }
         } else if args.flag_parallel {
-            sort_by!(all, par_sort_by, sel, numeric, reverse);
+            sort_by!(all, par_sort_by, sel, comparison, reverse);
         } else {
-            sort_by!(all, sort_by, sel, numeric, reverse);
+            sort_by!(all, sort_by, sel, comparison, reverse);
         }
 
-        Box::new(all.into_iter())
+        Box::new(all.into_iter().map(Ok))
     };
 
     let mut wtr = Config::new(&args.flag_output).writer()?;
@@ -242,10 +345,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut counter: u64 = 1;
     let mut line_buffer: Option<csv::ByteRecord> = None;
 
-    for r in all.into_iter() {
+    for result in all {
+        let r = result?;
+
         if args.flag_uniq {
             match prev {
-                Some(other_r) => match iter_cmp(sel.select(&r), sel.select(&other_r)) {
+                Some(other_r) => match keys.as_ref().map_or_else(
+                    || iter_cmp(sel.select(&r), sel.select(&other_r)),
+                    |keys| compare_by_keys(keys, &r, &other_r),
+                ) {
                     cmp::Ordering::Equal => {
                         if !count.is_none() {
                             counter += 1;
@@ -280,9 +388,206 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         to_flush.push_field(counter.to_string().as_bytes());
         wtr.write_byte_record(&to_flush)?;
     }
+
+    drop(chunks_dir);
+
+    Ok(wtr.flush()?)
+}
+
+// NOTE: removes the external sorting chunks directory when dropped, so that it
+// does not linger on disk when the command fails midway.
+struct ChunksDirGuard(PathBuf);
+
+impl Drop for ChunksDirGuard {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+fn sort_columns<R: io::Read, W: io::Write>(
+    rdr: &mut csv::Reader<R>,
+    wtr: &mut csv::Writer<W>,
+    headers: &csv::ByteRecord,
+    comparison: Comparison,
+    reverse: bool,
+) -> CliResult<()> {
+    let mut order = (0..headers.len()).collect::<Vec<_>>();
+
+    order.sort_by(|i, j| {
+        let a = std::iter::once(&headers[*i]);
+        let b = std::iter::once(&headers[*j]);
+
+        if reverse {
+            comparison.compare(b, a)
+        } else {
+            comparison.compare(a, b)
+        }
+    });
+
+    wtr.write_record(order.iter().map(|i| &headers[*i]))?;
+
+    let mut record = csv::ByteRecord::new();
+
+    while rdr.read_byte_record(&mut record)? {
+        wtr.write_record(order.iter().map(|i| &record[*i]))?;
+    }
+
     Ok(wtr.flush()?)
 }
 
+fn is_sorted<R: io::Read>(
+    rdr: &mut csv::Reader<R>,
+    sel: &Selection,
+    comparison: Comparison,
+    reverse: bool,
+) -> CliResult<bool> {
+    let mut record = csv::ByteRecord::new();
+
+    let mut last: Option<Vec<Vec<u8>>> = None;
+
+    while rdr.read_byte_record(&mut record)? {
+        let current_sel = sel
+            .select(&record)
+            .map(|part| part.to_vec())
+            .collect::<Vec<_>>();
+
+        match last {
+            None => {
+                last = Some(current_sel);
+            }
+            Some(ref last_sel) => {
+                let current_iter = current_sel.iter().map(|r| r.as_slice());
+                let last_iter = last_sel.iter().map(|r| r.as_slice());
+
+                let ordering = if reverse {
+                    comparison.compare(last_iter, current_iter)
+                } else {
+                    comparison.compare(current_iter, last_iter)
+                };
+
+                match ordering {
+                    cmp::Ordering::Less => return Ok(false),
+                    cmp::Ordering::Equal => continue,
+                    _ => last = Some(current_sel),
+                }
+            }
+        };
+    }
+
+    Ok(true)
+}
+
+fn is_sorted_by_keys<R: io::Read>(rdr: &mut csv::Reader<R>, keys: &[SortKey]) -> CliResult<bool> {
+    let mut record = csv::ByteRecord::new();
+
+    let mut last: Option<csv::ByteRecord> = None;
+
+    while rdr.read_byte_record(&mut record)? {
+        if let Some(last_record) = &last {
+            if compare_by_keys(keys, &record, last_record).is_lt() {
+                return Ok(false);
+            }
+        }
+
+        last = Some(record.clone());
+    }
+
+    Ok(true)
+}
+
+#[derive(Clone, Copy)]
+struct SortKey {
+    column: usize,
+    comparison: Comparison,
+    reverse: bool,
+}
+
+fn parse_sort_keys(
+    specs: &str,
+    headers: &csv::ByteRecord,
+    use_names: bool,
+) -> CliResult<Vec<SortKey>> {
+    let mut keys = Vec::new();
+
+    for spec in specs.split(',') {
+        let mut column = spec.trim();
+        let mut comparison: Option<Comparison> = None;
+        let mut reverse: Option<bool> = None;
+
+        // NOTE: modifiers are parsed from the end so that column names
+        // containing colons still work.
+        while let Some((rest, modifier)) = column.rsplit_once(':') {
+            match modifier {
+                "asc" | "desc" if reverse.is_none() => {
+                    reverse = Some(modifier == "desc");
+                }
+                "numeric" | "collated" if comparison.is_none() => {
+                    comparison = Some(if modifier == "numeric" {
+                        Comparison::Numeric
+                    } else {
+                        Comparison::Collated
+                    });
+                }
+                _ => break,
+            }
+
+            column = rest;
+        }
+
+        if column.is_empty() {
+            Err(format!("invalid --by-key spec \"{}\"!", spec))?;
+        }
+
+        keys.push(SortKey {
+            column: SelectColumns::parse(column)?.single_selection(headers, use_names)?,
+            comparison: comparison.unwrap_or(Comparison::Lexicographic),
+            reverse: reverse.unwrap_or(false),
+        });
+    }
+
+    Ok(keys)
+}
+
+fn compare_by_keys(keys: &[SortKey], r1: &csv::ByteRecord, r2: &csv::ByteRecord) -> cmp::Ordering {
+    for key in keys {
+        let a = std::iter::once(&r1[key.column]);
+        let b = std::iter::once(&r2[key.column]);
+
+        let ordering = if key.reverse {
+            key.comparison.compare(b, a)
+        } else {
+            key.comparison.compare(a, b)
+        };
+
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+
+    cmp::Ordering::Equal
+}
+
+#[derive(Clone, Copy)]
+enum Comparison {
+    Lexicographic,
+    Numeric,
+    Collated,
+}
+
+impl Comparison {
+    fn compare<'a, L, R>(&self, a: L, b: R) -> cmp::Ordering
+    where
+        L: Iterator<Item = &'a [u8]>,
+        R: Iterator<Item = &'a [u8]>,
+    {
+        match self {
+            Self::Lexicographic => iter_cmp(a, b),
+            Self::Numeric => iter_cmp_num(a, b),
+            Self::Collated => iter_cmp_collated(a, b),
+        }
+    }
+}
+
 /// Order `a` and `b` lexicographically using `Ord`
 pub fn iter_cmp<A, L, R>(mut a: L, mut b: R) -> cmp::Ordering
 where
@@ -322,6 +627,48 @@ where
     }
 }
 
+/// Order `a` and `b` using a Unicode-aware collation key, i.e. the
+/// lowercased ASCII transliteration of the strings, using the raw bytes
+/// as a tiebreaker.
+pub fn iter_cmp_collated<'a, L, R>(mut a: L, mut b: R) -> cmp::Ordering
+where
+    L: Iterator<Item = &'a [u8]>,
+    R: Iterator<Item = &'a [u8]>,
+{
+    loop {
+        match (a.next(), b.next()) {
+            (None, None) => return cmp::Ordering::Equal,
+            (None, _) => return cmp::Ordering::Less,
+            (_, None) => return cmp::Ordering::Greater,
+            (Some(x), Some(y)) => match compare_collated(x, y) {
+                cmp::Ordering::Equal => (),
+                non_eq => return non_eq,
+            },
+        }
+    }
+}
+
+fn collation_key(bytes: &[u8]) -> String {
+    unidecode::unidecode(&String::from_utf8_lossy(bytes)).to_lowercase()
+}
+
+fn compare_collated(x: &[u8], y: &[u8]) -> cmp::Ordering {
+    if x == y {
+        return cmp::Ordering::Equal;
+    }
+
+    if x.is_ascii() && y.is_ascii() {
+        return x
+            .to_ascii_lowercase()
+            .cmp(&y.to_ascii_lowercase())
+            .then_with(|| x.cmp(y));
+    }
+
+    collation_key(x)
+        .cmp(&collation_key(y))
+        .then_with(|| x.cmp(y))
+}
+
 #[derive(Clone, Copy, PartialEq)]
 enum Number {
     Int(i64),
@@ -5,8 +5,8 @@ use std::path::Path;
 use crossbeam_channel as channel;
 use threadpool::ThreadPool;
 
-use crate::config::{Config, Delimiter};
-use crate::index::Indexed;
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::SelectColumns;
 use crate::util::{self, FilenameTemplate};
 use crate::CliResult;
 
@@ -16,6 +16,28 @@ Splits the given CSV data into chunks.
 The files are written to the directory given with the name '{start}.csv',
 where {start} is the index of the first record of the chunk (starting at 0).
 
+The -k/--parts flag can be used instead of -s/--size to split the file into
+a fixed number of chunks of roughly equal size. Note that this requires to know
+the total number of records beforehand, which means the file will be read
+twice if no index exists (and that the command cannot work on stdin).
+
+Alternatively, the -k/--parts flag can be combined with --round-robin to
+assign each record to one of the parts in turn (record i goes to part i % k),
+in a single streaming pass. In this case, files are named after the index of
+the part (starting at 0) instead:
+
+    $ xan split --parts 4 --round-robin shards/ data.csv
+
+Finally, the --by-change flag can be used to start a new chunk every time the
+value of the given column changes, so that each contiguous run of rows sharing
+the same value ends up in its own file. This is useful to segment already
+sorted data, e.g. a log file grouped by session. Contrary to
+`xan partition`, non-contiguous rows with the same value will end up in
+different files. Files are still named after the index of the first record
+of the chunk:
+
+    $ xan split --by-change session_id sessions/ events.csv
+
 Usage:
     xan split [options] <outdir> [<input>]
     xan split --help
@@ -23,6 +45,12 @@ Usage:
 split options:
     -s, --size <arg>       The number of records to write into each chunk.
                            [default: 500]
+    -k, --parts <k>        Split the file into exactly <k> chunks instead.
+                           Overrides -s/--size.
+    --round-robin          When used with -k/--parts, dispatch records
+                           to the parts in round-robin fashion.
+    --by-change <column>   Start a new chunk each time the value of the given
+                           column changes. Cannot be used with -k/--parts.
     -j, --jobs <arg>       The number of spliting jobs to run in parallel.
                            This only works when the given CSV data has
                            an index already created. Note that a file handle
@@ -43,6 +71,8 @@ Common options:
                            appear in all chunks as the header row.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Clone, Deserialize)]
@@ -50,10 +80,14 @@ struct Args {
     arg_input: Option<String>,
     arg_outdir: String,
     flag_size: usize,
+    flag_parts: Option<usize>,
+    flag_round_robin: bool,
+    flag_by_change: Option<SelectColumns>,
     flag_jobs: usize,
     flag_filename: FilenameTemplate,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -61,10 +95,89 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     if args.flag_size == 0 {
         Err("--size must be greater than 0.")?;
     }
+
+    if args.flag_round_robin && args.flag_parts.is_none() {
+        Err("--round-robin can only be used with -k/--parts.")?;
+    }
+
+    if let Some(column) = &args.flag_by_change {
+        if args.flag_parts.is_some() {
+            Err("--by-change cannot be used with -k/--parts.")?;
+        }
+
+        fs::create_dir_all(&args.arg_outdir)?;
+        return args.by_change_split(column);
+    }
+
+    if let Some(parts) = args.flag_parts {
+        if parts == 0 {
+            Err("--parts must be greater than 0.")?;
+        }
+
+        if args.flag_round_robin {
+            fs::create_dir_all(&args.arg_outdir)?;
+            return args.round_robin_split(parts);
+        }
+
+        let idx_opt = args.rconfig().indexed()?;
+
+        let count = match &idx_opt {
+            Some(idx) => idx.count() as usize,
+            None => {
+                let rconfig = args.rconfig();
+
+                if rconfig.is_std() {
+                    Err("-k/--parts cannot work on stdin without --round-robin.")?;
+                }
+
+                let mut rdr = rconfig.reader()?;
+                let mut record = csv::ByteRecord::new();
+                let mut count: usize = 0;
+
+                while rdr.read_byte_record(&mut record)? {
+                    count += 1;
+                }
+
+                count
+            }
+        };
+
+        // NOTE: the remainder is spread over the first chunks, so that we
+        // get exactly k chunks whose sizes differ by at most one.
+        let (base, remainder) = (count / parts, count % parts);
+        let mut chunks = Vec::with_capacity(parts);
+        let mut start: usize = 0;
+
+        for p in 0..parts {
+            let size = base + usize::from(p < remainder);
+
+            if size == 0 {
+                break;
+            }
+
+            chunks.push((start, size));
+            start += size;
+        }
+
+        fs::create_dir_all(&args.arg_outdir)?;
+
+        return match idx_opt {
+            Some(_) => args.parallel_split(chunks),
+            None => args.sequential_split_in_chunks(&chunks),
+        };
+    }
+
     fs::create_dir_all(&args.arg_outdir)?;
 
     match args.rconfig().indexed()? {
-        Some(idx) => args.parallel_split(idx),
+        Some(idx) => {
+            let nchunks = util::num_of_chunks(idx.count() as usize, args.flag_size);
+            let chunks = (0..nchunks)
+                .map(|i| (i * args.flag_size, args.flag_size))
+                .collect();
+
+            args.parallel_split(chunks)
+        }
         None => args.sequential_split(),
     }
 }
@@ -90,21 +203,102 @@ impl Args {
         Ok(())
     }
 
-    fn parallel_split(&self, idx: Indexed<fs::File, fs::File>) -> CliResult<()> {
-        let nchunks = util::num_of_chunks(idx.count() as usize, self.flag_size);
+    fn sequential_split_in_chunks(&self, chunks: &[(usize, usize)]) -> CliResult<()> {
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+
+        let mut row = csv::ByteRecord::new();
+
+        for (start, size) in chunks {
+            let mut wtr = self.new_writer(&headers, *start)?;
+
+            for _ in 0..*size {
+                if !rdr.read_byte_record(&mut row)? {
+                    break;
+                }
+
+                wtr.write_byte_record(&row)?;
+            }
+
+            wtr.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn round_robin_split(&self, parts: usize) -> CliResult<()> {
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+
+        let mut writers = (0..parts)
+            .map(|i| self.new_writer(&headers, i))
+            .collect::<CliResult<Vec<_>>>()?;
+
+        let mut i: usize = 0;
+        let mut row = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut row)? {
+            writers[i % parts].write_byte_record(&row)?;
+            i += 1;
+        }
+
+        for wtr in writers.iter_mut() {
+            wtr.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn by_change_split(&self, column: &SelectColumns) -> CliResult<()> {
+        let rconfig = self.rconfig();
+        let mut rdr = rconfig.reader()?;
+        let headers = rdr.byte_headers()?.clone();
+        let column_index = column.single_selection(&headers, !rconfig.no_headers)?;
+
+        let mut wtr_opt: Option<csv::Writer<Box<dyn io::Write + Send + 'static>>> = None;
+        let mut last_key: Vec<u8> = Vec::new();
+        let mut i: usize = 0;
+        let mut row = csv::ByteRecord::new();
+        while rdr.read_byte_record(&mut row)? {
+            let key = &row[column_index];
+
+            if wtr_opt.is_none() || key != last_key.as_slice() {
+                if let Some(wtr) = wtr_opt.as_mut() {
+                    wtr.flush()?;
+                }
+
+                last_key.clear();
+                last_key.extend_from_slice(key);
+
+                wtr_opt = Some(self.new_writer(&headers, i)?);
+            }
+
+            wtr_opt.as_mut().unwrap().write_byte_record(&row)?;
+            i += 1;
+        }
+
+        if let Some(wtr) = wtr_opt.as_mut() {
+            wtr.flush()?;
+        }
+
+        Ok(())
+    }
+
+    fn parallel_split(&self, chunks: Vec<(usize, usize)>) -> CliResult<()> {
         let pool = ThreadPool::new(self.njobs());
         let (tx, rx) = channel::bounded::<()>(0);
-        for i in 0..nchunks {
+        for (start, size) in chunks {
             let args = self.clone();
             let tx = tx.clone();
             pool.execute(move || {
                 let conf = args.rconfig();
                 let mut idx = conf.indexed().unwrap().unwrap();
                 let headers = idx.byte_headers().unwrap().clone();
-                let mut wtr = args.new_writer(&headers, i * args.flag_size).unwrap();
+                let mut wtr = args.new_writer(&headers, start).unwrap();
 
-                idx.seek((i * args.flag_size) as u64).unwrap();
-                for row in idx.byte_records().take(args.flag_size) {
+                idx.seek(start as u64).unwrap();
+                for row in idx.byte_records().take(size) {
                     let row = row.unwrap();
                     wtr.write_byte_record(&row).unwrap();
                 }
@@ -135,6 +329,7 @@ impl Args {
     fn rconfig(&self) -> Config {
         Config::new(&self.arg_input)
             .delimiter(self.flag_delimiter)
+            .comment(self.flag_comment)
             .no_headers(self.flag_no_headers)
     }
 
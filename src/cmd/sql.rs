@@ -0,0 +1,666 @@
+use std::cmp::Ordering;
+
+use pest::Parser;
+use pest_derive::Parser;
+
+use crate::config::{Comment, Config, Delimiter};
+use crate::moonblade::{
+    is_aggregation_function, parse_named_expression_items, AggregationProgram, DynamicValue,
+    GroupAggregationProgram, NamedExpressionKind, Program,
+};
+use crate::select::SelectColumns;
+use crate::util;
+use crate::CliResult;
+
+static USAGE: &str = "
+Query CSV data using a subset of SQL. For now, only single-table queries
+of the following form are supported:
+
+    SELECT <items> FROM <table>
+        [WHERE <predicate>]
+        [GROUP BY <columns>]
+        [ORDER BY <expr> [ASC|DESC], ...]
+        [LIMIT <n>]
+
+Since only one table can be queried, the name given to FROM is not checked and
+always designates the given input (or stdin).
+
+Selected items, the WHERE predicate and ORDER BY keys are expressions using
+xan's script language (see `xan map --cheatsheet`), with some conveniences to
+make them feel like SQL: `=` and `<>` are understood as `eq` and `ne` (or
+`==` and `!=` when compared to a number literal), and the AND, OR, NOT, IN,
+AS, NULL, TRUE & FALSE keywords are case-insensitive.
+
+For instance:
+
+    $ xan sql \"SELECT name, population / 1000 AS k FROM cities WHERE country = 'France'\" cities.csv
+
+When using GROUP BY, selected items must either be grouping columns, or
+aggregations (see `xan agg --aggs`):
+
+    $ xan sql 'SELECT country, sum(population) AS total FROM cities GROUP BY country ORDER BY total DESC LIMIT 5' cities.csv
+
+Using aggregations without GROUP BY will aggregate the whole file instead.
+
+Note that ORDER BY expressions are evaluated on the output rows, which means
+they can only reference selected columns or their aliases. Also note that using
+ORDER BY or GROUP BY will buffer the results in memory.
+
+Usage:
+    xan sql [options] <query> [<input>]
+    xan sql --help
+
+Common options:
+    -h, --help             Display this message
+    -o, --output <file>    Write output to <file> instead of stdout.
+    -d, --delimiter <arg>  The field delimiter for reading CSV data.
+                           Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
+";
+
+#[derive(Parser)]
+#[grammar_inline = r#"
+WHITESPACE = _{ " " | "\t" | "\r" | "\n" }
+word_char  = _{ ASCII_ALPHANUMERIC | "_" }
+
+kw_select   = @{ ^"select" ~ !word_char }
+kw_from     = @{ ^"from" ~ !word_char }
+kw_where    = @{ ^"where" ~ !word_char }
+kw_group_by = @{ ^"group" ~ WHITESPACE+ ~ ^"by" ~ !word_char }
+kw_order_by = @{ ^"order" ~ WHITESPACE+ ~ ^"by" ~ !word_char }
+kw_limit    = @{ ^"limit" ~ !word_char }
+keyword     = _{ kw_select | kw_from | kw_where | kw_group_by | kw_order_by | kw_limit }
+
+single_quoted = _{ "'" ~ ("\\" ~ ANY | !"'" ~ ANY)* ~ "'" }
+double_quoted = _{ "\"" ~ ("\\" ~ ANY | !"\"" ~ ANY)* ~ "\"" }
+nested        = _{ ("(" ~ inner* ~ ")") | ("[" ~ inner* ~ "]") | ("{" ~ inner* ~ "}") }
+inner         = _{ single_quoted | double_quoted | nested | !(")" | "]" | "}") ~ ANY }
+chunk         = _{ single_quoted | double_quoted | nested | word_char+ | !(WHITESPACE | word_char | ";") ~ ANY }
+text          = @{ chunk ~ (WHITESPACE* ~ !keyword ~ chunk)* }
+
+table = @{ (word_char | "." | "-" | "/")+ | double_quoted }
+limit = @{ ASCII_DIGIT+ }
+
+query = {
+    SOI ~ kw_select ~ text ~ kw_from ~ table
+        ~ (kw_where ~ text)?
+        ~ (kw_group_by ~ text)?
+        ~ (kw_order_by ~ text)?
+        ~ (kw_limit ~ limit)?
+        ~ ";"? ~ EOI
+}
+"#]
+struct SqlParser;
+
+#[derive(Debug, Default, PartialEq)]
+struct Query {
+    select: String,
+    where_clause: Option<String>,
+    group_by: Option<String>,
+    order_by: Vec<(String, bool)>,
+    limit: Option<usize>,
+}
+
+// NOTE: splits on commas not nested in parentheses, brackets, braces or strings
+fn split_top_level(text: &str) -> Vec<String> {
+    let mut items = Vec::new();
+    let mut current = String::new();
+    let mut depth: usize = 0;
+    let mut quote: Option<char> = None;
+    let mut escaped = false;
+
+    for c in text.chars() {
+        if let Some(q) = quote {
+            current.push(c);
+
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == q {
+                quote = None;
+            }
+
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => quote = Some(c),
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => (),
+        }
+
+        current.push(c);
+    }
+
+    if !current.trim().is_empty() {
+        items.push(current.trim().to_string());
+    }
+
+    items
+}
+
+fn is_number_literal(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_ascii_digit() || c == '.') && token.parse::<f64>().is_ok()
+}
+
+// NOTE: `==` and `!=` compare numbers and will fail on strings, so we only
+// use them when one of the operands is a number literal, and use `eq` and
+// `ne` otherwise.
+fn is_numeric_comparison(before: &str, after: &[char]) -> bool {
+    let is_token_char = |c: char| c.is_ascii_alphanumeric() || c == '.' || c == '_';
+
+    let before = before.trim_end();
+    let previous_token = &before[before.trim_end_matches(is_token_char).len()..];
+
+    let next_token = after
+        .iter()
+        .skip_while(|c| c.is_whitespace())
+        .skip_while(|c| **c == '-')
+        .take_while(|c| is_token_char(**c))
+        .collect::<String>();
+
+    is_number_literal(previous_token) || is_number_literal(&next_token)
+}
+
+// NOTE: translates SQL operators & keywords into their xan script equivalents
+fn translate_expression(expr: &str) -> String {
+    let chars = expr.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(expr.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        if c == '\'' || c == '"' {
+            output.push(c);
+            i += 1;
+
+            while i < chars.len() {
+                output.push(chars[i]);
+
+                if chars[i] == '\\' && i + 1 < chars.len() {
+                    output.push(chars[i + 1]);
+                    i += 2;
+                    continue;
+                }
+
+                i += 1;
+
+                if chars[i - 1] == c {
+                    break;
+                }
+            }
+
+            continue;
+        }
+
+        if c.is_ascii_alphanumeric() || c == '_' {
+            let start = i;
+
+            while i < chars.len() && (chars[i].is_ascii_alphanumeric() || chars[i] == '_') {
+                i += 1;
+            }
+
+            let word = chars[start..i].iter().collect::<String>();
+
+            match word.to_lowercase().as_str() {
+                "and" | "or" | "in" | "as" | "null" | "true" | "false" => {
+                    output.push_str(&word.to_lowercase())
+                }
+                "not" => {
+                    let rest = chars[i..].iter().collect::<String>();
+                    let rest = rest.trim_start().to_lowercase();
+
+                    if rest.starts_with("in")
+                        && !rest[2..].starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_')
+                    {
+                        output.push_str("not");
+                    } else {
+                        output.push('!');
+                    }
+                }
+                _ => output.push_str(&word),
+            }
+
+            continue;
+        }
+
+        if c == '<' && chars.get(i + 1) == Some(&'>') {
+            output.push_str(if is_numeric_comparison(&output, &chars[i + 2..]) {
+                "!="
+            } else {
+                " ne "
+            });
+            i += 2;
+            continue;
+        }
+
+        if c == '=' {
+            let previous = output.trim_end().chars().last();
+            let next = chars.get(i + 1);
+
+            let is_comparison =
+                !matches!(previous, Some('=' | '!' | '<' | '>')) && next != Some(&'=');
+
+            // NOTE: named function arguments, e.g. `pad=true`, must be kept as-is
+            let trimmed = output.trim_end();
+            let before_word =
+                trimmed.trim_end_matches(|c: char| c.is_ascii_alphanumeric() || c == '_');
+            let is_named_argument = before_word.len() < trimmed.len()
+                && matches!(before_word.trim_end().chars().last(), Some('(' | ','));
+
+            if is_comparison && !is_named_argument {
+                output.push_str(if is_numeric_comparison(&output, &chars[i + 1..]) {
+                    "=="
+                } else {
+                    " eq "
+                });
+            } else {
+                output.push('=');
+            }
+
+            i += 1;
+            continue;
+        }
+
+        output.push(c);
+        i += 1;
+    }
+
+    output
+}
+
+fn parse_query(query: &str) -> Result<Query, String> {
+    let pairs = SqlParser::parse(Rule::query, query)
+        .map_err(|err| format!("could not parse SQL query:\n{}", err))?;
+
+    let mut parsed = Query::default();
+    let mut last_keyword = Rule::kw_select;
+
+    for pair in pairs.flatten() {
+        match pair.as_rule() {
+            Rule::kw_select
+            | Rule::kw_from
+            | Rule::kw_where
+            | Rule::kw_group_by
+            | Rule::kw_order_by
+            | Rule::kw_limit => {
+                last_keyword = pair.as_rule();
+            }
+            Rule::text => {
+                let text = pair.as_str().to_string();
+
+                match last_keyword {
+                    Rule::kw_select => parsed.select = text,
+                    Rule::kw_where => parsed.where_clause = Some(text),
+                    Rule::kw_group_by => parsed.group_by = Some(text),
+                    Rule::kw_order_by => {
+                        for item in split_top_level(&text) {
+                            let lower = item.to_lowercase();
+
+                            let (expr, desc) = if lower.ends_with(" desc") {
+                                (&item[..item.len() - 5], true)
+                            } else if lower.ends_with(" asc") {
+                                (&item[..item.len() - 4], false)
+                            } else {
+                                (item.as_str(), false)
+                            };
+
+                            parsed.order_by.push((expr.trim().to_string(), desc));
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            Rule::limit => {
+                parsed.limit = Some(pair.as_str().parse::<usize>().map_err(|e| e.to_string())?);
+            }
+            _ => (),
+        }
+    }
+
+    Ok(parsed)
+}
+
+// NOTE: null values are sorted last, numbers before strings
+#[derive(Debug, PartialEq)]
+enum SortValue {
+    Number(f64),
+    Bytes(Vec<u8>),
+    Null,
+}
+
+impl SortValue {
+    fn from_dynamic_value(value: DynamicValue) -> Self {
+        match value {
+            DynamicValue::None => Self::Null,
+            DynamicValue::Integer(i) => Self::Number(i as f64),
+            DynamicValue::Float(f) => Self::Number(f),
+            _ => {
+                let bytes = value.serialize_as_bytes();
+
+                if bytes.is_empty() {
+                    return Self::Null;
+                }
+
+                match std::str::from_utf8(&bytes)
+                    .ok()
+                    .and_then(|s| s.parse::<f64>().ok())
+                {
+                    Some(f) => Self::Number(f),
+                    None => Self::Bytes(bytes.into_owned()),
+                }
+            }
+        }
+    }
+
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Number(a), Self::Number(b)) => a.total_cmp(b),
+            (Self::Bytes(a), Self::Bytes(b)) => a.cmp(b),
+            (Self::Null, Self::Null) => Ordering::Equal,
+            (Self::Null, _) => Ordering::Greater,
+            (_, Self::Null) => Ordering::Less,
+            (Self::Number(_), Self::Bytes(_)) => Ordering::Less,
+            (Self::Bytes(_), Self::Number(_)) => Ordering::Greater,
+        }
+    }
+}
+
+enum SelectedItem {
+    Key(usize),
+    Aggregation(usize),
+}
+
+#[derive(Deserialize)]
+struct Args {
+    arg_query: String,
+    arg_input: Option<String>,
+    flag_output: Option<String>,
+    flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
+}
+
+pub fn run(argv: &[&str]) -> CliResult<()> {
+    let args: Args = util::get_args(USAGE, argv)?;
+
+    let query = parse_query(&args.arg_query)?;
+
+    let rconf = Config::new(&args.arg_input)
+        .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment);
+
+    let mut rdr = rconf.reader()?;
+    let mut wtr = Config::new(&args.flag_output).writer()?;
+    let headers = rdr.byte_headers()?.clone();
+
+    let predicate = query
+        .where_clause
+        .as_ref()
+        .map(|expr| Program::parse(&translate_expression(expr), &headers))
+        .transpose()?;
+
+    let select = translate_expression(&query.select);
+
+    // NOTE: selected items are inspected to know whether they are grouping
+    // columns or aggregations.
+    let selected_items = parse_named_expression_items(&select)
+        .map_err(|_| format!("could not parse selected items: {}", select));
+
+    let mut output_headers = csv::ByteRecord::new();
+    let mut rows: Vec<csv::ByteRecord> = Vec::new();
+    let mut record = csv::ByteRecord::new();
+    let mut index: usize = 0;
+
+    let must_buffer = !query.order_by.is_empty();
+
+    macro_rules! next_filtered_record {
+        () => {{
+            let mut found = false;
+
+            while rdr.read_byte_record(&mut record)? {
+                index += 1;
+
+                if let Some(program) = &predicate {
+                    if !program.run_with_record(index - 1, &record)?.is_truthy() {
+                        continue;
+                    }
+                }
+
+                found = true;
+                break;
+            }
+
+            found
+        }};
+    }
+
+    if let Some(group_by) = &query.group_by {
+        let group_sel = SelectColumns::parse(group_by.trim())?.selection(&headers, true)?;
+        let group_names = group_sel
+            .select(&headers)
+            .map(|h| String::from_utf8_lossy(h).into_owned())
+            .collect::<Vec<_>>();
+
+        let mut items = Vec::new();
+        let mut aggregations = Vec::new();
+        let mut key_names = Vec::new();
+
+        for item in selected_items? {
+            let key_index = match &item.kind {
+                NamedExpressionKind::Identifier(column) => {
+                    group_names.iter().position(|name| name == column)
+                }
+                _ => None,
+            };
+
+            match key_index {
+                Some(i) => {
+                    items.push(SelectedItem::Key(i));
+                    key_names.push(item.name);
+                }
+                None => {
+                    items.push(SelectedItem::Aggregation(aggregations.len()));
+                    aggregations.push(item.code);
+                }
+            }
+        }
+
+        let mut program = GroupAggregationProgram::parse(&aggregations.join(", "), &headers)?;
+        let aggregation_names = program.headers().map(|h| h.to_vec()).collect::<Vec<_>>();
+
+        let mut key_names_iter = key_names.iter();
+
+        for item in items.iter() {
+            match item {
+                SelectedItem::Key(_) => {
+                    output_headers.push_field(key_names_iter.next().unwrap().as_bytes())
+                }
+                SelectedItem::Aggregation(i) => output_headers.push_field(&aggregation_names[*i]),
+            }
+        }
+
+        while next_filtered_record!() {
+            program.run_with_record(group_sel.collect(&record), index - 1, &record)?;
+        }
+
+        for result in program.into_byte_records(false) {
+            let (group, aggregated) = result?;
+
+            rows.push(
+                items
+                    .iter()
+                    .map(|item| match item {
+                        SelectedItem::Key(i) => &group[*i][..],
+                        SelectedItem::Aggregation(i) => &aggregated[*i],
+                    })
+                    .collect(),
+            );
+        }
+    } else if selected_items.as_ref().is_ok_and(|items| {
+        items.iter().any(|item| {
+            matches!(&item.kind, NamedExpressionKind::Call(name) if is_aggregation_function(name))
+        })
+    }) {
+        let mut program = AggregationProgram::parse(&select, &headers)?;
+        output_headers = program.headers().collect();
+
+        while next_filtered_record!() {
+            program.run_with_record(index - 1, &record)?;
+        }
+
+        rows.push(program.finalize(false)?);
+    } else {
+        let projection = if select.trim() == "*" {
+            output_headers = headers.clone();
+            None
+        } else {
+            let (program, names) = Program::parse_named(&select, &headers)?;
+
+            for name in names {
+                output_headers.push_field(name.as_bytes());
+            }
+
+            Some(program)
+        };
+
+        let mut emitted: usize = 0;
+
+        if !must_buffer {
+            wtr.write_byte_record(&output_headers)?;
+        }
+
+        while next_filtered_record!() {
+            if !must_buffer && query.limit.is_some_and(|limit| emitted >= limit) {
+                break;
+            }
+
+            let row = match &projection {
+                None => record.clone(),
+                Some(program) => match program.run_with_record(index - 1, &record)? {
+                    DynamicValue::List(values) => values
+                        .iter()
+                        .map(|value| value.serialize_as_bytes().into_owned())
+                        .collect(),
+                    _ => unreachable!(),
+                },
+            };
+
+            if must_buffer {
+                rows.push(row);
+            } else {
+                wtr.write_byte_record(&row)?;
+                emitted += 1;
+            }
+        }
+
+        if !must_buffer {
+            return Ok(wtr.flush()?);
+        }
+    }
+
+    if !query.order_by.is_empty() {
+        let order_programs = query
+            .order_by
+            .iter()
+            .map(|(expr, desc)| {
+                Program::parse(&translate_expression(expr), &output_headers)
+                    .map(|program| (program, *desc))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut keyed_rows = rows
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| -> CliResult<(Vec<SortValue>, csv::ByteRecord)> {
+                let key = order_programs
+                    .iter()
+                    .map(|(program, _)| {
+                        program
+                            .run_with_record(i, &row)
+                            .map(SortValue::from_dynamic_value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                Ok((key, row))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        keyed_rows.sort_by(|(a, _), (b, _)| {
+            for ((x, y), (_, desc)) in a.iter().zip(b.iter()).zip(order_programs.iter()) {
+                let ordering = if *desc { y.cmp(x) } else { x.cmp(y) };
+
+                if ordering.is_ne() {
+                    return ordering;
+                }
+            }
+
+            Ordering::Equal
+        });
+
+        rows = keyed_rows.into_iter().map(|(_, row)| row).collect();
+    }
+
+    wtr.write_byte_record(&output_headers)?;
+
+    for row in rows.iter().take(query.limit.unwrap_or(usize::MAX)) {
+        wtr.write_byte_record(row)?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query() {
+        assert_eq!(
+            parse_query("select a, b as c from table where a > 1 and b = 'from' ORDER  BY c desc, a LIMIT 5;"),
+            Ok(Query {
+                select: "a, b as c".to_string(),
+                where_clause: Some("a > 1 and b = 'from'".to_string()),
+                group_by: None,
+                order_by: vec![("c".to_string(), true), ("a".to_string(), false)],
+                limit: Some(5)
+            })
+        );
+
+        assert_eq!(
+            parse_query("SELECT country, sum(pop) FROM cities.csv GROUP BY country"),
+            Ok(Query {
+                select: "country, sum(pop)".to_string(),
+                group_by: Some("country".to_string()),
+                ..Default::default()
+            })
+        );
+
+        assert!(parse_query("SELECT a").is_err());
+    }
+
+    #[test]
+    fn test_translate_expression() {
+        assert_eq!(
+            translate_expression("a = 1 AND b <> 'x = y' OR NOT c"),
+            "a == 1 and b  ne  'x = y' or ! c"
+        );
+        assert_eq!(translate_expression("a >= 1"), "a >= 1");
+        assert_eq!(translate_expression("a NOT IN [1, 2]"), "a not in [1, 2]");
+        assert_eq!(
+            translate_expression("zip(a, b, pad=true) AS z"),
+            "zip(a, b, pad=true) as z"
+        );
+        assert_eq!(translate_expression("a = b"), "a  eq  b");
+        assert_eq!(translate_expression("len(a) <> -2.5"), "len(a) != -2.5");
+        assert_eq!(translate_expression("1 = a"), "1 == a");
+    }
+}
@@ -1,12 +1,22 @@
-use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
-use crate::util;
+use std::cell::RefCell;
+use std::io;
+use std::num::NonZeroUsize;
+use std::sync::Arc;
+
+use rayon::prelude::*;
+use thread_local::ThreadLocal;
+
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util::{self, ChunksIteratorExt};
 use crate::CliResult;
 
 use crate::collections::ClusteredInsertHashmap;
+use crate::moonblade::agg::CovarianceWelford;
 use crate::moonblade::Stats;
 
 type GroupKey = Vec<Vec<u8>>;
+type GroupsToStats = ClusteredInsertHashmap<GroupKey, Vec<Stats>>;
 
 static USAGE: &str = "
 Computes descriptive statistics on CSV data.
@@ -40,13 +50,48 @@ approx_q1          (-a)      - Approximation of the first quartile of numerical
 approx_median      (-a)      - Approximation of the median of numerical values
 approx_q3          (-a)      - Approximation of the third quartile of numerical values
 cardinality        (-c, -A)  - Number of distinct string values
-mode               (-c, -A)  - Most frequent string value (tie breaking is arbitrary & random!)
+mode               (-c, -A)  - Most frequent string value (ties broken in favor of the
+                               first value in lexicographical order)
 tied_for_mode      (-c, -A)  - Number of values tied for mode
 lex_first          (default) - First string in lexical order
 lex_last           (default) - Last string in lexical order
 min_length         (default) - Minimum string length
 max_length         (default) - Maximum string length
 
+Note that computing exact quartiles using -q/--quartiles requires to buffer all
+numerical values in memory. On very large files, the -a/--approx flag can be used
+instead to compute approximate quartiles in bounded memory, using a t-digest
+sketch. Approximate statistics are always reported in columns prefixed with
+\"approx_\" so that they cannot be mistaken for exact ones, and will be empty for
+columns without any numerical value.
+
+Using the -T/--transpose flag will flip this output so that each statistic
+becomes a row and each described column becomes a column, which is often
+easier to read in a terminal, e.g. when piping into `xan view`.
+
+The --sketch flag can be used to output, for each numerical column, a five-number
+summary instead (min, q1, median, q3 & max), along with the interquartile range
+and the fences beyond which values are usually considered outliers, i.e.
+q1 - 1.5 * iqr and q3 + 1.5 * iqr. This is typically what you need to draw box
+plots. Columns without any numerical value are omitted. Note that quartiles will
+be exact, and therefore require to buffer numerical values in memory, unless
+the -a/--approx flag is also given.
+
+Finally, the --corr flag can be used to output a Pearson correlation matrix of
+the selected columns instead, computed in a single pass. Columns containing
+non-numerical values are excluded from the matrix (a note is printed on stderr
+when this happens), and empty cells are ignored when computing the correlation
+of a pair of columns. The output is a square matrix with column names as both
+header and first column. Note that this requires O(k²) memory, k being the number
+of selected columns.
+
+The -p/--parallel flag can be used to compute the statistics using multiple
+threads, each one processing chunks of rows before their results are merged.
+Since exact quartiles would require to buffer all numerical values, this cannot
+be used with -q/--quartiles, -A/--all nor with --sketch unless -a/--approx is
+also given. Note that the order of groups is not deterministic when grouping
+rows using -g/--groupby.
+
 Usage:
     xan stats [options] [<input>]
 
@@ -62,9 +107,23 @@ stats options:
                            This requires storing all CSV data in memory.
     -q, --quartiles        Show quartiles.
                            This requires storing all CSV data in memory.
-    -a, --approx           Compute approximated statistics.
+    -a, --approx           Compute approximated statistics, i.e. cardinality and
+                           quartiles, in bounded memory.
+    --sketch               Output a five-number summary, along with outlier
+                           fences, of the numerical columns instead. Cannot be
+                           used with -g, --groupby nor -T, --transpose.
+    --corr                 Output a correlation matrix of the numerical columns
+                           instead. Cannot be used with -g, --groupby.
     --nulls                Include empty values in the population size for computing
                            mean and standard deviation.
+    -T, --transpose        Output one row per statistic and one column per
+                           described column instead. Cannot be used with -g, --groupby.
+    -p, --parallel         Whether to use parallelization to speed up computations.
+                           Will automatically select a suitable number of threads to use
+                           based on your number of cores. Cannot be used with --corr.
+    --chunk-size <size>    Number of rows in a batch to send to a thread at once when
+                           using -p, --parallel.
+                           [default: 4096]
 
 Common options:
     -h, --help             Display this message
@@ -74,6 +133,8 @@ Common options:
                            in statistics.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Clone, Deserialize)]
@@ -85,10 +146,16 @@ struct Args {
     flag_cardinality: bool,
     flag_quartiles: bool,
     flag_approx: bool,
+    flag_sketch: bool,
+    flag_corr: bool,
     flag_nulls: bool,
+    flag_transpose: bool,
+    flag_parallel: bool,
+    flag_chunk_size: NonZeroUsize,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
 }
 
 impl Args {
@@ -103,7 +170,7 @@ impl Args {
             stats.compute_frequencies();
         }
 
-        if self.flag_all || self.flag_quartiles {
+        if self.flag_all || self.flag_quartiles || (self.flag_sketch && !self.flag_approx) {
             stats.compute_numbers();
         }
 
@@ -115,11 +182,133 @@ impl Args {
     }
 }
 
+// NOTE: only the upper triangle of the matrix, diagonal included, is stored.
+fn triangular_index(k: usize, i: usize, j: usize) -> usize {
+    i * k - i * (i + 1) / 2 + j
+}
+
+fn parse_float(cell: &[u8]) -> Option<f64> {
+    std::str::from_utf8(cell).ok()?.parse::<f64>().ok()
+}
+
+fn correlation_matrix<R: io::Read, W: io::Write>(
+    rdr: &mut csv::Reader<R>,
+    wtr: &mut csv::Writer<W>,
+    sel: &Selection,
+    field_names: &[Vec<u8>],
+) -> CliResult<()> {
+    let k = sel.len();
+
+    let mut is_numeric: Vec<bool> = vec![true; k];
+    let mut has_values: Vec<bool> = vec![false; k];
+    let mut matrix: Vec<CovarianceWelford> = vec![CovarianceWelford::new(); k * (k + 1) / 2];
+
+    let mut record = csv::ByteRecord::new();
+    let mut values: Vec<Option<f64>> = Vec::with_capacity(k);
+
+    while rdr.read_byte_record(&mut record)? {
+        values.clear();
+
+        for (i, cell) in sel.select(&record).enumerate() {
+            if cell.is_empty() {
+                values.push(None);
+                continue;
+            }
+
+            let value = parse_float(cell);
+
+            if value.is_some() {
+                has_values[i] = true;
+            } else {
+                is_numeric[i] = false;
+            }
+
+            values.push(value);
+        }
+
+        for i in 0..k {
+            if let Some(x) = values[i] {
+                for j in i..k {
+                    if let Some(y) = values[j] {
+                        matrix[triangular_index(k, i, j)].add(x, y);
+                    }
+                }
+            }
+        }
+    }
+
+    let numeric_columns = (0..k)
+        .filter(|i| is_numeric[*i] && has_values[*i])
+        .collect::<Vec<_>>();
+
+    if numeric_columns.len() < k {
+        let excluded = (0..k)
+            .filter(|i| !numeric_columns.contains(i))
+            .map(|i| String::from_utf8_lossy(&field_names[i]).into_owned())
+            .collect::<Vec<_>>();
+
+        eprintln!(
+            "Excluded non-numerical column(s) from correlation matrix: {}",
+            excluded.join(", ")
+        );
+    }
+
+    record.clear();
+    record.push_field(b"field");
+
+    for i in numeric_columns.iter() {
+        record.push_field(&field_names[*i]);
+    }
+
+    wtr.write_byte_record(&record)?;
+
+    for i in numeric_columns.iter().copied() {
+        record.clear();
+        record.push_field(&field_names[i]);
+
+        for j in numeric_columns.iter().copied() {
+            let index = triangular_index(k, i.min(j), i.max(j));
+
+            match matrix[index].correlation() {
+                Some(r) if !r.is_nan() => record.push_field(r.to_string().as_bytes()),
+                _ => record.push_field(b""),
+            }
+        }
+
+        wtr.write_byte_record(&record)?;
+    }
+
+    Ok(wtr.flush()?)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let args: Args = util::get_args(USAGE, argv)?;
 
+    if args.flag_corr && args.flag_groupby.is_some() {
+        Err("--corr cannot be used with -g, --groupby!")?;
+    }
+
+    if args.flag_sketch && (args.flag_corr || args.flag_transpose || args.flag_groupby.is_some()) {
+        Err("--sketch cannot be used with --corr, -T, --transpose nor -g, --groupby!")?;
+    }
+
+    if args.flag_transpose && (args.flag_corr || args.flag_groupby.is_some()) {
+        Err("-T, --transpose cannot be used with --corr nor -g, --groupby!")?;
+    }
+
+    if args.flag_parallel {
+        if args.flag_corr {
+            Err("-p, --parallel cannot be used with --corr!")?;
+        }
+
+        if args.flag_all || args.flag_quartiles || (args.flag_sketch && !args.flag_approx) {
+            Err("-p, --parallel cannot compute exact quartiles, since it would require to buffer all numerical values! Use -a, --approx instead.")?;
+        }
+    }
+
     let rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select.clone());
 
@@ -152,6 +341,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         sel.select(&headers).map(|h| h.to_vec()).collect()
     };
 
+    if args.flag_corr {
+        return correlation_matrix(&mut rdr, &mut wtr, &sel, &field_names);
+    }
+
     // Grouping
     if let Some(gsel) = groupby_sel_opt {
         let mut record = csv::ByteRecord::new();
@@ -164,29 +357,68 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
         wtr.write_byte_record(&record)?;
 
-        let mut groups: ClusteredInsertHashmap<GroupKey, Vec<Stats>> =
-            ClusteredInsertHashmap::new();
+        let mut groups = GroupsToStats::new();
 
-        while rdr.read_byte_record(&mut record)? {
-            let group_key: Vec<_> = gsel.select(&record).map(|cell| cell.to_vec()).collect();
+        if args.flag_parallel {
+            let local: Arc<ThreadLocal<RefCell<GroupsToStats>>> = Arc::new(ThreadLocal::new());
 
-            groups.insert_with_or_else(
-                group_key,
-                || {
-                    let mut fields = (0..sel.len()).map(|_| args.new_stats()).collect::<Vec<_>>();
+            rdr.into_byte_records()
+                .chunks(args.flag_chunk_size)
+                .par_bridge()
+                .try_for_each(|chunk| -> CliResult<()> {
+                    let mut local_groups = local
+                        .get_or(|| RefCell::new(GroupsToStats::new()))
+                        .borrow_mut();
 
-                    for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-                        stats.process(cell);
-                    }
+                    for result in chunk {
+                        let record = result?;
+                        let group_key: Vec<_> =
+                            gsel.select(&record).map(|cell| cell.to_vec()).collect();
 
-                    fields
-                },
-                |fields| {
-                    for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-                        stats.process(cell);
+                        let fields = local_groups.insert_with(group_key, || {
+                            (0..sel.len()).map(|_| args.new_stats()).collect()
+                        });
+
+                        for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
+                            stats.process(cell);
+                        }
                     }
-                },
-            );
+
+                    Ok(())
+                })?;
+
+            for local_groups in Arc::try_unwrap(local).unwrap().into_iter() {
+                for (group_key, fields) in local_groups.into_inner().into_iter() {
+                    groups.insert_or_update_with(group_key, fields, |current, other| {
+                        for (stats, other_stats) in current.iter_mut().zip(other) {
+                            stats.merge(other_stats);
+                        }
+                    });
+                }
+            }
+        } else {
+            while rdr.read_byte_record(&mut record)? {
+                let group_key: Vec<_> = gsel.select(&record).map(|cell| cell.to_vec()).collect();
+
+                groups.insert_with_or_else(
+                    group_key,
+                    || {
+                        let mut fields =
+                            (0..sel.len()).map(|_| args.new_stats()).collect::<Vec<_>>();
+
+                        for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
+                            stats.process(cell);
+                        }
+
+                        fields
+                    },
+                    |fields| {
+                        for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
+                            stats.process(cell);
+                        }
+                    },
+                );
+            }
         }
 
         for (group, fields) in groups.into_iter() {
@@ -209,14 +441,79 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     // No grouping
     let mut fields = (0..sel.len()).map(|_| args.new_stats()).collect::<Vec<_>>();
 
-    wtr.write_byte_record(&fields[0].headers())?;
+    if args.flag_sketch {
+        wtr.write_byte_record(&Stats::sketch_headers())?;
+    } else if !args.flag_transpose {
+        wtr.write_byte_record(&fields[0].headers())?;
+    }
 
     let mut record = csv::ByteRecord::new();
 
-    while rdr.read_byte_record(&mut record)? {
-        for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
-            stats.process(cell);
+    if args.flag_parallel {
+        let local: Arc<ThreadLocal<RefCell<Vec<Stats>>>> = Arc::new(ThreadLocal::new());
+
+        rdr.into_byte_records()
+            .chunks(args.flag_chunk_size)
+            .par_bridge()
+            .try_for_each(|chunk| -> CliResult<()> {
+                let mut local_fields = local
+                    .get_or(|| RefCell::new((0..sel.len()).map(|_| args.new_stats()).collect()))
+                    .borrow_mut();
+
+                for result in chunk {
+                    let record = result?;
+
+                    for (cell, stats) in sel.select(&record).zip(local_fields.iter_mut()) {
+                        stats.process(cell);
+                    }
+                }
+
+                Ok(())
+            })?;
+
+        for local_fields in Arc::try_unwrap(local).unwrap().into_iter() {
+            for (stats, other_stats) in fields.iter_mut().zip(local_fields.into_inner()) {
+                stats.merge(other_stats);
+            }
+        }
+    } else {
+        while rdr.read_byte_record(&mut record)? {
+            for (cell, stats) in sel.select(&record).zip(fields.iter_mut()) {
+                stats.process(cell);
+            }
+        }
+    }
+
+    if args.flag_sketch {
+        for (name, stats) in field_names.iter().zip(fields) {
+            if let Some(sketch) = stats.sketch(name) {
+                wtr.write_byte_record(&sketch)?;
+            }
+        }
+
+        return Ok(wtr.flush()?);
+    }
+
+    if args.flag_transpose {
+        let headers = fields[0].headers();
+        let results = field_names
+            .iter()
+            .zip(fields)
+            .map(|(name, stats)| stats.results(name))
+            .collect::<Vec<_>>();
+
+        for (i, statistic) in headers.iter().enumerate() {
+            record.clear();
+            record.push_field(statistic);
+
+            for result in results.iter() {
+                record.push_field(&result[i]);
+            }
+
+            wtr.write_byte_record(&record)?;
         }
+
+        return Ok(wtr.flush()?);
     }
 
     for (name, stats) in field_names.into_iter().zip(fields.into_iter()) {
@@ -2,6 +2,7 @@ use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 use std::ops::RangeInclusive;
 
+use indexmap::IndexMap;
 use paltoquet::stemmers::{fr::carry_stemmer, s_stemmer};
 use paltoquet::tokenizers::{
     split_paragraphs, split_sentences, NgramsIteratorExt, WordToken, WordTokenKind,
@@ -10,7 +11,7 @@ use paltoquet::tokenizers::{
 use pariter::IteratorExt;
 use regex::Regex;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::select::SelectColumns;
 use crate::util::{self, ImmutableRecordHelpers, JoinIteratorExt};
 use crate::CliResult;
@@ -69,6 +70,13 @@ However, when giving a column name to -T, --token-type, the command will
 instead emit one row per token with the token in a new \"token\" column, along
 with a new column containing the token's type.
 
+Similarly, when using the --counts flag, the command will emit one row per
+distinct token found in each row, in a new \"token\" column, along with a new
+\"count\" column containing the number of times the token appeared in the row,
+which is handy when building bag-of-words representations of documents:
+
+    $ xan tokenize words --counts text file.csv > counts.csv
+
 This subcommand also exposes many ways to filter and process the resulting
 tokens as well as ways to refine a vocabulary iteratively in tandem with
 the \"xan vocab\" command.
@@ -78,11 +86,20 @@ the \"xan vocab\" command.
 Tokenize the given text by splitting it into sentences, emitting one row per
 sentence with a new \"sentence\" column at the end.
 
+When given -N, --ngrams, the command will instead emit overlapping ngrams of
+consecutive sentences, joined by a space, which can be useful to detect
+near-duplicate texts:
+
+    $ xan tokenize sentences -N 3 text file.csv
+
 # tokenize paragraphs
 
 Tokenize the given text by splitting it into paragraphs, emitting one row per
 paragraph, with a new \"paragraph\" column at the end.
 
+Just like sentences, -N, --ngrams can be used to emit ngrams of consecutive
+paragraphs instead, joined by a line break (or a blank line with -A, --aerated).
+
 ---
 
 Note that the command will always drop the text column from the
@@ -104,7 +121,8 @@ Usage:
 
 tokenize options:
     -c, --column <name>      Name for the token column. Will default to \"tokens\", \"token\"
-                             when -T/--token-type is provided, \"paragraphs\" or \"sentences\".
+                             when -T/--token-type or --counts is provided, \"paragraphs\"
+                             or \"sentences\".
     -p, --parallel           Whether to use parallelization to speed up computations.
                              Will automatically select a suitable number of threads to use
                              based on your number of cores. Use -t, --threads if you want to
@@ -118,9 +136,13 @@ tokenize words options:
                              to infer token types, nor handle subtle cases.
     -N, --ngrams <n>         If given, will output token ngrams using the given n or the given
                              range of n values using a comma as separator e.g. \"1,3\".
-                             This cannot be used with -T, --token-type.
+                             This cannot be used with -T, --token-type. Also works
+                             with sentences and paragraphs.
     -T, --token-type <name>  Name of a column to add containing the type of the tokens.
                              This cannot be used with -N, --ngrams.
+    --counts                 Emit one row per distinct token with the number of times
+                             it appeared in the row, in a new \"count\" column.
+                             This cannot be used with -T, --token-type.
     -D, --drop <types>       Types of tokens to drop from the results, separated by comma,
                              e.g. \"word,number\". Cannot work with -k, --keep.
                              See the list of recognized types above.
@@ -163,6 +185,8 @@ Common options:
                            as headers.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -174,9 +198,11 @@ struct Args {
     cmd_paragraphs: bool,
     flag_column: Option<String>,
     flag_token_type: Option<String>,
+    flag_counts: bool,
     flag_output: Option<String>,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
     flag_drop: Option<String>,
@@ -209,19 +235,23 @@ impl Args {
 
     fn validate(&self) -> Result<(), &str> {
         if self.cmd_sentences || self.cmd_paragraphs {
-            if self.flag_ngrams.is_some() {
-                return Err("--ngrams cannot work with paragraphs nor sentences!");
-            }
-
             if self.flag_token_type.is_some() {
                 return Err("-T,--token-type cannot work with paragraphs nor sentences!");
             }
+
+            if self.flag_counts {
+                return Err("--counts cannot work with paragraphs nor sentences!");
+            }
         }
 
         if self.flag_ngrams.is_some() && self.flag_token_type.is_some() {
             return Err("--ngrams cannot be used with -T,--token-type!");
         }
 
+        if self.flag_counts && self.flag_token_type.is_some() {
+            return Err("--counts cannot be used with -T,--token-type!");
+        }
+
         Ok(())
     }
 }
@@ -237,6 +267,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.arg_column.clone());
 
@@ -261,7 +292,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let token_column_name = match &args.flag_column {
         Some(name) => name,
         None => {
-            if args.cmd_words && args.flag_token_type.is_some() {
+            if args.cmd_words && (args.flag_token_type.is_some() || args.flag_counts) {
                 "token"
             } else if args.cmd_paragraphs {
                 "paragraph"
@@ -284,6 +315,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             headers.push_field(name.as_bytes());
         }
 
+        if args.flag_counts {
+            headers.push_field(b"count");
+        }
+
         wtr.write_byte_record(&headers)?;
     }
 
@@ -377,18 +412,40 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     // NOTE: everything in this function will be parallelized
     let tokenize = move |string: &str| -> Vec<(String, WordTokenKind)> {
-        if args.cmd_paragraphs {
-            return split_paragraphs(string, args.flag_aerated)
-                .map(|paragraph| (paragraph.to_string(), WordTokenKind::Word))
-                .collect();
-        } else if args.cmd_sentences {
-            return if args.flag_squeeze {
-                split_sentences(&squeeze_regex.replace_all(string, " "))
-                    .map(|sentence| (sentence.to_string(), WordTokenKind::Word))
+        if args.cmd_paragraphs || args.cmd_sentences {
+            let (pieces, ngrams_joiner): (Vec<String>, &str) = if args.cmd_paragraphs {
+                (
+                    split_paragraphs(string, args.flag_aerated)
+                        .map(|paragraph| paragraph.to_string())
+                        .collect(),
+                    if args.flag_aerated { "\n\n" } else { "\n" },
+                )
+            } else if args.flag_squeeze {
+                (
+                    split_sentences(&squeeze_regex.replace_all(string, " "))
+                        .map(|sentence| sentence.to_string())
+                        .collect(),
+                    " ",
+                )
+            } else {
+                (
+                    split_sentences(string)
+                        .map(|sentence| sentence.to_string())
+                        .collect(),
+                    " ",
+                )
+            };
+
+            return if let Some(range) = &ngrams {
+                pieces
+                    .into_iter()
+                    .ngrams_range(range.clone())
+                    .map(|gram| (gram.join(ngrams_joiner), WordTokenKind::Word))
                     .collect()
             } else {
-                split_sentences(string)
-                    .map(|sentence| (sentence.to_string(), WordTokenKind::Word))
+                pieces
+                    .into_iter()
+                    .map(|piece| (piece, WordTokenKind::Word))
                     .collect()
             };
         }
@@ -485,6 +542,25 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     record_to_write.push_field(token.0.as_bytes());
                     record_to_write.push_field(token.1.as_str().as_bytes());
 
+                    wtr.write_record(&record_to_write)?;
+                }
+            } else if args.flag_counts {
+                let mut counts: IndexMap<&str, u64> = IndexMap::new();
+
+                for token in $tokens.iter() {
+                    *counts.entry(token.0.as_str()).or_insert(0) += 1;
+                }
+
+                for (token, count) in counts {
+                    let mut record_to_write = if args.flag_keep_text {
+                        $record.clone()
+                    } else {
+                        $record.remove(col_index)
+                    };
+
+                    record_to_write.push_field(token.as_bytes());
+                    record_to_write.push_field(count.to_string().as_bytes());
+
                     wtr.write_record(&record_to_write)?;
                 }
             } else {
@@ -1,4 +1,4 @@
-use std::cmp::Reverse;
+use std::cmp::{Ordering, Reverse};
 use std::num::NonZeroUsize;
 
 use ordered_float::NotNan;
@@ -6,7 +6,8 @@ use ordered_float::NotNan;
 use crate::collections::{
     ClusteredInsertHashmap, FixedReverseHeapMap, FixedReverseHeapMapWithTies,
 };
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
+use crate::moonblade::{DynamicValue, Program};
 use crate::select::SelectColumns;
 use crate::util::{self, ImmutableRecordHelpers};
 use crate::CliResult;
@@ -18,11 +19,35 @@ Find top k CSV rows according to some column values.
 
 Runs in O(N * log k) time, consuming only O(k) memory.
 
+Instead of a column, rows can also be ranked using an expression evaluated for
+each row, without needing to materialize it as a column first:
+
+    $ xan top --by 'retweets + likes' -l 10 file.csv
+
+Rows for which the expression fails or does not return a number are ranked last,
+unless the --strict flag is given, in which case the command will error. Note that rows
+whose column value is not a number are simply ignored, on the contrary.
+
+By default, rows tied on their score are kept in file order. You can instead
+use the --tiebreak flag to order them using a secondary expression, e.g. to
+order tied rows alphabetically by name:
+
+    $ xan top count --tiebreak name file.csv
+
+Tied rows are ordered by ascending tiebreak value, unless the expression is
+prefixed with \"-\", and remaining ties are still kept in file order. Numerical
+tiebreak values are compared as numbers and are considered lower than strings.
+
 Usage:
     xan top <column> [options] [<input>]
+    xan top --by <expr> [options] [<input>]
     xan top --help
 
 dedup options:
+    --by <expr>           Rank rows using the given expression instead of a column.
+    --strict              Error when --by expression fails or does not return
+                          a number.
+                          Can only be used with --by.
     -l, --limit <n>       Number of top items to return. Cannot be < 1.
                           [default: 10]
     -R, --reverse         Reverse order.
@@ -31,6 +56,8 @@ dedup options:
     -r, --rank <col>      Name of a rank column to prepend.
     -T, --ties            Keep all rows tied for last. Will therefore
                           consume O(k + t) memory, t being the number of ties.
+    --tiebreak <expr>     Expression used to order rows tied on their score.
+                          Cannot be used with -T, --ties.
 
 Common options:
     -h, --help               Display this message
@@ -39,42 +66,174 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 ";
 
 #[derive(PartialEq, PartialOrd, Ord, Eq)]
 struct Forward<T>(T);
 
+enum ScoreMethod {
+    Column(usize),
+    Expression(Program),
+}
+
+#[derive(PartialEq, PartialOrd, Ord, Eq)]
+enum TieBreakValue {
+    Number(NotNan<f64>),
+    String(String),
+}
+
+impl From<DynamicValue> for TieBreakValue {
+    fn from(value: DynamicValue) -> Self {
+        match value.try_as_f64().ok().and_then(|f| NotNan::new(f).ok()) {
+            Some(f) => Self::Number(f),
+            None => Self::String(value.try_as_str().unwrap_or_default().into_owned()),
+        }
+    }
+}
+
+// NOTE: heaps keep the greatest items, so the ordering is reversed
+// when tiebreaking in ascending order. Row index is used as a last
+// resort to keep the tiebreak stable.
+#[derive(PartialEq, Eq)]
+struct TieBreak {
+    value: TieBreakValue,
+    descending: bool,
+    index: usize,
+}
+
+impl PartialOrd for TieBreak {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TieBreak {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let ordering = self.value.cmp(&other.value);
+
+        let ordering = if self.descending {
+            ordering
+        } else {
+            ordering.reverse()
+        };
+
+        ordering.then_with(|| other.index.cmp(&self.index))
+    }
+}
+
 #[derive(Deserialize)]
 struct Args {
     arg_input: Option<String>,
-    arg_column: SelectColumns,
+    arg_column: Option<String>,
+    flag_by: Option<String>,
+    flag_strict: bool,
     flag_no_headers: bool,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_limit: NonZeroUsize,
     flag_reverse: bool,
     flag_groupby: Option<SelectColumns>,
     flag_rank: Option<String>,
     flag_ties: bool,
+    flag_tiebreak: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
-    let args: Args = util::get_args(USAGE, argv)?;
+    let mut args: Args = util::get_args(USAGE, argv)?;
+
+    if args.flag_ties && args.flag_tiebreak.is_some() {
+        Err("--tiebreak cannot be used with -T, --ties!")?;
+    }
+
+    // NOTE: when using --by, docopt will parse the input as the column
+    if args.flag_by.is_some() {
+        if args.arg_input.is_some() {
+            Err("cannot give both a <column> and --by!")?;
+        }
+
+        args.arg_input = args.arg_column.take();
+    } else if args.flag_strict {
+        Err("--strict can only be used with --by!")?;
+    }
 
-    let rconf = Config::new(&args.arg_input)
+    let mut rconf = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
-        .no_headers(args.flag_no_headers)
-        .select(args.arg_column);
+        .comment(args.flag_comment)
+        .no_headers(args.flag_no_headers);
+
+    if let Some(column) = &args.arg_column {
+        rconf = rconf.select(SelectColumns::parse(column)?);
+    }
 
     let mut rdr = rconf.reader()?;
     let headers = rdr.byte_headers()?;
-    let score_col = rconf.single_selection(headers)?;
+
+    let score_method = match &args.flag_by {
+        Some(expr) => ScoreMethod::Expression(Program::parse(expr, headers)?),
+        None => ScoreMethod::Column(rconf.single_selection(headers)?),
+    };
+
+    // NOTE: non-numerical expression results are ranked last
+    let lowest = if args.flag_reverse {
+        f64::INFINITY
+    } else {
+        f64::NEG_INFINITY
+    };
+
+    let score = |index: usize, record: &csv::ByteRecord| -> CliResult<Option<NotNan<f64>>> {
+        match &score_method {
+            ScoreMethod::Column(score_col) => Ok(std::str::from_utf8(&record[*score_col])
+                .unwrap_or("")
+                .parse::<NotNan<f64>>()
+                .ok()),
+            ScoreMethod::Expression(program) => {
+                let value = match program.run_with_record(index, record) {
+                    Ok(value) => value,
+                    Err(err) if args.flag_strict => Err(err)?,
+                    Err(_) => return Ok(Some(NotNan::new(lowest).unwrap())),
+                };
+
+                match value.try_as_f64().ok().and_then(|f| NotNan::new(f).ok()) {
+                    Some(f) => Ok(Some(f)),
+                    None if args.flag_strict => Err(format!(
+                        "--by expression did not return a number for row n°{}, but a {}",
+                        index + 1,
+                        value.type_of()
+                    ))?,
+                    None => Ok(Some(NotNan::new(lowest).unwrap())),
+                }
+            }
+        }
+    };
 
     let groupby_sel_opt = args
         .flag_groupby
         .map(|cols| cols.selection(headers, !args.flag_no_headers))
         .transpose()?;
 
+    let tiebreak_opt = args
+        .flag_tiebreak
+        .as_ref()
+        .map(|expr| match expr.trim().strip_prefix('-') {
+            Some(stripped) => Program::parse(stripped, headers).map(|p| (p, true)),
+            None => Program::parse(expr, headers).map(|p| (p, false)),
+        })
+        .transpose()?;
+
+    let tiebreak = |index: usize, record: &csv::ByteRecord| -> CliResult<Option<TieBreak>> {
+        Ok(match &tiebreak_opt {
+            None => None,
+            Some((program, descending)) => Some(TieBreak {
+                value: TieBreakValue::from(program.run_with_record(index, record)?),
+                descending: *descending,
+                index,
+            }),
+        })
+    };
+
     let mut wtr = Config::new(&args.flag_output).writer()?;
 
     if !args.flag_no_headers {
@@ -88,17 +247,18 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     macro_rules! run {
         ($heap:ident, $type:ident) => {{
             let mut record = csv::ByteRecord::new();
-            let mut heap = $heap::<$type<NotNan<f64>>, csv::ByteRecord>::with_capacity(
-                usize::from(args.flag_limit),
-            );
+            let mut heap =
+                $heap::<($type<NotNan<f64>>, Option<TieBreak>), csv::ByteRecord>::with_capacity(
+                    usize::from(args.flag_limit),
+                );
+            let mut index: usize = 0;
 
             while rdr.read_byte_record(&mut record)? {
-                if let Ok(score) = std::str::from_utf8(&record[score_col])
-                    .unwrap_or("")
-                    .parse::<NotNan<f64>>()
-                {
-                    heap.push_with($type(score), || record.clone());
+                if let Some(score) = score(index, &record)? {
+                    heap.push_with(($type(score), tiebreak(index, &record)?), || record.clone());
                 }
+
+                index += 1;
             }
 
             for (i, (_, record)) in heap.into_sorted_vec().into_iter().enumerate() {
@@ -116,31 +276,24 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             let mut record = csv::ByteRecord::new();
             let mut groups: ClusteredInsertHashmap<
                 GroupKey,
-                $heap<$type<NotNan<f64>>, csv::ByteRecord>,
+                $heap<($type<NotNan<f64>>, Option<TieBreak>), csv::ByteRecord>,
             > = ClusteredInsertHashmap::new();
+            let mut index: usize = 0;
 
             while rdr.read_byte_record(&mut record)? {
-                if let Ok(score) = std::str::from_utf8(&record[score_col])
-                    .unwrap_or("")
-                    .parse::<NotNan<f64>>()
-                {
+                if let Some(score) = score(index, &record)? {
+                    let key = ($type(score), tiebreak(index, &record)?);
                     let group = $sel
                         .select(&record)
                         .map(|cell| cell.to_vec())
                         .collect::<Vec<_>>();
 
-                    groups.insert_with_or_else(
-                        group,
-                        || {
-                            let mut heap = $heap::with_capacity(usize::from(args.flag_limit));
-                            heap.push_with($type(score), || record.clone());
-                            heap
-                        },
-                        |heap| {
-                            heap.push_with($type(score), || record.clone());
-                        },
-                    );
+                    groups
+                        .insert_with(group, || $heap::with_capacity(usize::from(args.flag_limit)))
+                        .push_with(key, || record.clone());
                 }
+
+                index += 1;
             }
 
             for heap in groups.into_values() {
@@ -3,7 +3,7 @@ use std::convert::TryFrom;
 use crate::cmd::moonblade::{
     run_moonblade_cmd, MoonbladeCmdArgs, MoonbladeErrorPolicy, MoonbladeMode,
 };
-use crate::config::Delimiter;
+use crate::config::{Comment, Delimiter};
 use crate::util;
 use crate::CliResult;
 
@@ -56,10 +56,24 @@ transform options:
                                  - "report": add a column containing error
                                  - "ignore": coerce result for row to null
                                  - "log": print error to stderr
-                               [default: panic].
+                               Defaults to "panic".
     --error-column <name>      Name of the column containing errors if
                                "-E/--errors" is set to "report".
                                [default: xan_error].
+    --errors-to <path>         Write evaluation errors, along with the index of
+                               the offending row, as an "index,error" CSV file
+                               at <path>. Note that this index starts at 0, like
+                               the one given by "xan enum", while logged errors
+                               report row numbers starting at 1. Implies
+                               "-E/--errors ignore" so that the main output is
+                               not polluted, and therefore cannot be used
+                               with -E/--errors.
+    --prelude <aggs>           Aggregations, using the same syntax as the "agg"
+                               command, to compute over the whole file in a first
+                               pass, e.g. 'max(value) as cmax'. Their results can
+                               then be referenced by name in the expression, as if
+                               they were columns, and must therefore not be named
+                               like an existing column. Does not work with stdin.
 
 Common options:
     -h, --help               Display this message
@@ -68,6 +82,8 @@ Common options:
                              as headers.
     -d, --delimiter <arg>    The field delimiter for reading CSV data.
                              Must be a single character.
+    --comment <char>         Skip lines starting with <char> when reading CSV
+                             data.
 "#;
 
 #[derive(Deserialize)]
@@ -81,10 +97,13 @@ struct Args {
     flag_cheatsheet: bool,
     flag_no_headers: bool,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_parallel: bool,
     flag_threads: Option<usize>,
-    flag_errors: String,
+    flag_errors: Option<String>,
     flag_error_column: String,
+    flag_errors_to: Option<String>,
+    flag_prelude: Option<String>,
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -96,6 +115,14 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         _ => None,
     };
 
+    let error_policy = match (&args.flag_errors_to, args.flag_errors) {
+        (Some(_), Some(_)) => Err("--errors-to cannot be used with -E/--errors!")?,
+        (Some(_), None) => MoonbladeErrorPolicy::Ignore,
+        (None, policy) => {
+            MoonbladeErrorPolicy::try_from(policy.unwrap_or_else(|| "panic".to_string()))?
+        }
+    };
+
     let moonblade_args = MoonbladeCmdArgs {
         print_cheatsheet: args.flag_cheatsheet,
         print_functions: args.flag_functions,
@@ -106,10 +133,13 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         output: args.flag_output,
         no_headers: args.flag_no_headers,
         delimiter: args.flag_delimiter,
+        comment: args.flag_comment,
         parallelization,
-        error_policy: MoonbladeErrorPolicy::try_from(args.flag_errors)?,
+        error_policy,
         error_column_name: Some(args.flag_error_column),
         mode: MoonbladeMode::Transform,
+        errors_to: args.flag_errors_to,
+        prelude: args.flag_prelude,
         ..Default::default()
     };
 
@@ -1,4 +1,7 @@
-use crate::config::{Config, Delimiter};
+use std::collections::{HashMap, HashSet};
+
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::SelectColumns;
 use crate::util;
 use crate::CliResult;
 
@@ -15,15 +18,26 @@ Will become:
 A,C
 B,D
 
+Note that the values of the first column naturally become the header row
+of the transposed file. Use --header-col if you want the header row to be
+taken from another column instead, in which case duplicate values will be
+disambiguated by adding a numerical suffix (e.g. \"name_1\").
+
 Usage:
     xan transpose [options] [<input>]
     xan transpose --help
 
+transpose options:
+    --header-col <column>  Column whose values will become the header row of
+                           the transposed file.
+
 Common options:
     -h, --help             Display this message
     -o, --output <file>    Write output to <file> instead of stdout.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize)]
@@ -31,6 +45,38 @@ struct Args {
     arg_input: Option<String>,
     flag_output: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
+    flag_header_col: Option<SelectColumns>,
+}
+
+fn disambiguate_headers<'a>(values: impl Iterator<Item = &'a [u8]>) -> csv::ByteRecord {
+    let mut used: HashSet<Vec<u8>> = HashSet::new();
+    let mut counters: HashMap<Vec<u8>, usize> = HashMap::new();
+    let mut headers = csv::ByteRecord::new();
+
+    for value in values {
+        let mut name = value.to_vec();
+
+        if used.contains(&name) {
+            let counter = counters.entry(value.to_vec()).or_insert(0);
+
+            loop {
+                *counter += 1;
+
+                let candidate = [value, format!("_{}", counter).as_bytes()].concat();
+
+                if !used.contains(&candidate) {
+                    name = candidate;
+                    break;
+                }
+            }
+        }
+
+        headers.push_field(&name);
+        used.insert(name);
+    }
+
+    headers
 }
 
 pub fn run(argv: &[&str]) -> CliResult<()> {
@@ -38,6 +84,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(true);
 
     let rdr = rconfig.reader()?;
@@ -53,7 +100,22 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let columns = records[0].len();
 
+    let header_col = args
+        .flag_header_col
+        .map(|sel| sel.single_selection(&records[0], true))
+        .transpose()?;
+
+    if let Some(h) = header_col {
+        wtr.write_byte_record(&disambiguate_headers(
+            records.iter().map(|record| &record[h]),
+        ))?;
+    }
+
     for i in 0..columns {
+        if header_col == Some(i) {
+            continue;
+        }
+
         output_record.clear();
 
         for record in records.iter() {
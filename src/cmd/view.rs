@@ -3,13 +3,13 @@ use std::io::{self, Write};
 use std::num::NonZeroUsize;
 use std::str::FromStr;
 
-use colored::{self, Colorize};
+use colored::{self, Colorize, Styles};
 use numfmt::{Formatter, Precision};
 use unicode_width::UnicodeWidthStr;
 
-use crate::config::{Config, Delimiter};
-use crate::select::SelectColumns;
-use crate::util::{self, ImmutableRecordHelpers};
+use crate::config::{Comment, Config, Delimiter};
+use crate::select::{SelectColumns, Selection};
+use crate::util::{self, ColorOrStyles, ImmutableRecordHelpers};
 use crate::CliResult;
 
 const HEADERS_ROWS: usize = 8;
@@ -195,10 +195,16 @@ the -e/--expand and -C/--force-colors flags before piping like so:
 
     $ xan view -eC file.csv | less -SR
 
+When a file has too many columns to be comfortably displayed as a table, it
+is possible to inspect a single row, given by its index, using the -r, --record
+flag. The row will then be displayed vertically, one column per line:
+
+    $ xan view -r 3 file.csv
+
 Finally, it is possible to customize the default behavior of this command through
 the \"XAN_VIEW_ARGS\" environment variable. This variable takes a series of
-supported flags: -t/--theme, -p/--pager, -l/--limit, -R/--rainbow, -E/--sanitize-emojis,
-and -S/--significance, -I/--hide-index.
+supported flags: -t/--theme, -p/--pager, -l/--limit, -R/--rainbow, -w/--wrap, --max-width,
+as well as -E/--sanitize-emojis, -S/--significance, -I/--hide-index and -#/--number.
 
 So if you want, for instance, to use the borderles theme, hide the index column and
 restrict the number of floating points decimals to be shown by default:
@@ -223,6 +229,18 @@ view options:
                             set to 0 to disable the limit.
                             [default: 100]
     -R, --rainbow           Alternating colors for columns, rather than color by value type.
+    -y, --color-types       Infer the dominant type of each column from the displayed rows
+                            and emphasize cells whose type does not match it, e.g. to spot
+                            stray text in a numeric column. Cannot be used with -R, --rainbow.
+    --no-color              Disable colors altogether. Colors are also disabled when
+                            the NO_COLOR environment variable is set.
+    -w, --wrap              Wrap the content of cells too long to fit their column on
+                            multiple lines instead of truncating them with an ellipsis.
+    --no-wrap               Truncate the content of cells too long to fit their column
+                            with an ellipsis. This is the default, but can be useful to
+                            override XAN_VIEW_ARGS.
+    --max-width <n>         Maximum width, in terminal columns, a single column can
+                            take up when displayed.
     --cols <num>            Width of the graph in terminal columns, i.e. characters.
                             Defaults to using all your terminal's width or 80 if
                             terminal's size cannot be found (i.e. when piping to file).
@@ -234,8 +252,13 @@ view options:
     -E, --sanitize-emojis   Replace emojis by their shortcode to avoid formatting issues.
     -S, --significance <n>  Maximum floating point significance used to format numbers.
     -I, --hide-index        Hide the row index on the left.
+    -#, --number            Number the rows in the index column on the left starting
+                            from 1, i.e. the first data row, instead of 0. Useful to
+                            refer to rows when collaborating. Overrides -I, --hide-index.
     -H, --hide-headers      Hide the headers.
     -M, --hide-info         Hide information about number of displayed columns, rows etc.
+    -r, --record <index>    Only display the row at given index (starting from 0),
+                            vertically, one column per line.
     -g, --groupby <cols>    Isolate and emphasize groups of rows, represented by consecutive
                             rows with identical values in selected columns.
 
@@ -245,6 +268,8 @@ Common options:
                            the file header.
     -d, --delimiter <arg>  The field delimiter for reading CSV data.
                            Must be a single character.
+    --comment <char>       Skip lines starting with <char> when reading CSV
+                           data.
 ";
 
 #[derive(Deserialize, Debug)]
@@ -255,17 +280,25 @@ struct Args {
     flag_theme: String,
     flag_cols: Option<String>,
     flag_delimiter: Option<Delimiter>,
+    flag_comment: Option<Comment>,
     flag_no_headers: bool,
     flag_force_colors: bool,
+    flag_no_color: bool,
+    flag_color_types: bool,
     flag_all: bool,
     flag_limit: usize,
     flag_rainbow: bool,
+    flag_wrap: bool,
+    flag_no_wrap: bool,
+    flag_max_width: Option<NonZeroUsize>,
     flag_expand: bool,
     flag_sanitize_emojis: bool,
     flag_hide_index: bool,
+    flag_number: bool,
     flag_hide_headers: bool,
     flag_hide_info: bool,
     flag_groupby: Option<SelectColumns>,
+    flag_record: Option<usize>,
     flag_significance: Option<NonZeroUsize>,
 }
 
@@ -301,6 +334,10 @@ impl Args {
             from_argv.flag_rainbow = true;
         }
 
+        if !from_argv.flag_color_types && from_env.flag_color_types {
+            from_argv.flag_color_types = true;
+        }
+
         if !from_argv.flag_sanitize_emojis && from_env.flag_sanitize_emojis {
             from_argv.flag_sanitize_emojis = true;
         }
@@ -313,10 +350,125 @@ impl Args {
             from_argv.flag_significance = from_env.flag_significance;
         }
 
+        if !from_argv.flag_wrap && !from_argv.flag_no_wrap && from_env.flag_wrap {
+            from_argv.flag_wrap = true;
+        }
+
+        if from_argv.flag_max_width.is_none() && from_env.flag_max_width.is_some() {
+            from_argv.flag_max_width = from_env.flag_max_width;
+        }
+
+        if !from_argv.flag_number && from_env.flag_number {
+            from_argv.flag_number = true;
+        }
+
+        // NOTE: numbering rows requires the index column
+        if from_argv.flag_number {
+            from_argv.flag_hide_index = false;
+        }
+
         from_argv
     }
 }
 
+// NOTE: we setup the pager when everything has been read and process and no error
+// occurred along the way, so that we don't get to read a paged error
+fn setup_pager(args: &Args) -> CliResult<()> {
+    if args.flag_pager {
+        #[cfg(not(windows))]
+        {
+            pager::Pager::with_pager("less -SR").setup();
+        }
+
+        #[cfg(windows)]
+        {
+            Err("The -p/--pager flag does not work on windows, sorry :'(".to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+fn view_record<R: io::Read, W: Write>(
+    args: &Args,
+    mut output: W,
+    mut rdr: csv::Reader<R>,
+    sel: &Selection,
+    index: usize,
+    cols: usize,
+) -> CliResult<()> {
+    let headers = sel
+        .select_string_record(rdr.headers()?)
+        .enumerate()
+        .map(|(i, h)| {
+            if args.flag_no_headers {
+                i.to_string()
+            } else {
+                h.to_string()
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let record = match rdr.into_records().nth(index) {
+        None => Err(format!("row {} does not exist!", index))?,
+        Some(record) => record?,
+    };
+
+    let max_header_width = headers
+        .iter()
+        .map(|h| h.width())
+        .max()
+        .ok_or("file is empty")?;
+
+    if cols < max_header_width + 2 {
+        Err("not enough cols provided to safely print data!")?;
+    }
+
+    let max_value_width = cols - max_header_width - 1;
+    let emoji_sanitizer = util::EmojiSanitizer::new();
+
+    setup_pager(args)?;
+
+    writeln!(output, "{}", format!("Row n°{}", index).bold())?;
+    writeln!(output, "{}", "─".repeat(cols).dimmed())?;
+
+    for (i, (header, cell)) in headers
+        .iter()
+        .zip(sel.select_string_record(&record))
+        .enumerate()
+    {
+        let mut cell = match cell.trim() {
+            "" => "<empty>".to_string(),
+            _ => cell.to_string(),
+        };
+
+        if args.flag_sanitize_emojis {
+            cell = emoji_sanitizer.sanitize(&cell);
+        }
+
+        let cell_colorizer = if args.flag_rainbow {
+            util::colorizer_by_rainbow(i, &cell)
+        } else {
+            util::colorizer_by_type(&cell)
+        };
+
+        let cell = util::unicode_aware_wrap(
+            &util::sanitize_text_for_multi_line_printing(&cell),
+            max_value_width,
+            max_header_width + 1,
+        );
+
+        writeln!(
+            output,
+            "{}{}",
+            util::unicode_aware_rpad(header, max_header_width + 1, " "),
+            util::colorize(&cell_colorizer, &cell)
+        )?;
+    }
+
+    Ok(output.flush()?)
+}
+
 pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut args: Args = util::get_args(USAGE, argv)?;
     args.resolve();
@@ -332,9 +484,23 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let mut env_args: Args = util::get_args(USAGE, &env_var_argv)?;
     env_args.resolve();
 
+    if args.flag_wrap && args.flag_no_wrap {
+        Err("-w, --wrap cannot be used with --no-wrap!")?;
+    }
+
+    if args.flag_no_color && args.flag_force_colors {
+        Err("--no-color cannot be used with -C, --force-colors!")?;
+    }
+
     let args = Args::merge(env_args, args);
 
-    if args.infer_force_colors() {
+    if args.flag_color_types && args.flag_rainbow {
+        Err("-y, --color-types cannot be used with -R, --rainbow!")?;
+    }
+
+    if args.flag_no_color {
+        colored::control::set_override(false);
+    } else if args.infer_force_colors() {
         colored::control::set_override(true);
     }
 
@@ -353,6 +519,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let rconfig = Config::new(&args.arg_input)
         .delimiter(args.flag_delimiter)
+        .comment(args.flag_comment)
         .no_headers(args.flag_no_headers)
         .select(args.flag_select.clone());
 
@@ -360,6 +527,10 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     let byte_headers = rdr.byte_headers()?;
     let sel = rconfig.selection(byte_headers)?;
 
+    if let Some(index) = args.flag_record {
+        return view_record(&args, &output, rdr, &sel, index, cols);
+    }
+
     let mut groupby_sel_opt = args
         .flag_groupby
         .clone()
@@ -437,7 +608,8 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                         .collect::<csv::StringRecord>();
 
                     if !args.flag_hide_index {
-                        record = record.prepend(&i.to_string());
+                        let n = if args.flag_number { i + 1 } else { i };
+                        record = record.prepend(&n.to_string());
                     }
 
                     records.push(record);
@@ -456,6 +628,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         records
     };
 
+    let dominant_types = if args.flag_color_types {
+        Some(infer_dominant_types(&records, headers.len()))
+    } else {
+        None
+    };
+
     let need_to_repeat_headers = match rows {
         None => true,
         Some(r) => records.len() + HEADERS_ROWS > r,
@@ -465,7 +643,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
         .iter()
         .enumerate()
         .map(|(i, h)| {
-            usize::max(
+            let width = usize::max(
                 if args.flag_hide_headers { 0 } else { h.width() },
                 records
                     .iter()
@@ -475,7 +653,12 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     })
                     .max()
                     .unwrap_or(0),
-            )
+            );
+
+            match args.flag_max_width {
+                Some(max_width) => width.min(max_width.get()),
+                None => width,
+            }
         })
         .collect();
 
@@ -490,19 +673,7 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
 
     let all_columns_shown = displayed_columns.len() == headers.len();
 
-    // NOTE: we setup the pager when everything has been read and process and no error
-    // occurred along the way, so that we don't get to read a paged error
-    if args.flag_pager {
-        #[cfg(not(windows))]
-        {
-            pager::Pager::with_pager("less -SR").setup();
-        }
-
-        #[cfg(windows)]
-        {
-            Err("The -p/--pager flag does not work on windows, sorry :'(".to_string())?;
-        }
-    }
+    setup_pager(&args)?;
 
     let write_info = || -> Result<(), io::Error> {
         if args.flag_hide_info {
@@ -738,14 +909,15 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
             write_horizontal_ruler(HRPosition::Middle)?;
         }
 
-        let row: Vec<colored::ColoredString> = displayed_columns
+        // NOTE: each cell can span multiple lines when wrapping
+        let cells: Vec<Vec<colored::ColoredString>> = displayed_columns
             .iter()
             .map(|col| (col, &record[col.index]))
             .enumerate()
             .map(|(i, (col, cell))| {
                 if let Some(groupby_sel) = &groupby_sel_opt {
                     if need_to_erase_sel && groupby_sel.contains(i) {
-                        return " ".repeat(col.allowed_width).normal();
+                        return vec![" ".repeat(col.allowed_width).normal()];
                     }
                 }
 
@@ -760,11 +932,44 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                     util::colorizer_by_type(cell)
                 };
 
+                let mismatched = match &dominant_types {
+                    Some(types) => {
+                        colorizer != ColorOrStyles::Styles(Styles::Dimmed)
+                            && types[col.index]
+                                .as_ref()
+                                .is_some_and(|dominant| dominant != &colorizer)
+                    }
+                    None => false,
+                };
+
+                let colorize = |string: &str| {
+                    let colored = util::colorize(&colorizer, string);
+
+                    if mismatched {
+                        colored.bold().underline()
+                    } else {
+                        colored
+                    }
+                };
+
                 if !args.flag_hide_index && i == 0 {
-                    util::unicode_aware_rpad_with_ellipsis(cell, col.allowed_width, " ").dimmed()
+                    vec![
+                        util::unicode_aware_rpad_with_ellipsis(cell, col.allowed_width, " ")
+                            .dimmed(),
+                    ]
+                } else if args.flag_wrap && cell.width() > col.allowed_width {
+                    textwrap::wrap(cell, col.allowed_width)
+                        .iter()
+                        .map(|line| {
+                            colorize(&util::unicode_aware_rpad_with_ellipsis(
+                                line,
+                                col.allowed_width,
+                                " ",
+                            ))
+                        })
+                        .collect()
                 } else {
-                    util::colorize(
-                        &colorizer,
+                    vec![colorize(
                         &util::unicode_aware_highlighted_pad_with_ellipsis(
                             false,
                             cell,
@@ -772,12 +977,28 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
                             " ",
                             true,
                         ),
-                    )
+                    )]
                 }
             })
             .collect();
 
-        write_row(row, record_i % 2 == 0)?;
+        let height = cells.iter().map(|lines| lines.len()).max().unwrap_or(1);
+
+        for line in 0..height {
+            let row: Vec<colored::ColoredString> = cells
+                .iter()
+                .zip(displayed_columns.iter())
+                .map(|(lines, col)| {
+                    lines
+                        .get(line)
+                        .cloned()
+                        .unwrap_or_else(|| " ".repeat(col.allowed_width).normal())
+                })
+                .collect();
+
+            write_row(row, record_i % 2 == 0)?;
+        }
+
         record_i += 1;
     }
 
@@ -806,6 +1027,40 @@ pub fn run(argv: &[&str]) -> CliResult<()> {
     Ok(())
 }
 
+// NOTE: the dominant type of a column is the most frequent one among its
+// non-null cells, as colored by `util::colorizer_by_type`.
+fn infer_dominant_types(records: &[csv::StringRecord], len: usize) -> Vec<Option<ColorOrStyles>> {
+    (0..len)
+        .map(|i| {
+            let mut counts: Vec<(ColorOrStyles, usize)> = Vec::new();
+
+            for record in records {
+                let cell = record[i].trim();
+
+                if cell.is_empty() {
+                    continue;
+                }
+
+                let colorizer = util::colorizer_by_type(cell);
+
+                if colorizer == ColorOrStyles::Styles(Styles::Dimmed) {
+                    continue;
+                }
+
+                match counts.iter_mut().find(|(c, _)| c == &colorizer) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((colorizer, 1)),
+                }
+            }
+
+            counts
+                .into_iter()
+                .max_by_key(|(_, count)| *count)
+                .map(|(colorizer, _)| colorizer)
+        })
+        .collect()
+}
+
 fn adjust_column_widths(widths: &[usize], max_width: usize) -> Vec<usize> {
     widths.iter().map(|m| usize::min(*m, max_width)).collect()
 }
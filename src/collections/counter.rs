@@ -25,6 +25,15 @@ impl<K: Eq + Hash + Send + Ord> ExactCounter<K> {
             .or_insert(1);
     }
 
+    pub fn merge(&mut self, other: Self) {
+        for (key, count) in other.map {
+            self.map
+                .entry(key)
+                .and_modify(|c| *c += count)
+                .or_insert(count);
+        }
+    }
+
     pub fn into_total_and_sorted_vec(self, parallel: bool) -> (u64, Vec<(K, u64)>) {
         let mut total: u64 = 0;
 
@@ -136,6 +145,17 @@ impl<K: Eq + Hash + Send + Ord> Counter<K> {
         }
     }
 
+    // NOTE: approximate counters cannot be merged, so callers must
+    // only merge exact ones.
+    pub fn merge(&mut self, other: Self) {
+        match (self, other) {
+            (Self::Exact(inner), Self::Exact(other_inner)) => {
+                inner.merge(other_inner);
+            }
+            _ => unreachable!(),
+        }
+    }
+
     pub fn into_total_and_items(
         self,
         limit: Option<usize>,
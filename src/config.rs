@@ -7,7 +7,7 @@ use std::io::{self, prelude::*, BufReader, IsTerminal, Read, SeekFrom};
 use std::ops::Deref;
 use std::path::PathBuf;
 
-use crate::index::Indexed;
+use crate::index::{Indexed, KeyIndex};
 use flate2::read::GzDecoder;
 use serde::de::{Deserialize, Deserializer, Error};
 
@@ -59,6 +59,33 @@ impl<'de> Deserialize<'de> for Delimiter {
     }
 }
 
+/// Comment represents the character that can be passed from the command line
+/// so that lines starting with it are skipped when reading CSV data.
+#[derive(Clone, Copy, Debug)]
+pub struct Comment(pub u8);
+
+impl Comment {
+    pub fn as_byte(self) -> u8 {
+        self.0
+    }
+}
+
+impl<'de> Deserialize<'de> for Comment {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Comment, D::Error> {
+        let c = String::deserialize(d)?;
+        match c.as_bytes() {
+            [b] if b.is_ascii() => Ok(Comment(*b)),
+            _ => {
+                let msg = format!(
+                    "Could not convert '{}' to a single ASCII comment character.",
+                    c
+                );
+                Err(D::Error::custom(msg))
+            }
+        }
+    }
+}
+
 struct ReverseRead {
     input: Box<File>,
     offset: u64,
@@ -125,6 +152,7 @@ pub struct Config {
     double_quote: bool,
     escape: Option<u8>,
     quoting: bool,
+    comment: Option<u8>,
 }
 
 impl Config {
@@ -159,6 +187,7 @@ impl Config {
             double_quote: true,
             escape: None,
             quoting: true,
+            comment: None,
         }
     }
 
@@ -173,6 +202,11 @@ impl Config {
         self
     }
 
+    pub fn comment(mut self, c: Option<Comment>) -> Config {
+        self.comment = c.map(|c| c.as_byte());
+        self
+    }
+
     pub fn no_headers(mut self, mut yes: bool) -> Config {
         if env::var("XAN_TOGGLE_HEADERS").unwrap_or("0".to_owned()) == "1" {
             yes = !yes;
@@ -332,6 +366,30 @@ impl Config {
         }
     }
 
+    pub fn key_indexed(&self) -> CliResult<Option<KeyIndex<BufReader<fs::File>>>> {
+        let path = match &self.path {
+            None => return Ok(None),
+            Some(p) => p,
+        };
+
+        let kidx_file = match fs::File::open(util::key_idx_path(path)) {
+            Err(_) => return Ok(None),
+            Ok(f) => f,
+        };
+
+        let data_modified = util::last_modified(&fs::metadata(path)?);
+        let kidx_modified = util::last_modified(&kidx_file.metadata()?);
+        if data_modified > kidx_modified {
+            return Err(CliError::Other(
+                "The CSV file was modified after the key index file. \
+                 Please re-create the key index."
+                    .to_string(),
+            ));
+        }
+
+        Ok(Some(KeyIndex::open(BufReader::new(kidx_file))?))
+    }
+
     pub fn io_reader(&self) -> io::Result<Box<dyn io::Read + Send + 'static>> {
         Ok(match self.path {
             None => {
@@ -496,6 +554,7 @@ impl Config {
             .quote(self.quote)
             .quoting(self.quoting)
             .escape(self.escape)
+            .comment(self.comment)
             .from_reader(rdr)
     }
 
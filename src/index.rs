@@ -1,8 +1,10 @@
-use std::io;
+use std::io::{self, SeekFrom};
 use std::ops;
 
 use csv_index::RandomAccessSimple;
+use transient_btree_index::{BtreeConfig, BtreeIndex};
 
+use crate::select::Selection;
 use crate::CliResult;
 
 /// Indexed composes a CSV reader with a simple random access index.
@@ -61,3 +63,210 @@ impl<R: io::Read + io::Seek, I: io::Read + io::Seek> Indexed<R, I> {
         Ok(())
     }
 }
+
+const KEY_INDEX_MAGIC: &[u8; 8] = b"XANKIDX1";
+
+fn write_u64<W: io::Write>(wtr: &mut W, n: u64) -> io::Result<u64> {
+    wtr.write_all(&n.to_be_bytes())?;
+    Ok(8)
+}
+
+fn read_u64<R: io::Read>(rdr: &mut R) -> io::Result<u64> {
+    let mut buf = [0u8; 8];
+    rdr.read_exact(&mut buf)?;
+    Ok(u64::from_be_bytes(buf))
+}
+
+fn write_bytes<W: io::Write>(wtr: &mut W, bytes: &[u8]) -> io::Result<u64> {
+    write_u64(wtr, bytes.len() as u64)?;
+    wtr.write_all(bytes)?;
+    Ok(8 + bytes.len() as u64)
+}
+
+fn read_bytes<R: io::Read>(rdr: &mut R) -> io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; read_u64(rdr)? as usize];
+    rdr.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Create a key index mapping the values of the selected columns to the
+/// positions of the records having them.
+///
+/// The key index is a sidecar file with the following layout, where all
+/// integers are big-endian u64 and all byte strings are prefixed by their
+/// length:
+///
+///   1. the magic bytes `XANKIDX1`
+///   2. the number of key columns, followed by their names
+///   3. the entries, sorted by key, each one being made of the key fields
+///      followed by the position of the record
+///   4. the offsets of the entries, so they can be binary searched
+///   5. the offset of the previous table, followed by the number of entries
+///
+/// Entries are sorted using an on-disk B-tree so that memory usage remains
+/// bounded, save for the offsets of the entries.
+pub fn create_key_index<R: io::Read, W: io::Write>(
+    rdr: &mut csv::Reader<R>,
+    sel: &Selection,
+    wtr: &mut W,
+) -> CliResult<()> {
+    let mut btree_index = BtreeIndex::<(Vec<Vec<u8>>, u64), ()>::with_capacity(
+        BtreeConfig::default().fixed_value_size(0),
+        1024,
+    )?;
+
+    let headers = rdr.byte_headers()?.clone();
+    let mut record = csv::ByteRecord::new();
+    let mut i: u64 = 0;
+
+    while rdr.read_byte_record(&mut record)? {
+        btree_index.insert((sel.collect(&record), i), ())?;
+        i += 1;
+    }
+
+    let mut offset: u64 = 0;
+
+    wtr.write_all(KEY_INDEX_MAGIC)?;
+    offset += KEY_INDEX_MAGIC.len() as u64;
+
+    offset += write_u64(wtr, sel.len() as u64)?;
+
+    for name in sel.select(&headers) {
+        offset += write_bytes(wtr, name)?;
+    }
+
+    let mut offsets: Vec<u64> = Vec::new();
+
+    for result in btree_index.into_iter()? {
+        let ((key, position), _) = result?;
+
+        offsets.push(offset);
+
+        for field in key.iter() {
+            offset += write_bytes(wtr, field)?;
+        }
+
+        offset += write_u64(wtr, position)?;
+    }
+
+    for entry_offset in offsets.iter() {
+        write_u64(wtr, *entry_offset)?;
+    }
+
+    write_u64(wtr, offset)?;
+    write_u64(wtr, offsets.len() as u64)?;
+
+    Ok(())
+}
+
+/// KeyIndex gives access to a key index created with `create_key_index`,
+/// without loading it in memory.
+pub struct KeyIndex<I> {
+    idx: I,
+    headers: Vec<Vec<u8>>,
+    table_offset: u64,
+    len: u64,
+}
+
+impl<I: io::Read + io::Seek> KeyIndex<I> {
+    /// Opens a key index.
+    pub fn open(mut idx: I) -> CliResult<KeyIndex<I>> {
+        let mut magic = [0u8; 8];
+        idx.seek(SeekFrom::Start(0))?;
+        idx.read_exact(&mut magic)?;
+
+        if &magic != KEY_INDEX_MAGIC {
+            Err("invalid key index file!")?;
+        }
+
+        let arity = read_u64(&mut idx)?;
+        let headers = (0..arity)
+            .map(|_| read_bytes(&mut idx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        idx.seek(SeekFrom::End(-16))?;
+        let table_offset = read_u64(&mut idx)?;
+        let len = read_u64(&mut idx)?;
+
+        Ok(KeyIndex {
+            idx,
+            headers,
+            table_offset,
+            len,
+        })
+    }
+
+    /// Return the names of the columns used as key.
+    pub fn headers(&self) -> &[Vec<u8>] {
+        &self.headers
+    }
+
+    fn entry(&mut self, i: u64) -> io::Result<(Vec<Vec<u8>>, u64)> {
+        self.idx.seek(SeekFrom::Start(self.table_offset + i * 8))?;
+        let offset = read_u64(&mut self.idx)?;
+
+        self.idx.seek(SeekFrom::Start(offset))?;
+
+        let key = (0..self.headers.len())
+            .map(|_| read_bytes(&mut self.idx))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((key, read_u64(&mut self.idx)?))
+    }
+
+    /// Return the positions of the records matching the given key, in file
+    /// order, using a binary search.
+    pub fn get(&mut self, key: &[Vec<u8>]) -> CliResult<Vec<u64>> {
+        let mut lo: u64 = 0;
+        let mut hi: u64 = self.len;
+
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+
+            if self.entry(mid)?.0.as_slice() < key {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+
+        let mut positions = Vec::new();
+
+        while lo < self.len {
+            let (entry_key, position) = self.entry(lo)?;
+
+            if entry_key.as_slice() != key {
+                break;
+            }
+
+            positions.push(position);
+            lo += 1;
+        }
+
+        Ok(positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_index() {
+        let data = "name,color\njohn,blue\nmary,red\njohn,green\nlucy,red\n";
+        let mut rdr = csv::Reader::from_reader(data.as_bytes());
+        let sel = Selection::full(1);
+
+        let mut buf = Vec::new();
+        create_key_index(&mut rdr, &sel, &mut buf).unwrap();
+
+        let mut index = KeyIndex::open(io::Cursor::new(buf)).unwrap();
+
+        assert_eq!(index.headers(), &[b"name".to_vec()]);
+        assert_eq!(index.get(&[b"john".to_vec()]).unwrap(), vec![0, 2]);
+        assert_eq!(index.get(&[b"lucy".to_vec()]).unwrap(), vec![3]);
+        assert_eq!(index.get(&[b"mary".to_vec()]).unwrap(), vec![1]);
+        assert_eq!(index.get(&[b"zoe".to_vec()]).unwrap(), Vec::<u64>::new());
+        assert_eq!(index.get(&[b"a".to_vec()]).unwrap(), Vec::<u64>::new());
+    }
+}
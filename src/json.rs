@@ -139,27 +139,34 @@ enum JSONTraversalState {
 
 type JSONTraversalStack = Vec<JSONTraversalState>;
 
-fn traverse_to_build_stack(value: &Value, stack: &mut JSONTraversalStack, depth: usize) {
+fn traverse_to_build_stack(
+    value: &Value,
+    stack: &mut JSONTraversalStack,
+    depth: usize,
+    sort_keys: bool,
+) {
     match value {
         Value::Object(map) => {
             let mut items = map.iter().collect::<Vec<_>>();
 
             // NOTE: we put scalar values first, then nested ones and we also sort by key
-            items.sort_by_key(|i| {
-                (
-                    if matches!(i.1, Value::Object(_)) {
-                        1
-                    } else {
-                        0
-                    },
-                    i.0,
-                )
-            });
+            if sort_keys {
+                items.sort_by_key(|i| {
+                    (
+                        if matches!(i.1, Value::Object(_)) {
+                            1
+                        } else {
+                            0
+                        },
+                        i.0,
+                    )
+                });
+            }
 
             for (k, v) in items {
                 stack.push(JSONTraversalState::Delve(k.to_string(), depth));
 
-                traverse_to_build_stack(v, stack, depth + 1);
+                traverse_to_build_stack(v, stack, depth + 1, sort_keys);
 
                 stack.push(JSONTraversalState::Pop(depth));
             }
@@ -309,7 +316,7 @@ where
 
         // Emitting headers
         if !headers_emitted {
-            traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0);
+            traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0, true);
             callback(&headers_from_stack(&stack))?;
 
             for sample in sampled_records.iter() {
@@ -327,7 +334,7 @@ where
 
     // Sample was larger than the file
     if !sampled_records.is_empty() {
-        traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0);
+        traverse_to_build_stack(&merged_value_from_sample, &mut stack, 0, true);
         callback(&headers_from_stack(&stack))?;
 
         for sample in sampled_records.iter() {
@@ -339,6 +346,49 @@ where
     Ok(())
 }
 
+// NOTE: flattens JSON objects into CSV columns, using a schema built from the
+// union of the keys of all the given values.
+pub struct JSONFlattener {
+    stack: JSONTraversalStack,
+}
+
+impl JSONFlattener {
+    fn build<'a>(values: impl Iterator<Item = &'a Value>, sort_keys: bool) -> Self {
+        let mut merged_value = Value::Object(serde_json::Map::new());
+
+        for value in values.filter(|value| value.is_object()) {
+            merge(&mut merged_value, value);
+        }
+
+        let mut stack = JSONTraversalStack::new();
+        traverse_to_build_stack(&merged_value, &mut stack, 0, sort_keys);
+
+        Self { stack }
+    }
+
+    pub fn from_values<'a>(values: impl Iterator<Item = &'a Value>) -> Self {
+        Self::build(values, true)
+    }
+
+    // NOTE: keys are emitted in the order they were first seen, which relies
+    // on serde_json's preserve_order feature when merging the values.
+    pub fn from_values_in_order<'a>(values: impl Iterator<Item = &'a Value>) -> Self {
+        Self::build(values, false)
+    }
+
+    pub fn headers(&self) -> StringRecord {
+        if self.stack.is_empty() {
+            return StringRecord::new();
+        }
+
+        headers_from_stack(&self.stack)
+    }
+
+    pub fn fill_record(&self, value: &Value, record: &mut StringRecord) {
+        fill_record(value, record, &self.stack);
+    }
+}
+
 const JSON_MAX_SAFE_INTEGER: i64 = 9007199254740991;
 
 #[derive(Debug, Clone, Copy)]
@@ -42,6 +42,7 @@ macro_rules! command_list {
     search      Search CSV data with regexes
     filter      Only keep some CSV rows based on an evaluated expression
     slice       Slice rows of CSV file
+    lookup      Fetch rows by key using a key index
     top         Find top rows of a CSV file according to some column
     sample      Randomly sample CSV data
 
@@ -56,6 +57,7 @@ macro_rules! command_list {
     stats            Compute basic statistics
     agg              Aggregate data from CSV file
     bins             Dispatch numeric columns into bins
+    sql              Query CSV data using a subset of SQL
 
 ## Combine multiple CSV files
     cat         Concatenate by row or column
@@ -223,6 +225,7 @@ enum Command {
     Index,
     Input,
     Join,
+    Lookup,
     Map,
     Matrix,
     Merge,
@@ -244,6 +247,7 @@ enum Command {
     Slice,
     Sort,
     Split,
+    Sql,
     Stats,
     To,
     Tokenize,
@@ -311,6 +315,7 @@ impl Command {
             Command::Input => cmd::input::run(argv),
             Command::Join => cmd::join::run(argv),
             Command::Network => cmd::network::run(argv),
+            Command::Lookup => cmd::lookup::run(argv),
             Command::Map => cmd::map::run(argv),
             Command::Matrix => cmd::matrix::run(argv),
             Command::Merge => cmd::merge::run(argv),
@@ -329,6 +334,7 @@ impl Command {
             Command::Slice => cmd::slice::run(argv),
             Command::Sort => cmd::sort::run(argv),
             Command::Split => cmd::split::run(argv),
+            Command::Sql => cmd::sql::run(argv),
             Command::Stats => cmd::stats::run(argv),
             Command::To => cmd::to::run(argv),
             Command::Tokenize => cmd::tokenize::run(argv),
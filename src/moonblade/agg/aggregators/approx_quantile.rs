@@ -49,6 +49,10 @@ impl ApproxQuantiles {
         self.flush();
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty() && self.digest.as_ref().unwrap().is_empty()
+    }
+
     pub fn get(&self, q: f64) -> f64 {
         self.digest.as_ref().unwrap().estimate_quantile(q)
     }
@@ -80,6 +80,18 @@ impl Numbers {
         Some(median)
     }
 
+    // NOTE: median absolute deviation from the interpolated median
+    pub fn mad(&self) -> Option<DynamicNumber> {
+        let median = self.median(&MedianType::Interpolation)?;
+
+        let mut deviations = Numbers {
+            numbers: self.numbers.iter().map(|n| (*n - median).abs()).collect(),
+        };
+
+        deviations.finalize(false);
+        deviations.median(&MedianType::Interpolation)
+    }
+
     // NOTE: using the inclusive method from https://github.com/python/cpython/blob/3.12/Lib/statistics.py
     pub fn quantiles(&self, n: usize) -> Option<Vec<DynamicNumber>> {
         let l = self.numbers.len();
@@ -268,6 +280,12 @@ mod tests {
             Some(DynamicNumber::Float(4.0))
         );
 
+        // Median absolute deviation
+        assert_eq!(no_numbers.mad(), None);
+        assert_eq!(lone_numbers.mad(), Some(DynamicNumber::Integer(0)));
+        assert_eq!(odd_numbers.mad(), Some(DynamicNumber::Integer(2)));
+        assert_eq!(even_numbers.mad(), Some(DynamicNumber::Float(2.5)));
+
         // Quartiles
         fn manual_quartiles(n: &Numbers) -> Option<Vec<DynamicNumber>> {
             Some(vec![
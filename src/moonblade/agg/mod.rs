@@ -3,5 +3,5 @@ mod program;
 mod stats;
 
 pub use aggregators::CovarianceWelford;
-pub use program::{AggregationProgram, GroupAggregationProgram};
+pub use program::{is_aggregation_function, AggregationProgram, GroupAggregationProgram};
 pub use stats::Stats;
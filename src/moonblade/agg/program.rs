@@ -11,7 +11,7 @@ use super::aggregators::{
 use crate::collections::ClusteredInsertHashmap;
 use crate::moonblade::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
 use crate::moonblade::interpreter::{
-    concretize_expression, eval_expression, ConcreteExpr, EvaluationContext,
+    concretize_expression, eval_expression, ConcreteExpr, EvaluationContext, Program,
 };
 use crate::moonblade::parser::{parse_aggregations, Aggregations};
 use crate::moonblade::types::{DynamicNumber, DynamicValue, FunctionArguments};
@@ -99,7 +99,7 @@ impl Aggregator {
                 DynamicValue::from(inner.get())
             }
             (ConcreteAggregationMethod::ApproxQuantile(q), Self::ApproxQuantiles(inner)) => {
-                DynamicValue::from(inner.get(*q))
+                DynamicValue::from((!inner.is_empty()).then(|| inner.get(*q)))
             }
             (ConcreteAggregationMethod::ArgTop(_, expr_opt, separator), Self::ArgTop(inner)) => {
                 DynamicValue::from(match expr_opt {
@@ -129,6 +129,9 @@ impl Aggregator {
             (ConcreteAggregationMethod::Cardinality, Self::Frequencies(inner)) => {
                 DynamicValue::from(inner.cardinality())
             }
+            (ConcreteAggregationMethod::CountDistinctWhere, Self::Frequencies(inner)) => {
+                DynamicValue::from(inner.cardinality())
+            }
             (ConcreteAggregationMethod::Correlation, Self::CovarianceWelford(inner)) => {
                 DynamicValue::from(inner.correlation())
             }
@@ -193,12 +196,24 @@ impl Aggregator {
             (ConcreteAggregationMethod::Median(median_type), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.median(median_type))
             }
+            (ConcreteAggregationMethod::Iqr, Self::Numbers(inner)) => {
+                DynamicValue::from(inner.quartiles().map(|q| q[2] - q[0]))
+            }
+            (ConcreteAggregationMethod::Mad, Self::Numbers(inner)) => {
+                DynamicValue::from(inner.mad())
+            }
             (ConcreteAggregationMethod::Quantile(p), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.quantile(*p))
             }
             (ConcreteAggregationMethod::Quartile(idx), Self::Numbers(inner)) => {
                 DynamicValue::from(inner.quartiles().map(|q| q[*idx]))
             }
+            (ConcreteAggregationMethod::Span, Self::NumericExtent(inner)) => {
+                DynamicValue::from(inner.min().zip(inner.max()).map(|(min, max)| max - min))
+            }
+            (ConcreteAggregationMethod::Span, Self::ArgExtent(inner)) => {
+                DynamicValue::from(inner.min().zip(inner.max()).map(|(min, max)| max - min))
+            }
             (ConcreteAggregationMethod::Max, Self::NumericExtent(inner)) => {
                 DynamicValue::from(inner.max())
             }
@@ -365,7 +380,9 @@ impl CompositeAggregator {
             | ConcreteAggregationMethod::Correlation => {
                 upsert_aggregator!(CovarianceWelford)
             }
-            ConcreteAggregationMethod::Min | ConcreteAggregationMethod::Max => {
+            ConcreteAggregationMethod::Min
+            | ConcreteAggregationMethod::Max
+            | ConcreteAggregationMethod::Span => {
                 // NOTE: if some ArgExtent already exists, we merge into it.
                 match self
                     .methods
@@ -417,6 +434,8 @@ impl CompositeAggregator {
                 upsert_aggregator!(ZonedExtent)
             }
             ConcreteAggregationMethod::Median(_)
+            | ConcreteAggregationMethod::Mad
+            | ConcreteAggregationMethod::Iqr
             | ConcreteAggregationMethod::Quantile(_)
             | ConcreteAggregationMethod::Quartile(_)
             | ConcreteAggregationMethod::Sparkline(_) => {
@@ -425,6 +444,7 @@ impl CompositeAggregator {
             ConcreteAggregationMethod::Mode
             | ConcreteAggregationMethod::Modes(_)
             | ConcreteAggregationMethod::Cardinality
+            | ConcreteAggregationMethod::CountDistinctWhere
             | ConcreteAggregationMethod::DistinctValues(_)
             | ConcreteAggregationMethod::MostCommonCounts(_, _)
             | ConcreteAggregationMethod::MostCommonValues(_, _) => {
@@ -583,6 +603,21 @@ impl CompositeAggregator {
                         _ => ()
                     }
                 }
+                Aggregator::Frequencies(frequencies) => {
+                    if first.is_truthy() && !second.is_nullish() {
+                        frequencies.add(second.try_as_str()?.into_owned());
+                    }
+                }
+                Aggregator::Sum(sum) => {
+                    if first.is_truthy() && !second.is_nullish() {
+                        sum.add(second.try_as_number()?);
+                    }
+                }
+                Aggregator::Welford(variance) => {
+                    if first.is_truthy() && !second.is_nullish() {
+                        variance.add(second.try_as_f64()?);
+                    }
+                }
                 _ => unreachable!(),
             }
         }
@@ -636,6 +671,10 @@ fn cast_as_separator(arg_opt: Option<&ConcreteExpr>) -> Result<String, Concretiz
 
 type ArgumentParser = fn(&[ConcreteExpr]) -> Result<ConcreteAggregationMethod, ConcretizationError>;
 
+pub fn is_aggregation_function(name: &str) -> bool {
+    get_function_arguments_parser(name).is_some()
+}
+
 fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, ArgumentParser)> {
     use ConcreteAggregationMethod::*;
 
@@ -643,6 +682,7 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         "all" => (FunctionArguments::unary(), |_| Ok(All)),
         "any" => (FunctionArguments::unary(), |_| Ok(Any)),
         "approx_cardinality" => (FunctionArguments::unary(), |_| Ok(ApproxCardinality)),
+        "approx_median" => (FunctionArguments::unary(), |_| Ok(ApproxQuantile(0.5))),
         "approx_quantile" => (FunctionArguments::binary(), |args| {
             Ok(ApproxQuantile(cast_as_static_value(
                 args.first().unwrap(),
@@ -665,6 +705,8 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         "cardinality" => (FunctionArguments::unary(), |_| Ok(Cardinality)),
         "correlation" => (FunctionArguments::unary(), |_| Ok(Correlation)),
         "count" => (FunctionArguments::unary(), |_| Ok(Count)),
+        "count_distinct_where" => (FunctionArguments::unary(), |_| Ok(CountDistinctWhere)),
+        "count_where" => (FunctionArguments::unary(), |_| Ok(Count)),
         "count_seconds" => (FunctionArguments::unary(), |_| Ok(CountTime(Unit::Second))),
         "count_hours" => (FunctionArguments::unary(), |_| Ok(CountTime(Unit::Hour))),
         "count_days" => (FunctionArguments::unary(), |_| Ok(CountTime(Unit::Day))),
@@ -676,13 +718,16 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
         }),
         "earliest" => (FunctionArguments::unary(), |_| Ok(Earliest)),
         "first" => (FunctionArguments::unary(), |_| Ok(First)),
+        "iqr" => (FunctionArguments::unary(), |_| Ok(Iqr)),
         "latest" => (FunctionArguments::unary(), |_| Ok(Latest)),
         "last" => (FunctionArguments::unary(), |_| Ok(Last)),
         "lex_first" => (FunctionArguments::unary(), |_| Ok(LexFirst)),
         "lex_last" => (FunctionArguments::unary(), |_| Ok(LexLast)),
+        "mad" => (FunctionArguments::unary(), |_| Ok(Mad)),
         "min" => (FunctionArguments::unary(), |_| Ok(Min)),
         "max" => (FunctionArguments::unary(), |_| Ok(Max)),
         "avg" | "mean" => (FunctionArguments::unary(), |_| Ok(Mean)),
+        "avg_where" | "mean_where" => (FunctionArguments::unary(), |_| Ok(Mean)),
         "median" => (FunctionArguments::unary(), |_| {
             Ok(Median(MedianType::Interpolation))
         }),
@@ -726,9 +771,11 @@ fn get_function_arguments_parser(name: &str) -> Option<(FunctionArguments, Argum
                 None => 10,
             }))
         }),
+        "span" => (FunctionArguments::unary(), |_| Ok(Span)),
         "stddev" | "stddev_pop" => (FunctionArguments::unary(), |_| Ok(StddevPop)),
         "stddev_sample" => (FunctionArguments::unary(), |_| Ok(StddevSample)),
         "sum" => (FunctionArguments::unary(), |_| Ok(Sum)),
+        "sum_where" => (FunctionArguments::unary(), |_| Ok(Sum)),
         "top" => (FunctionArguments::with_range(1..=3), |args| {
             Ok(Top(
                 cast_as_static_value(args.first().unwrap(), DynamicValue::try_as_usize)?,
@@ -753,16 +800,19 @@ enum ConcreteAggregationMethod {
     Cardinality,
     Correlation,
     Count,
+    CountDistinctWhere,
     CountTime(Unit),
     CovariancePop,
     CovarianceSample,
     DistinctValues(String),
     Earliest,
     First,
+    Iqr,
     Latest,
     Last,
     LexFirst,
     LexLast,
+    Mad,
     Min,
     Max,
     Mean,
@@ -776,6 +826,7 @@ enum ConcreteAggregationMethod {
     Quantile(f64),
     Ratio,
     Sparkline(usize),
+    Span,
     Sum,
     Values(String),
     VarPop,
@@ -841,12 +892,34 @@ fn concretize_aggregations(
 
         let mut skip: usize = 1;
 
+        // NOTE: conditional aggregations must be given their predicate and
+        // cannot omit their expression.
+        let where_arguments = match aggregation.func_name.as_str() {
+            "count_where" => Some(FunctionArguments::unary()),
+            "count_distinct_where" | "avg_where" | "mean_where" | "sum_where" => {
+                Some(FunctionArguments::binary())
+            }
+            _ => None,
+        };
+
+        if let Some(function_arguments) = where_arguments {
+            function_arguments
+                .validate_arity(aggregation.args.len())
+                .map_err(|invalid_arity| {
+                    ConcretizationError::InvalidArity(aggregation.func_name.clone(), invalid_arity)
+                })?;
+        }
+
         let pair_expr = if aggregation.args.len() > 1
             && [
                 "covariance",
                 "covariance_pop",
                 "covariance_sample",
                 "correlation",
+                "count_distinct_where",
+                "avg_where",
+                "mean_where",
+                "sum_where",
             ]
             .contains(&aggregation.func_name.as_str())
         {
@@ -1013,9 +1086,11 @@ fn run_with_record_on_aggregators(
         if let Some(pair_expr) = &unit.pair_expr {
             let second_value = eval_expression(pair_expr, Some(index), record, context)?;
 
-            return aggregator
+            aggregator
                 .process_pair(index, value.unwrap(), second_value)
-                .map_err(|err| err.specify("<agg-expr>"));
+                .map_err(|err| err.specify("<agg-expr>"))?;
+
+            continue;
         }
 
         if let Some(DynamicValue::List(list)) = value {
@@ -1039,6 +1114,7 @@ pub struct AggregationProgram {
     aggregators: Vec<CompositeAggregator>,
     planner: ConcreteAggregationPlanner,
     context: EvaluationContext,
+    post_processing: Option<(Program, Vec<String>)>,
 }
 
 impl AggregationProgram {
@@ -1051,9 +1127,20 @@ impl AggregationProgram {
             planner,
             aggregators,
             context: EvaluationContext::new(headers),
+            post_processing: None,
         })
     }
 
+    // NOTE: post-processing named expressions are evaluated once, when
+    // finalizing, on a record containing the aggregation results, so they
+    // can reference them by name.
+    pub fn set_post_processing(&mut self, code: &str) -> Result<(), ConcretizationError> {
+        let headers = self.planner.headers().collect::<ByteRecord>();
+        self.post_processing = Some(Program::parse_named(code, &headers)?);
+
+        Ok(())
+    }
+
     pub fn clear(&mut self) {
         for aggregator in self.aggregators.iter_mut() {
             aggregator.clear()
@@ -1082,8 +1169,11 @@ impl AggregationProgram {
         )
     }
 
-    pub fn headers(&self) -> impl Iterator<Item = &[u8]> {
-        self.planner.headers()
+    pub fn headers(&self) -> Box<dyn Iterator<Item = &[u8]> + '_> {
+        match &self.post_processing {
+            None => Box::new(self.planner.headers()),
+            Some((_, names)) => Box::new(names.iter().map(|name| name.as_bytes())),
+        }
     }
 
     pub fn finalize(&mut self, parallel: bool) -> Result<ByteRecord, SpecifiedEvaluationError> {
@@ -1097,6 +1187,21 @@ impl AggregationProgram {
             record.push_field(&value?.serialize_as_bytes());
         }
 
+        if let Some((program, _)) = &self.post_processing {
+            let mut post_record = ByteRecord::new();
+
+            match program.run_with_record(0, &record)? {
+                DynamicValue::List(values) => {
+                    for value in values.iter() {
+                        post_record.push_field(&value.serialize_as_bytes());
+                    }
+                }
+                _ => unreachable!(),
+            }
+
+            return Ok(post_record);
+        }
+
         Ok(record)
     }
 }
@@ -50,6 +50,7 @@ impl Stats {
         self.count.merge(other.count);
         self.extent.merge(other.extent);
         self.length_extent.merge(other.length_extent);
+        self.lexicograhic_extent.merge(other.lexicograhic_extent);
         self.welford.merge(other.welford);
         self.sum.merge(other.sum);
         self.types.merge(other.types);
@@ -176,18 +177,21 @@ impl Stats {
 
         if let Some(mut approx_quantiles) = self.approx_quantiles {
             approx_quantiles.finalize();
-            record.push_field(approx_quantiles.get(0.25).to_string().as_bytes());
-            record.push_field(approx_quantiles.get(0.5).to_string().as_bytes());
-            record.push_field(approx_quantiles.get(0.75).to_string().as_bytes());
+
+            for q in [0.25, 0.5, 0.75] {
+                if approx_quantiles.is_empty() {
+                    record.push_field(b"");
+                } else {
+                    record.push_field(approx_quantiles.get(q).to_string().as_bytes());
+                }
+            }
         }
 
         if let Some(frequencies) = self.frequencies.as_ref() {
             record.push_field(frequencies.cardinality().to_string().as_bytes());
 
-            let modes = frequencies.modes();
-
-            record.push_field(&map_to_field(modes.as_ref().map(|m| m[0].clone())));
-            record.push_field(&map_to_field(modes.map(|m| m.len())));
+            record.push_field(&map_to_field(frequencies.mode()));
+            record.push_field(&map_to_field(frequencies.modes().map(|m| m.len())));
         }
 
         record.push_field(&map_to_field(self.lexicograhic_extent.first()));
@@ -198,6 +202,76 @@ impl Stats {
         record
     }
 
+    pub fn sketch_headers() -> ByteRecord {
+        let mut headers = ByteRecord::new();
+
+        for name in [
+            "field",
+            "count",
+            "min",
+            "q1",
+            "median",
+            "q3",
+            "max",
+            "iqr",
+            "lower_fence",
+            "upper_fence",
+        ] {
+            headers.push_field(name.as_bytes());
+        }
+
+        headers
+    }
+
+    // NOTE: returns None if the column does not contain any numerical value
+    pub fn sketch(self, name: &[u8]) -> Option<ByteRecord> {
+        let (min, max) = (self.extent.min()?, self.extent.max()?);
+
+        let (q1, median, q3) = if let Some(mut numbers) = self.numbers {
+            numbers.finalize(false);
+
+            let quartiles = numbers.quartiles()?;
+
+            (
+                quartiles[0].as_float(),
+                quartiles[1].as_float(),
+                quartiles[2].as_float(),
+            )
+        } else {
+            let mut approx_quantiles = self.approx_quantiles?;
+            approx_quantiles.finalize();
+
+            if approx_quantiles.is_empty() {
+                return None;
+            }
+
+            (
+                approx_quantiles.get(0.25),
+                approx_quantiles.get(0.5),
+                approx_quantiles.get(0.75),
+            )
+        };
+
+        let iqr = q3 - q1;
+
+        let mut record = ByteRecord::new();
+
+        record.push_field(name);
+        record.push_field(self.count.get_truthy().to_string().as_bytes());
+        record.push_field(min.to_string().as_bytes());
+
+        for value in [q1, median, q3] {
+            record.push_field(value.to_string().as_bytes());
+        }
+
+        record.push_field(max.to_string().as_bytes());
+        record.push_field(iqr.to_string().as_bytes());
+        record.push_field((q1 - 1.5 * iqr).to_string().as_bytes());
+        record.push_field((q3 + 1.5 * iqr).to_string().as_bytes());
+
+        Some(record)
+    }
+
     pub fn process(&mut self, cell: &[u8]) {
         self.length_extent.add(cell.len());
 
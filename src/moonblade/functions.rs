@@ -5,7 +5,7 @@ use std::fs::{self, File};
 use std::io::Read;
 use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use base64::prelude::*;
 use bstr::ByteSlice;
@@ -20,7 +20,9 @@ use paltoquet::{
     stemmers::{fr::carry_stemmer, s_stemmer},
     tokenizers::FingerprintTokenizer,
 };
-use rand::Rng;
+use rand::{Rng, RngCore};
+use rand_chacha::ChaCha8Rng;
+use rand_seeder::Seeder;
 use unidecode::unidecode;
 use uuid::Uuid;
 
@@ -81,6 +83,12 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             |args| unary_arithmetic_op(args, DynamicNumber::ceil),
             FunctionArguments::unary(),
         ),
+        "ceil_to" => (
+            |args| round_to_multiple(args, f64::ceil),
+            FunctionArguments::binary(),
+        ),
+        "center" => (center, FunctionArguments::with_range(2..=3)),
+        "clamp" => (clamp, FunctionArguments::nary(3)),
         "coalesce" => (coalesce, FunctionArguments::variadic(2)),
         "compact" => (compact, FunctionArguments::unary()),
         "concat" => (concat, FunctionArguments::variadic(2)),
@@ -99,30 +107,55 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             |args| variadic_arithmetic_op(args, Div::div),
             FunctionArguments::variadic(2),
         ),
+        "domain" => (domain, FunctionArguments::unary()),
         "endswith" => (endswith, FunctionArguments::binary()),
+        "enumerate" => (enumerate, FunctionArguments::unary()),
         "err" => (err, FunctionArguments::unary()),
         "escape_regex" => (escape_regex, FunctionArguments::unary()),
         "ext" => (ext, FunctionArguments::unary()),
         "filesize" => (filesize, FunctionArguments::unary()),
         "fingerprint" => (fingerprint, FunctionArguments::unary()),
         "first" => (first, FunctionArguments::unary()),
+        "first_non_empty" | "coalesce_empty" => (first_non_empty, FunctionArguments::variadic(2)),
+        "fixed" => (fixed, FunctionArguments::binary()),
         "floor" => (
             |args| unary_arithmetic_op(args, DynamicNumber::floor),
             FunctionArguments::unary(),
         ),
+        "floor_to" => (
+            |args| round_to_multiple(args, f64::floor),
+            FunctionArguments::binary(),
+        ),
         "fmt" => (fmt, FunctionArguments::variadic(2)),
         "numfmt" => (fmt_number, FunctionArguments::unary()),
+        "gcd" => (gcd, FunctionArguments::binary()),
         "get" => (get, FunctionArguments::with_range(2..=3)),
+        "get_path" => (get_path, FunctionArguments::with_range(2..=3)),
         "idiv" => (
             |args| arithmetic_op(args, DynamicNumber::idiv),
             FunctionArguments::binary(),
         ),
         "index_by" => (index_by, FunctionArguments::binary()),
+        "is_finite" => (
+            |args| number_predicate(args, DynamicNumber::is_finite),
+            FunctionArguments::unary(),
+        ),
+        "is_nan" => (
+            |args| number_predicate(args, DynamicNumber::is_nan),
+            FunctionArguments::unary(),
+        ),
         "isfile" => (isfile, FunctionArguments::unary()),
+        "jaro_winkler" => (jaro_winkler_fn, FunctionArguments::binary()),
         "join" => (join, FunctionArguments::binary()),
+        "json_stringify" => (
+            json_stringify,
+            FunctionArguments::complex(vec![Argument::Positional, Argument::with_name("pretty")]),
+        ),
         "keys" => (keys, FunctionArguments::unary()),
         "last" => (last, FunctionArguments::unary()),
+        "lcm" => (lcm, FunctionArguments::binary()),
         "len" => (len, FunctionArguments::unary()),
+        "levenshtein" => (levenshtein_fn, FunctionArguments::binary()),
         "log" => (
             |args| unary_arithmetic_op(args, DynamicNumber::ln),
             FunctionArguments::unary(),
@@ -132,6 +165,7 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         "match" => (regex_match, FunctionArguments::with_range(2..=3)),
         "max" => (variadic_max, FunctionArguments::variadic(2)),
         "md5" => (md5, FunctionArguments::unary()),
+        "metaphone" => (metaphone_fn, FunctionArguments::unary()),
         "mean" => (mean, FunctionArguments::unary()),
         "mime_ext" => (mime_ext, FunctionArguments::unary()),
         "min" => (variadic_min, FunctionArguments::variadic(2)),
@@ -152,20 +186,26 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             |args| variadic_arithmetic_op(args, Mul::mul),
             FunctionArguments::variadic(2),
         ),
+        "nan_to_null" => (nan_to_null, FunctionArguments::unary()),
         "neg" => (
             |args| unary_arithmetic_op(args, Neg::neg),
             FunctionArguments::unary(),
         ),
+        "normalize_url" => (normalize_url, FunctionArguments::unary()),
         "not" => (not, FunctionArguments::unary()),
         "or" => (or, FunctionArguments::variadic(2)),
+        "parse_bytes" => (parse_bytes, FunctionArguments::unary()),
         "parse_dataurl" => (parse_dataurl, FunctionArguments::unary()),
         "parse_json" => (parse_json, FunctionArguments::unary()),
+        "percent" => (percent, FunctionArguments::with_range(1..=2)),
         "pjoin" | "pathjoin" => (pathjoin, FunctionArguments::variadic(2)),
         "pow" => (
             |args| binary_arithmetic_op(args, DynamicNumber::pow),
             FunctionArguments::binary(),
         ),
+        "product" => (product, FunctionArguments::unary()),
         "random" => (random, FunctionArguments::nullary()),
+        "range" => (range, FunctionArguments::with_range(2..=3)),
         "read" => (
             read,
             FunctionArguments::complex(vec![
@@ -176,12 +216,21 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         ),
         "read_csv" => (read_csv, FunctionArguments::unary()),
         "read_json" => (read_json, FunctionArguments::unary()),
+        "repeat" => (repeat, FunctionArguments::binary()),
         "replace" => (replace, FunctionArguments::nary(3)),
         "round" => (
             |args| unary_arithmetic_op(args, DynamicNumber::round),
             FunctionArguments::unary(),
         ),
+        "round_to" => (
+            |args| round_to_multiple(args, f64::round),
+            FunctionArguments::binary(),
+        ),
         "rtrim" => (rtrim, FunctionArguments::with_range(1..=2)),
+        "sign" => (
+            |args| unary_arithmetic_op(args, DynamicNumber::sign),
+            FunctionArguments::unary(),
+        ),
         "slice" => (slice, FunctionArguments::with_range(2..=3)),
         "split" => (split, FunctionArguments::with_range(2..=3)),
         "sqrt" => (
@@ -202,6 +251,8 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
             FunctionArguments::variadic(2),
         ),
         "s_stemmer" => (s_stemmer_fn, FunctionArguments::unary()),
+        "soundex" => (soundex_fn, FunctionArguments::unary()),
+        "sum" => (sum, FunctionArguments::unary()),
         "eq" => (
             |args| sequence_compare(args, Ordering::is_eq),
             FunctionArguments::binary(),
@@ -229,6 +280,13 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         "timestamp" => (timestamp, FunctionArguments::unary()),
         "timestamp_ms" => (timestamp_ms, FunctionArguments::unary()),
         "trim" => (trim, FunctionArguments::with_range(1..=2)),
+        "tld" => (tld, FunctionArguments::unary()),
+        "url_decode" => (url_decode, FunctionArguments::unary()),
+        "url_encode" => (url_encode, FunctionArguments::unary()),
+        "url_parse" => (
+            url_parse,
+            FunctionArguments::complex(vec![Argument::Positional, Argument::with_name("strict")]),
+        ),
         "trunc" => (
             |args| unary_arithmetic_op(args, DynamicNumber::trunc),
             FunctionArguments::unary(),
@@ -239,6 +297,14 @@ pub fn get_function(name: &str) -> Option<(Function, FunctionArguments)> {
         "uuid" => (uuid, FunctionArguments::nullary()),
         "values" => (values, FunctionArguments::unary()),
         "write" => (write, FunctionArguments::binary()),
+        "zip" => (
+            zip,
+            FunctionArguments::complex(vec![
+                Argument::Positional,
+                Argument::Positional,
+                Argument::with_name("pad"),
+            ]),
+        ),
         "year" => (
             |args| custom_strftime(args, "%Y"),
             FunctionArguments::complex(vec![Argument::Positional, Argument::with_name("timezone")]),
@@ -346,6 +412,67 @@ fn upper(args: BoundArguments) -> FunctionResult {
     })
 }
 
+// NOTE: guarding against accidentally huge strings.
+const REPEAT_MAX_LEN: usize = 100_000_000;
+
+fn repeat(args: BoundArguments) -> FunctionResult {
+    let (string, times) = args.get2();
+    let string = string.try_as_str()?;
+    let times = times.try_as_usize()?;
+
+    let len = string.len().saturating_mul(times);
+
+    if len > REPEAT_MAX_LEN {
+        return Err(EvaluationError::Custom(format!(
+            "repeat would produce a string of {} bytes, which is more than the maximum of {}",
+            len, REPEAT_MAX_LEN
+        )));
+    }
+
+    Ok(DynamicValue::from(string.repeat(times)))
+}
+
+fn center(args: BoundArguments) -> FunctionResult {
+    let (string, width) = args.get2();
+    let string = string.try_as_str()?;
+    let width = width.try_as_usize()?;
+
+    let fill = match args.get_not_none(2) {
+        None => ' ',
+        Some(value) => {
+            let fill = value.try_as_str()?;
+            let mut chars = fill.chars();
+
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => c,
+                _ => {
+                    return Err(EvaluationError::Custom(
+                        "center fill should be a single character".to_string(),
+                    ))
+                }
+            }
+        }
+    };
+
+    let count = string.chars().count();
+
+    if count >= width {
+        return Ok(DynamicValue::from(string.into_owned()));
+    }
+
+    let padding = width - count;
+    let left = padding / 2;
+    let right = padding - left;
+
+    let mut centered = String::with_capacity(string.len() + padding * fill.len_utf8());
+
+    centered.extend(std::iter::repeat_n(fill, left));
+    centered.push_str(&string);
+    centered.extend(std::iter::repeat_n(fill, right));
+
+    Ok(DynamicValue::from(centered))
+}
+
 fn len(mut args: BoundArguments) -> FunctionResult {
     let arg = args.pop1();
 
@@ -467,6 +594,38 @@ fn fmt_number(mut args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(crate::util::format_number(number)))
 }
 
+fn get_decimals(value: &DynamicValue) -> Result<usize, EvaluationError> {
+    match value.try_as_number()?.try_as_integral() {
+        Some(n) if n >= 0 => Ok(n as usize),
+        _ => Err(EvaluationError::Custom(
+            "number of decimals should be a non-negative integer".to_string(),
+        )),
+    }
+}
+
+// NOTE: rust float formatting already rounds ties to even
+fn fixed(args: BoundArguments) -> FunctionResult {
+    let (number, decimals) = args.get2();
+    let number = number.try_as_f64()?;
+    let decimals = get_decimals(decimals)?;
+
+    Ok(DynamicValue::from(format!("{:.*}", decimals, number)))
+}
+
+fn percent(args: BoundArguments) -> FunctionResult {
+    let number = args.get1().try_as_f64()?;
+    let decimals = match args.get_not_none(1) {
+        Some(value) => get_decimals(value)?,
+        None => 1,
+    };
+
+    Ok(DynamicValue::from(format!(
+        "{:.*}%",
+        decimals,
+        number * 100.0
+    )))
+}
+
 // Lists & Sequences
 fn first(mut args: BoundArguments) -> FunctionResult {
     let arg = args.pop1();
@@ -575,6 +734,31 @@ fn get(mut args: BoundArguments) -> FunctionResult {
     }
 }
 
+fn get_path(args: BoundArguments) -> FunctionResult {
+    let path = args.get(1).unwrap().try_as_str()?;
+    let default = || args.get(2).cloned().unwrap_or(DynamicValue::None);
+
+    let mut current = args.get1().clone();
+
+    for step in path.split('.') {
+        let next = match &current {
+            DynamicValue::Map(map) => map.get(step).cloned(),
+            DynamicValue::List(_) | DynamicValue::String(_) => match step.parse::<i64>() {
+                Ok(index) => get_subroutine(&current, &DynamicValue::from(index))?,
+                Err(_) => None,
+            },
+            _ => None,
+        };
+
+        match next {
+            None => return Ok(default()),
+            Some(value) => current = value,
+        }
+    }
+
+    Ok(current)
+}
+
 fn slice(args: BoundArguments) -> FunctionResult {
     let target = args.get(0).unwrap();
 
@@ -742,6 +926,88 @@ fn replace(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(replaced))
 }
 
+fn enumerate(args: BoundArguments) -> FunctionResult {
+    let list = args.get1().try_as_list()?;
+
+    Ok(DynamicValue::from(
+        list.iter()
+            .enumerate()
+            .map(|(i, value)| DynamicValue::from(vec![DynamicValue::from(i), value.clone()]))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+// NOTE: range is materialized as a list, so we need to guard against
+// accidentally huge ones.
+const RANGE_MAX_LEN: i64 = 10_000_000;
+
+fn range(args: BoundArguments) -> FunctionResult {
+    let mut bounds = Vec::with_capacity(3);
+
+    for arg in args {
+        match arg.try_as_number()?.try_as_integral() {
+            Some(n) => bounds.push(n),
+            None => {
+                return Err(EvaluationError::Custom(
+                    "range expects integral numbers".to_string(),
+                ))
+            }
+        }
+    }
+
+    let start = bounds[0];
+    let end = bounds[1];
+    let step = bounds.get(2).copied().unwrap_or(1);
+
+    if step == 0 {
+        return Err(EvaluationError::Custom(
+            "range step cannot be 0".to_string(),
+        ));
+    }
+
+    let len = if (step > 0 && end > start) || (step < 0 && end < start) {
+        (end.abs_diff(start) - 1) / step.unsigned_abs() + 1
+    } else {
+        0
+    };
+
+    if len > RANGE_MAX_LEN as u64 {
+        return Err(EvaluationError::Custom(format!(
+            "range would contain {} items, which is more than the maximum of {}",
+            len, RANGE_MAX_LEN
+        )));
+    }
+
+    Ok(DynamicValue::from(
+        (0..len as i64)
+            .map(|i| DynamicValue::from(start + i * step))
+            .collect::<Vec<_>>(),
+    ))
+}
+
+fn zip(args: BoundArguments) -> FunctionResult {
+    let (first, second) = args.get2();
+    let (first, second) = (first.try_as_list()?, second.try_as_list()?);
+    let pad = args.get_not_none(2).is_some_and(|v| v.is_truthy());
+
+    let len = if pad {
+        first.len().max(second.len())
+    } else {
+        first.len().min(second.len())
+    };
+
+    Ok(DynamicValue::from(
+        (0..len)
+            .map(|i| {
+                DynamicValue::from(vec![
+                    first.get(i).cloned().unwrap_or(DynamicValue::None),
+                    second.get(i).cloned().unwrap_or(DynamicValue::None),
+                ])
+            })
+            .collect::<Vec<_>>(),
+    ))
+}
+
 fn compact(mut args: BoundArguments) -> FunctionResult {
     let arg = args.pop1();
     let list = arg.try_into_arc_list()?;
@@ -829,6 +1095,23 @@ where
     Ok(DynamicValue::from(op(args.pop1_number()?)))
 }
 
+fn number_predicate<F>(mut args: BoundArguments, predicate: F) -> FunctionResult
+where
+    F: Fn(&DynamicNumber) -> bool,
+{
+    Ok(DynamicValue::from(predicate(&args.pop1_number()?)))
+}
+
+fn nan_to_null(mut args: BoundArguments) -> FunctionResult {
+    let number = args.pop1_number()?;
+
+    Ok(if number.is_finite() {
+        DynamicValue::from(number)
+    } else {
+        DynamicValue::None
+    })
+}
+
 fn binary_arithmetic_op<F>(args: BoundArguments, op: F) -> FunctionResult
 where
     F: Fn(DynamicNumber, DynamicNumber) -> DynamicNumber,
@@ -910,6 +1193,109 @@ fn variadic_max(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(max_value))
 }
 
+fn clamp(args: BoundArguments) -> FunctionResult {
+    let (x, lo, hi) = args.get3();
+    let (x, lo, hi) = (x.try_as_number()?, lo.try_as_number()?, hi.try_as_number()?);
+
+    if lo > hi {
+        return Err(EvaluationError::Custom(format!(
+            "clamp lower bound ({}) is greater than upper bound ({})",
+            lo.as_float(),
+            hi.as_float()
+        )));
+    }
+
+    Ok(DynamicValue::from(if x < lo {
+        lo
+    } else if x > hi {
+        hi
+    } else {
+        x
+    }))
+}
+
+fn round_to_multiple<F>(args: BoundArguments, op: F) -> FunctionResult
+where
+    F: Fn(f64) -> f64,
+{
+    let (x, multiple) = args.get2_number()?;
+
+    if multiple.as_float() <= 0.0 {
+        return Err(EvaluationError::Custom(format!(
+            "multiple should be greater than 0, but got {}",
+            multiple
+        )));
+    }
+
+    Ok(DynamicValue::from(match (x, multiple) {
+        (DynamicNumber::Integer(x), DynamicNumber::Integer(m)) => {
+            DynamicNumber::Integer(op(x as f64 / m as f64) as i64 * m)
+        }
+        _ => {
+            let m = multiple.as_float();
+            let rounded = op(x.as_float() / m) * m;
+
+            // NOTE: rounding to the multiple's precision to avoid floating
+            // point artifacts such as 0.15000000000000002
+            let decimals = m
+                .to_string()
+                .split_once('.')
+                .map(|(_, d)| d.len())
+                .unwrap_or(0);
+            let factor = 10f64.powi(decimals as i32);
+
+            DynamicNumber::Float((rounded * factor).round() / factor)
+        }
+    }))
+}
+
+fn get2_integral(args: &BoundArguments) -> Result<(i64, i64), EvaluationError> {
+    let (a, b) = args.get2();
+
+    match (
+        a.try_as_number()?.try_as_integral(),
+        b.try_as_number()?.try_as_integral(),
+    ) {
+        (Some(a), Some(b)) => Ok((a, b)),
+        _ => Err(EvaluationError::Custom(
+            "expected integral numbers".to_string(),
+        )),
+    }
+}
+
+// NOTE: computing on absolute values as u64 so that i64::MIN cannot overflow
+fn compute_gcd(a: i64, b: i64) -> Result<i64, EvaluationError> {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    i64::try_from(a).map_err(|_| EvaluationError::Custom("gcd overflowed".to_string()))
+}
+
+fn gcd(args: BoundArguments) -> FunctionResult {
+    let (a, b) = get2_integral(&args)?;
+
+    Ok(DynamicValue::from(compute_gcd(a, b)?))
+}
+
+fn lcm(args: BoundArguments) -> FunctionResult {
+    let (a, b) = get2_integral(&args)?;
+
+    if a == 0 || b == 0 {
+        return Ok(DynamicValue::from(0));
+    }
+
+    match (a / compute_gcd(a, b)?)
+        .checked_mul(b)
+        .and_then(|m| m.checked_abs())
+    {
+        Some(m) => Ok(DynamicValue::from(m)),
+        None => Err(EvaluationError::Custom("lcm overflowed".to_string())),
+    }
+}
+
 fn argcompare<F>(args: BoundArguments, validate: F) -> FunctionResult
 where
     F: Fn(Ordering) -> bool,
@@ -959,6 +1345,16 @@ fn coalesce(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::None)
 }
 
+fn first_non_empty(args: BoundArguments) -> FunctionResult {
+    for arg in args {
+        if !arg.is_nullish() {
+            return Ok(arg);
+        }
+    }
+
+    Ok(DynamicValue::None)
+}
+
 // Boolean
 fn not(mut args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(!args.pop1_bool()))
@@ -1056,6 +1452,28 @@ fn mean(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(welford.mean()))
 }
 
+fn sum(args: BoundArguments) -> FunctionResult {
+    let items = args.get1().try_as_list()?;
+    let mut total = DynamicNumber::Integer(0);
+
+    for item in items {
+        total += item.try_as_number()?;
+    }
+
+    Ok(DynamicValue::from(total))
+}
+
+fn product(args: BoundArguments) -> FunctionResult {
+    let items = args.get1().try_as_list()?;
+    let mut total = DynamicNumber::Integer(1);
+
+    for item in items {
+        total *= item.try_as_number()?;
+    }
+
+    Ok(DynamicValue::from(total))
+}
+
 // IO
 fn abspath(args: BoundArguments) -> FunctionResult {
     let arg = args.get1_str()?;
@@ -1308,6 +1726,18 @@ fn bytesize(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(human_readable))
 }
 
+fn parse_bytes(args: BoundArguments) -> FunctionResult {
+    let string = args.get1_str()?;
+
+    match string.trim().parse::<ByteSize>() {
+        Ok(size) => Ok(DynamicValue::from(size.as_u64() as i64)),
+        Err(_) => Err(EvaluationError::Custom(format!(
+            "cannot parse \"{}\" as a number of bytes",
+            string
+        ))),
+    }
+}
+
 // Dates
 fn timestamp(args: BoundArguments) -> FunctionResult {
     let seconds = args.get1().try_as_i64()?;
@@ -1458,8 +1888,27 @@ fn type_of(mut args: BoundArguments) -> FunctionResult {
 }
 
 // Random
+lazy_static! {
+    static ref SEEDED_RNG: Option<Mutex<ChaCha8Rng>> =
+        crate::util::env_seed().map(|seed| Mutex::new(Seeder::from(seed).into_rng::<ChaCha8Rng>()));
+}
+
+fn with_rng<T>(callback: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    match SEEDED_RNG.as_ref() {
+        Some(mutex) => callback(&mut *mutex.lock().unwrap()),
+        None => callback(&mut rand::rng()),
+    }
+}
+
 fn uuid(_args: BoundArguments) -> FunctionResult {
-    let id = Uuid::new_v4()
+    let mut bytes = [0u8; 16];
+    with_rng(|rng| rng.fill_bytes(&mut bytes));
+
+    // Setting version 4 & RFC 4122 variant bits
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+
+    let id = Uuid::from_bytes(bytes)
         .to_hyphenated()
         .encode_lower(&mut Uuid::encode_buffer())
         .to_string();
@@ -1468,7 +1917,7 @@ fn uuid(_args: BoundArguments) -> FunctionResult {
 }
 
 fn random(_args: BoundArguments) -> FunctionResult {
-    Ok(DynamicValue::from(rand::rng().random::<f64>()))
+    Ok(DynamicValue::from(with_rng(|rng| rng.random::<f64>())))
 }
 
 // Fuzzy matching
@@ -1496,6 +1945,336 @@ fn carry_stemmer_fn(args: BoundArguments) -> FunctionResult {
     Ok(DynamicValue::from(carry_stemmer(&string)))
 }
 
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    if a.is_empty() {
+        return b.len();
+    }
+
+    if b.is_empty() {
+        return a.len();
+    }
+
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.iter().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+
+            row[j + 1] = if ca == cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+
+            diagonal = above;
+        }
+    }
+
+    row[b.len()]
+}
+
+fn levenshtein_fn(args: BoundArguments) -> FunctionResult {
+    let (a, b) = args.get2_str()?;
+
+    Ok(DynamicValue::from(levenshtein(&a, &b)))
+}
+
+fn jaro(a: &[char], b: &[char]) -> f64 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let window = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+    let mut a_matches = vec![false; a.len()];
+    let mut b_matches = vec![false; b.len()];
+    let mut matches: usize = 0;
+
+    for (i, ca) in a.iter().enumerate() {
+        let start = i.saturating_sub(window);
+        let end = (i + window + 1).min(b.len());
+
+        for (j, cb) in b.iter().enumerate().take(end).skip(start) {
+            if !b_matches[j] && cb == ca {
+                a_matches[i] = true;
+                b_matches[j] = true;
+                matches += 1;
+                break;
+            }
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let mut transpositions: usize = 0;
+    let mut k: usize = 0;
+
+    for (i, ca) in a.iter().enumerate() {
+        if !a_matches[i] {
+            continue;
+        }
+
+        while !b_matches[k] {
+            k += 1;
+        }
+
+        if *ca != b[k] {
+            transpositions += 1;
+        }
+
+        k += 1;
+    }
+
+    let m = matches as f64;
+
+    (m / a.len() as f64 + m / b.len() as f64 + (m - (transpositions / 2) as f64) / m) / 3.0
+}
+
+pub fn jaro_winkler(a: &str, b: &str) -> f64 {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+
+    let similarity = jaro(&a, &b);
+
+    let prefix = a
+        .iter()
+        .zip(b.iter())
+        .take(4)
+        .take_while(|(ca, cb)| ca == cb)
+        .count();
+
+    similarity + prefix as f64 * 0.1 * (1.0 - similarity)
+}
+
+fn jaro_winkler_fn(args: BoundArguments) -> FunctionResult {
+    let (a, b) = args.get2_str()?;
+
+    Ok(DynamicValue::from(jaro_winkler(&a, &b)))
+}
+
+// NOTE: phonetic encoders only consider ASCII letters, after transliteration.
+fn phonetic_letters(string: &str) -> Vec<u8> {
+    unidecode(string)
+        .bytes()
+        .filter(|b| b.is_ascii_alphabetic())
+        .map(|b| b.to_ascii_uppercase())
+        .collect()
+}
+
+fn soundex_code(letter: u8) -> Option<u8> {
+    Some(match letter {
+        b'B' | b'F' | b'P' | b'V' => b'1',
+        b'C' | b'G' | b'J' | b'K' | b'Q' | b'S' | b'X' | b'Z' => b'2',
+        b'D' | b'T' => b'3',
+        b'L' => b'4',
+        b'M' | b'N' => b'5',
+        b'R' => b'6',
+        _ => return None,
+    })
+}
+
+fn soundex(string: &str) -> String {
+    let letters = phonetic_letters(string);
+
+    let first = match letters.first() {
+        None => return String::new(),
+        Some(b) => *b,
+    };
+
+    let mut key = vec![first];
+    let mut last_code = soundex_code(first);
+
+    for letter in letters[1..].iter().copied() {
+        if key.len() == 4 {
+            break;
+        }
+
+        let code = soundex_code(letter);
+
+        match code {
+            Some(c) if code != last_code => key.push(c),
+            Some(_) => (),
+            // NOTE: H & W do not separate letters having the same code
+            None if letter == b'H' || letter == b'W' => continue,
+            None => (),
+        }
+
+        last_code = code;
+    }
+
+    key.resize(4, b'0');
+
+    String::from_utf8(key).unwrap()
+}
+
+fn is_vowel(letter: u8) -> bool {
+    matches!(letter, b'A' | b'E' | b'I' | b'O' | b'U')
+}
+
+fn metaphone(string: &str) -> String {
+    let mut letters = phonetic_letters(string);
+
+    if letters.is_empty() {
+        return String::new();
+    }
+
+    // Initial exceptions
+    match letters.as_slice() {
+        [b'A', b'E', ..]
+        | [b'G', b'N', ..]
+        | [b'K', b'N', ..]
+        | [b'P', b'N', ..]
+        | [b'W', b'R', ..] => {
+            letters.remove(0);
+        }
+        [b'X', ..] => {
+            letters[0] = b'S';
+        }
+        [b'W', b'H', ..] => {
+            letters.remove(1);
+        }
+        _ => (),
+    }
+
+    let n = letters.len();
+    let at = |i: usize| -> u8 { letters.get(i).copied().unwrap_or(0) };
+    let mut key = String::new();
+
+    for (i, &letter) in letters.iter().enumerate() {
+        let prev = if i > 0 { at(i - 1) } else { 0 };
+        let next = at(i + 1);
+        let next_next = at(i + 2);
+
+        // Dropping duplicate adjacent letters, except for C
+        if letter == prev && letter != b'C' {
+            continue;
+        }
+
+        match letter {
+            b'A' | b'E' | b'I' | b'O' | b'U' => {
+                if i == 0 {
+                    key.push(letter as char);
+                }
+            }
+            b'B' => {
+                if !(prev == b'M' && i == n - 1) {
+                    key.push('B');
+                }
+            }
+            b'C' => {
+                if next == b'I' && next_next == b'A' {
+                    key.push('X');
+                } else if next == b'H' {
+                    key.push(if prev == b'S' { 'K' } else { 'X' });
+                } else if matches!(next, b'I' | b'E' | b'Y') {
+                    if prev != b'S' {
+                        key.push('S');
+                    }
+                } else {
+                    key.push('K');
+                }
+            }
+            b'D' => {
+                if next == b'G' && matches!(next_next, b'E' | b'Y' | b'I') {
+                    key.push('J');
+                } else {
+                    key.push('T');
+                }
+            }
+            b'G' => {
+                if prev == b'D' && matches!(next, b'E' | b'Y' | b'I') {
+                    continue;
+                }
+
+                if next == b'H' && !(i + 2 >= n || is_vowel(next_next)) {
+                    continue;
+                }
+
+                if next == b'N'
+                    && (i + 2 == n || (next_next == b'E' && at(i + 3) == b'D' && i + 4 == n))
+                {
+                    continue;
+                }
+
+                if matches!(next, b'I' | b'E' | b'Y') && prev != b'G' {
+                    key.push('J');
+                } else {
+                    key.push('K');
+                }
+            }
+            b'H' => {
+                if (is_vowel(prev) && !is_vowel(next))
+                    || matches!(prev, b'C' | b'S' | b'P' | b'T' | b'G')
+                {
+                    continue;
+                }
+
+                key.push('H');
+            }
+            b'K' => {
+                if prev != b'C' {
+                    key.push('K');
+                }
+            }
+            b'P' => {
+                key.push(if next == b'H' { 'F' } else { 'P' });
+            }
+            b'Q' => key.push('K'),
+            b'S' => {
+                if next == b'H' || (next == b'I' && matches!(next_next, b'O' | b'A')) {
+                    key.push('X');
+                } else {
+                    key.push('S');
+                }
+            }
+            b'T' => {
+                if next == b'I' && matches!(next_next, b'O' | b'A') {
+                    key.push('X');
+                } else if next == b'H' {
+                    key.push('0');
+                } else if !(next == b'C' && next_next == b'H') {
+                    key.push('T');
+                }
+            }
+            b'V' => key.push('F'),
+            b'W' | b'Y' => {
+                if is_vowel(next) {
+                    key.push(letter as char);
+                }
+            }
+            b'X' => key.push_str("KS"),
+            b'Z' => key.push('S'),
+            _ => key.push(letter as char),
+        }
+    }
+
+    key
+}
+
+fn soundex_fn(args: BoundArguments) -> FunctionResult {
+    let string = args.get1().try_as_str()?;
+
+    Ok(DynamicValue::from(soundex(&string)))
+}
+
+fn metaphone_fn(args: BoundArguments) -> FunctionResult {
+    let string = args.get1().try_as_str()?;
+
+    Ok(DynamicValue::from(metaphone(&string)))
+}
+
 // Utils
 fn err(args: BoundArguments) -> FunctionResult {
     let arg = args.get1_str()?;
@@ -1509,6 +2288,48 @@ fn parse_json(args: BoundArguments) -> FunctionResult {
     serde_json::from_str(arg.as_ref()).map_err(|_| EvaluationError::JSONParseError)
 }
 
+// NOTE: map keys are sorted so that the output remains deterministic.
+fn dynamic_value_to_json(value: &DynamicValue) -> serde_json::Value {
+    match value {
+        DynamicValue::None => serde_json::Value::Null,
+        DynamicValue::Boolean(b) => serde_json::Value::Bool(*b),
+        DynamicValue::Integer(i) => serde_json::Value::from(*i),
+        DynamicValue::Float(f) => serde_json::Number::from_f64(*f)
+            .map(serde_json::Value::Number)
+            .unwrap_or(serde_json::Value::Null),
+        DynamicValue::String(string) => serde_json::Value::String(string.to_string()),
+        DynamicValue::Bytes(bytes) => serde_json::Value::String(bytes.to_str_lossy().into_owned()),
+        DynamicValue::List(list) => {
+            serde_json::Value::Array(list.iter().map(dynamic_value_to_json).collect())
+        }
+        DynamicValue::Map(map) => {
+            let mut items = map.iter().collect::<Vec<_>>();
+            items.sort_by(|a, b| a.0.cmp(b.0));
+
+            serde_json::Value::Object(
+                items
+                    .into_iter()
+                    .map(|(k, v)| (k.clone(), dynamic_value_to_json(v)))
+                    .collect(),
+            )
+        }
+        DynamicValue::Regex(_) | DynamicValue::DateTime(_) => {
+            serde_json::Value::String(value.serialize_as_bytes().to_str_lossy().into_owned())
+        }
+    }
+}
+
+fn json_stringify(args: BoundArguments) -> FunctionResult {
+    let json = dynamic_value_to_json(args.get1());
+    let pretty = args.get_not_none(1).is_some_and(|v| v.is_truthy());
+
+    Ok(DynamicValue::from(if pretty {
+        serde_json::to_string_pretty(&json).unwrap()
+    } else {
+        serde_json::to_string(&json).unwrap()
+    }))
+}
+
 fn parse_dataurl(args: BoundArguments) -> FunctionResult {
     let bytes = args.get1().try_as_bytes()?;
 
@@ -1541,6 +2362,293 @@ fn parse_dataurl(args: BoundArguments) -> FunctionResult {
     }
 }
 
+// Urls
+fn url_encode(args: BoundArguments) -> FunctionResult {
+    let bytes = args.get1().try_as_bytes()?;
+    let mut encoded = String::with_capacity(bytes.len());
+
+    for &byte in bytes {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    Ok(DynamicValue::from(encoded))
+}
+
+fn url_decode(args: BoundArguments) -> FunctionResult {
+    let bytes = args.get1().try_as_bytes()?;
+    let mut decoded: Vec<u8> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap();
+            decoded.push(u8::from_str_radix(hex, 16).unwrap());
+            i += 3;
+            continue;
+        }
+
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+
+    Ok(DynamicValue::from(
+        String::from_utf8_lossy(&decoded).into_owned(),
+    ))
+}
+
+struct ParsedUrl<'a> {
+    scheme: &'a str,
+    host: &'a str,
+    port: Option<u16>,
+    path: &'a str,
+    query: Option<&'a str>,
+    fragment: Option<&'a str>,
+}
+
+impl<'a> ParsedUrl<'a> {
+    fn parse(url: &'a str) -> Option<Self> {
+        let (scheme, rest) = url.trim().split_once("://")?;
+
+        let mut scheme_chars = scheme.chars();
+
+        if !scheme_chars.next()?.is_ascii_alphabetic()
+            || !scheme_chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        {
+            return None;
+        }
+
+        let (rest, fragment) = match rest.split_once('#') {
+            Some((rest, fragment)) => (rest, Some(fragment)),
+            None => (rest, None),
+        };
+
+        let (rest, query) = match rest.split_once('?') {
+            Some((rest, query)) => (rest, Some(query)),
+            None => (rest, None),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], &rest[i..]),
+            None => (rest, ""),
+        };
+
+        // Dropping user info
+        let authority = match authority.rsplit_once('@') {
+            Some((_, host_and_port)) => host_and_port,
+            None => authority,
+        };
+
+        let (host, port) = if authority.starts_with('[') {
+            // IPv6 hosts
+            let end = authority.find(']')?;
+
+            match &authority[end + 1..] {
+                "" => (&authority[..=end], None),
+                port => (&authority[..=end], Some(port.strip_prefix(':')?)),
+            }
+        } else {
+            match authority.rsplit_once(':') {
+                Some((host, port)) => (host, Some(port)),
+                None => (authority, None),
+            }
+        };
+
+        if host.is_empty() {
+            return None;
+        }
+
+        let port = match port {
+            Some(port) if !port.is_empty() => Some(port.parse::<u16>().ok()?),
+            _ => None,
+        };
+
+        Some(Self {
+            scheme,
+            host,
+            port,
+            path,
+            query,
+            fragment,
+        })
+    }
+
+    fn is_ip(&self) -> bool {
+        self.host.starts_with('[') || self.host.chars().all(|c| c.is_ascii_digit() || c == '.')
+    }
+
+    // NOTE: this is a heuristic, since we don't rely on the public suffix list.
+    // It only knows about common second-level domains used by country code
+    // top-level domains, such as "co.uk" or "com.au".
+    fn domain(&self) -> String {
+        let host = self.host.to_lowercase();
+
+        if self.is_ip() {
+            return host;
+        }
+
+        let labels: Vec<&str> = host.split('.').filter(|l| !l.is_empty()).collect();
+
+        if labels.len() < 2 {
+            return labels.join(".");
+        }
+
+        let tld = labels[labels.len() - 1];
+        let sld = labels[labels.len() - 2];
+
+        let keep = if labels.len() > 2
+            && tld.len() == 2
+            && matches!(
+                sld,
+                "ac" | "co" | "com" | "edu" | "go" | "gov" | "ne" | "net" | "or" | "org"
+            ) {
+            3
+        } else {
+            2
+        };
+
+        labels[labels.len() - keep..].join(".")
+    }
+
+    fn tld(&self) -> Option<String> {
+        if self.is_ip() {
+            return None;
+        }
+
+        self.host
+            .rsplit('.')
+            .find(|l| !l.is_empty())
+            .map(|l| l.to_lowercase())
+    }
+
+    fn normalize(&self) -> String {
+        let scheme = self.scheme.to_lowercase();
+        let mut normalized = format!("{}://{}", scheme, self.host.to_lowercase());
+
+        if let Some(port) = self.port {
+            if !matches!((scheme.as_str(), port), ("http", 80) | ("https", 443)) {
+                normalized.push_str(&format!(":{}", port));
+            }
+        }
+
+        normalized.push_str(if self.path.is_empty() { "/" } else { self.path });
+
+        if let Some(query) = self.query {
+            let params = query
+                .split('&')
+                .filter(|param| {
+                    let name = param.split_once('=').map(|(n, _)| n).unwrap_or(*param);
+                    !name.is_empty() && !is_tracking_param(name)
+                })
+                .collect::<Vec<_>>();
+
+            if !params.is_empty() {
+                normalized.push('?');
+                normalized.push_str(&params.join("&"));
+            }
+        }
+
+        normalized
+    }
+
+    fn into_map(self) -> HashMap<String, DynamicValue> {
+        let mut map = HashMap::new();
+        map.insert(
+            "scheme".to_string(),
+            DynamicValue::from(self.scheme.to_lowercase()),
+        );
+        map.insert(
+            "host".to_string(),
+            DynamicValue::from(self.host.to_lowercase()),
+        );
+        map.insert(
+            "port".to_string(),
+            self.port
+                .map(|p| DynamicValue::from(p as usize))
+                .unwrap_or(DynamicValue::None),
+        );
+        map.insert("path".to_string(), DynamicValue::from(self.path));
+        map.insert(
+            "query".to_string(),
+            self.query
+                .map(DynamicValue::from)
+                .unwrap_or(DynamicValue::None),
+        );
+        map.insert(
+            "fragment".to_string(),
+            self.fragment
+                .map(DynamicValue::from)
+                .unwrap_or(DynamicValue::None),
+        );
+
+        map
+    }
+}
+
+fn is_tracking_param(name: &str) -> bool {
+    name.starts_with("utm_")
+        || matches!(
+            name,
+            "fbclid"
+                | "gclid"
+                | "dclid"
+                | "msclkid"
+                | "yclid"
+                | "igshid"
+                | "mc_cid"
+                | "mc_eid"
+                | "_ga"
+        )
+}
+
+fn url_parse(args: BoundArguments) -> FunctionResult {
+    let url = args.get1().try_as_str()?;
+    let strict = args.get(1).map(|v| v.is_truthy()).unwrap_or(false);
+
+    match ParsedUrl::parse(&url) {
+        Some(parsed) => Ok(DynamicValue::from(parsed.into_map())),
+        None => {
+            if strict {
+                Err(EvaluationError::Custom(format!("invalid url \"{}\"", url)))
+            } else {
+                Ok(DynamicValue::None)
+            }
+        }
+    }
+}
+
+fn domain(args: BoundArguments) -> FunctionResult {
+    let url = args.get1().try_as_str()?;
+
+    Ok(ParsedUrl::parse(&url)
+        .map(|parsed| DynamicValue::from(parsed.domain()))
+        .unwrap_or(DynamicValue::None))
+}
+
+fn tld(args: BoundArguments) -> FunctionResult {
+    let url = args.get1().try_as_str()?;
+
+    Ok(ParsedUrl::parse(&url)
+        .and_then(|parsed| parsed.tld())
+        .map(DynamicValue::from)
+        .unwrap_or(DynamicValue::None))
+}
+
+fn normalize_url(args: BoundArguments) -> FunctionResult {
+    let url = args.get1().try_as_str()?;
+
+    Ok(ParsedUrl::parse(&url)
+        .map(|parsed| DynamicValue::from(parsed.normalize()))
+        .unwrap_or(DynamicValue::None))
+}
+
 fn mime_ext(args: BoundArguments) -> FunctionResult {
     let target = args.get1_str()?;
 
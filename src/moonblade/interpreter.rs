@@ -6,7 +6,7 @@ use regex::RegexBuilder;
 
 use super::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
 use super::functions::{get_function, Function};
-use super::parser::{parse_expression, Expr, FunctionCall};
+use super::parser::{parse_expression, parse_named_expressions, Expr, FunctionCall};
 use super::special_functions::{get_special_function, RuntimeFunction as SpecialFunction};
 use super::types::{
     BoundArguments, ColumIndexationBy, DynamicValue, EvaluationResult, FunctionArguments,
@@ -16,18 +16,24 @@ use super::types::{
 #[derive(Debug, Clone, Default)]
 pub struct EvaluationContext {
     headers_index: HeadersIndex,
+    filename: Option<String>,
 }
 
 impl EvaluationContext {
     pub fn new(headers: &ByteRecord) -> Self {
         Self {
             headers_index: HeadersIndex::from_headers(headers),
+            filename: None,
         }
     }
 
     pub fn get_column_index(&self, indexation: &ColumIndexationBy) -> Option<usize> {
         self.headers_index.get(indexation)
     }
+
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -71,6 +77,39 @@ impl ConcreteExpr {
         }
     }
 
+    // NOTE: columns whose index is greater or equal to offset are
+    // understood as referring to the given constants instead.
+    fn bind_constants(&mut self, offset: usize, constants: &ByteRecord) {
+        match self {
+            Self::Column(index) if *index >= offset => {
+                let value = DynamicValue::from_bytes(&constants[*index - offset]);
+                *self = Self::Value(value);
+            }
+            Self::Lambda(_, expr) => expr.bind_constants(offset, constants),
+            Self::List(exprs) => {
+                for expr in exprs.iter_mut() {
+                    expr.bind_constants(offset, constants);
+                }
+            }
+            Self::Map(entries) => {
+                for (_, expr) in entries.iter_mut() {
+                    expr.bind_constants(offset, constants);
+                }
+            }
+            Self::Call(call) => {
+                for expr in call.args.iter_mut() {
+                    expr.bind_constants(offset, constants);
+                }
+            }
+            Self::SpecialCall(call) => {
+                for expr in call.args.iter_mut() {
+                    expr.bind_constants(offset, constants);
+                }
+            }
+            _ => (),
+        }
+    }
+
     fn as_column(&self) -> Option<usize> {
         match self {
             Self::Column(index) => Some(*index),
@@ -252,7 +291,17 @@ impl ConcreteSpecialFunctionCall {
     fn is_statically_evaluable(&self, bound: &Vec<String>) -> bool {
         // NOTE: other special function are not suitable for late
         // statical evaluation.
-        if ["col", "cols", "headers", "index"].contains(&self.name.as_str()) {
+        if [
+            "col",
+            "col_exists",
+            "cols",
+            "filename",
+            "headers",
+            "index",
+            "row",
+        ]
+        .contains(&self.name.as_str())
+        {
             return false;
         }
 
@@ -504,6 +553,50 @@ impl Program {
         })
     }
 
+    // NOTE: the resulting program will evaluate to a list containing the
+    // result of each named expression, in order.
+    pub fn parse_named(
+        code: &str,
+        headers: &ByteRecord,
+    ) -> Result<(Self, Vec<String>), ConcretizationError> {
+        let parsed_exprs = match parse_named_expressions(code) {
+            Err(_) => return Err(ConcretizationError::ParseError(code.to_string())),
+            Ok(parsed_exprs) => parsed_exprs,
+        };
+
+        let mut exprs = Vec::with_capacity(parsed_exprs.len());
+        let mut names = Vec::with_capacity(parsed_exprs.len());
+
+        for (expr, name) in parsed_exprs {
+            if matches!(expr, Expr::StarSlice(_)) {
+                return Err(ConcretizationError::ParseError(code.to_string()));
+            }
+
+            exprs.push(expr);
+            names.push(name);
+        }
+
+        Ok((
+            Self {
+                expr: concretize_list(exprs, headers)?,
+                context: EvaluationContext::new(headers),
+            },
+            names,
+        ))
+    }
+
+    // NOTE: the program must have been parsed using the given headers,
+    // followed by the names of the constants, so that references to them
+    // can be replaced by their values.
+    pub fn bind_constants(&mut self, headers: &ByteRecord, constants: &ByteRecord) {
+        self.expr.bind_constants(headers.len(), constants);
+        self.context = EvaluationContext::new(headers);
+    }
+
+    pub fn set_filename(&mut self, filename: Option<String>) {
+        self.context.filename = filename;
+    }
+
     pub fn run_with_record(
         &self,
         index: usize,
@@ -618,6 +711,12 @@ mod tests {
         assert_eq!(eval_code("index() + 2"), Ok(DynamicValue::from(4)));
     }
 
+    #[test]
+    fn test_row() {
+        assert_eq!(eval_code("get(row(), 'surname')"), Ok(b("SMITH")));
+        assert_eq!(eval_code("len(row())"), Ok(DynamicValue::from(4)));
+    }
+
     #[test]
     fn test_typeof() {
         assert_eq!(eval_code("typeof(name)"), Ok(DynamicValue::from("bytes")));
@@ -652,6 +751,75 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_zip_enumerate() {
+        fn pair(a: DynamicValue, b: DynamicValue) -> DynamicValue {
+            DynamicValue::from(vec![a, b])
+        }
+
+        assert_eq!(
+            eval_code("zip(split('a|b|c', '|'), split('1|2', '|'))"),
+            Ok(DynamicValue::from(vec![
+                pair(DynamicValue::from("a"), DynamicValue::from("1")),
+                pair(DynamicValue::from("b"), DynamicValue::from("2")),
+            ]))
+        );
+
+        assert_eq!(
+            eval_code("zip(split('a|b|c', '|'), split('1|2', '|'), pad=true)"),
+            Ok(DynamicValue::from(vec![
+                pair(DynamicValue::from("a"), DynamicValue::from("1")),
+                pair(DynamicValue::from("b"), DynamicValue::from("2")),
+                pair(DynamicValue::from("c"), DynamicValue::None),
+            ]))
+        );
+
+        assert_eq!(
+            eval_code("enumerate(split('a|b', '|'))"),
+            Ok(DynamicValue::from(vec![
+                pair(DynamicValue::from(0), DynamicValue::from("a")),
+                pair(DynamicValue::from(1), DynamicValue::from("b")),
+            ]))
+        );
+    }
+
+    #[test]
+    fn test_range() {
+        fn ints(values: &[i64]) -> DynamicValue {
+            DynamicValue::from(
+                values
+                    .iter()
+                    .copied()
+                    .map(DynamicValue::from)
+                    .collect::<Vec<_>>(),
+            )
+        }
+
+        assert_eq!(eval_code("range(0, 5)"), Ok(ints(&[0, 1, 2, 3, 4])));
+        assert_eq!(eval_code("range(0, 5, 2)"), Ok(ints(&[0, 2, 4])));
+        assert_eq!(eval_code("range(5, 0, -2)"), Ok(ints(&[5, 3, 1])));
+        assert_eq!(eval_code("range(5, 0)"), Ok(ints(&[])));
+        assert!(eval_code("range(0, 5, 0)").is_err());
+        assert!(eval_code("range(0, 100000000000)").is_err());
+    }
+
+    #[test]
+    fn test_fixed_percent() {
+        assert_eq!(
+            eval_code("fixed(3.14159, 2)"),
+            Ok(DynamicValue::from("3.14"))
+        );
+        assert_eq!(eval_code("fixed(3, 2)"), Ok(DynamicValue::from("3.00")));
+        assert_eq!(eval_code("fixed(2.5, 0)"), Ok(DynamicValue::from("2")));
+        assert_eq!(eval_code("fixed(0.125, 2)"), Ok(DynamicValue::from("0.12")));
+        assert!(eval_code("fixed(2.5, -1)").is_err());
+        assert_eq!(eval_code("percent(0.42)"), Ok(DynamicValue::from("42.0%")));
+        assert_eq!(
+            eval_code("percent(0.4256, 2)"),
+            Ok(DynamicValue::from("42.56%"))
+        );
+    }
+
     #[test]
     fn test_arithmetics() {
         assert_eq!(eval_code("add(1, 2)"), Ok(DynamicValue::Integer(3)));
@@ -662,6 +830,54 @@ mod tests {
         assert_eq!(eval_code("div(3, 2)"), Ok(DynamicValue::Float(1.5)));
         assert_eq!(eval_code("idiv(4.5, 2)"), Ok(DynamicValue::Integer(2)));
         assert_eq!(eval_code("idiv(-4.5, 2)"), Ok(DynamicValue::Integer(-3)));
+        assert_eq!(
+            eval_code("clamp(150, 0, 100)"),
+            Ok(DynamicValue::Integer(100))
+        );
+        assert_eq!(
+            eval_code("clamp(-3.5, 0, 100)"),
+            Ok(DynamicValue::Integer(0))
+        );
+        assert_eq!(
+            eval_code("clamp(4.5, 0, 100)"),
+            Ok(DynamicValue::Float(4.5))
+        );
+        assert!(eval_code("clamp(4, 10, 0)").is_err());
+        assert_eq!(eval_code("sign(-4.5)"), Ok(DynamicValue::Integer(-1)));
+        assert_eq!(eval_code("sign(0)"), Ok(DynamicValue::Integer(0)));
+        assert_eq!(eval_code("sign(12)"), Ok(DynamicValue::Integer(1)));
+        assert_eq!(eval_code("gcd(12, -18)"), Ok(DynamicValue::Integer(6)));
+        assert_eq!(eval_code("lcm(4, 6.0)"), Ok(DynamicValue::Integer(12)));
+        assert!(eval_code("gcd(4.5, 2)").is_err());
+        assert!(eval_code("gcd(-9223372036854775807 - 1, 0)").is_err());
+        assert_eq!(
+            eval_code("gcd(-9223372036854775807 - 1, 6)"),
+            Ok(DynamicValue::Integer(2))
+        );
+        assert_eq!(
+            eval_code("floor_to(7205, 3600)"),
+            Ok(DynamicValue::Integer(7200))
+        );
+        assert_eq!(eval_code("ceil_to(11, 5)"), Ok(DynamicValue::Integer(15)));
+        assert_eq!(
+            eval_code("floor_to(0.17, 0.05)"),
+            Ok(DynamicValue::Float(0.15))
+        );
+        assert_eq!(
+            eval_code("round_to(1.3, 0.25)"),
+            Ok(DynamicValue::Float(1.25))
+        );
+        assert!(eval_code("round_to(1.3, 0)").is_err());
+        assert_eq!(eval_code("is_nan(sqrt(-1))"), Ok(DynamicValue::from(true)));
+        assert_eq!(eval_code("is_nan(1.5)"), Ok(DynamicValue::from(false)));
+        assert_eq!(
+            eval_code("is_finite(log(0))"),
+            Ok(DynamicValue::from(false))
+        );
+        assert_eq!(eval_code("is_finite(3)"), Ok(DynamicValue::from(true)));
+        assert_eq!(eval_code("nan_to_null(sqrt(-1))"), Ok(DynamicValue::None));
+        assert_eq!(eval_code("nan_to_null('inf')"), Ok(DynamicValue::None));
+        assert_eq!(eval_code("nan_to_null(2.5)"), Ok(DynamicValue::Float(2.5)));
     }
 
     #[test]
@@ -695,6 +911,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_first_non_empty() {
+        assert_eq!(
+            eval_code("first_non_empty(null, '', 0, 'test')"),
+            Ok(DynamicValue::from(0))
+        );
+        assert_eq!(
+            eval_code("first_non_empty('', false)"),
+            Ok(DynamicValue::from(false))
+        );
+        assert_eq!(
+            eval_code("coalesce_empty(null, '')"),
+            Ok(DynamicValue::None)
+        );
+    }
+
     #[test]
     fn test_bool() {
         assert_eq!(eval_code("not(true)"), Ok(DynamicValue::from(false)));
@@ -811,6 +1043,31 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_repeat_center() {
+        assert_eq!(
+            eval_code("repeat('ab', 3)"),
+            Ok(DynamicValue::from("ababab"))
+        );
+        assert_eq!(eval_code("repeat('ab', 0)"), Ok(DynamicValue::from("")));
+        assert!(eval_code("repeat('ab', 1000000000)").is_err());
+
+        assert_eq!(
+            eval_code("center('ab', 6)"),
+            Ok(DynamicValue::from("  ab  "))
+        );
+        assert_eq!(
+            eval_code("center('ab', 5, '-')"),
+            Ok(DynamicValue::from("-ab--"))
+        );
+        assert_eq!(
+            eval_code("center('été', 5, '*')"),
+            Ok(DynamicValue::from("*été*"))
+        );
+        assert_eq!(eval_code("center('abc', 2)"), Ok(DynamicValue::from("abc")));
+        assert!(eval_code("center('ab', 6, '--')").is_err());
+    }
+
     #[test]
     fn test_abs() {
         assert_eq!(eval_code("abs(-5)"), Ok(DynamicValue::Integer(5)));
@@ -878,6 +1135,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_try() {
+        assert_eq!(eval_code("try(upper(name), 'nope')"), Ok(b("JOHN")));
+        assert_eq!(
+            eval_code("try(err(name), 'nope')"),
+            Ok(DynamicValue::from("nope"))
+        );
+        assert_eq!(eval_code("try(err(name))"), Ok(DynamicValue::None));
+    }
+
     #[test]
     fn test_unless() {
         assert_eq!(eval_code("unless(true, 3, 2)"), Ok(DynamicValue::from(2)));
@@ -1068,6 +1335,24 @@ mod tests {
         assert_eq!(eval_code("bytesize(0)"), Ok(DynamicValue::from("0 B")));
     }
 
+    #[test]
+    fn test_parse_bytes() {
+        assert_eq!(
+            eval_code("parse_bytes('1.5GB')"),
+            Ok(DynamicValue::from(1_500_000_000))
+        );
+        assert_eq!(
+            eval_code("parse_bytes('2 KiB')"),
+            Ok(DynamicValue::from(2048))
+        );
+        assert_eq!(eval_code("parse_bytes('345')"), Ok(DynamicValue::from(345)));
+        assert_eq!(
+            eval_code("parse_bytes(bytesize(2510))"),
+            Ok(DynamicValue::from(2500))
+        );
+        assert!(eval_code("parse_bytes('nope')").is_err());
+    }
+
     #[test]
     fn test_map() {
         assert_eq!(
@@ -1089,6 +1374,35 @@ mod tests {
             eval_code("parse_json('{\"one\": 34}') | get(_, 'one')"),
             Ok(DynamicValue::from(34))
         );
+
+        assert_eq!(
+            eval_code("{id: 1, tags: split('a|b', '|'), name: name, none: null} | json_stringify"),
+            Ok(DynamicValue::from(
+                "{\"id\":1,\"name\":\"john\",\"none\":null,\"tags\":[\"a\",\"b\"]}"
+            ))
+        );
+
+        assert_eq!(
+            eval_code(
+                "parse_json('{\"user\": {\"addresses\": [{\"city\": \"Paris\"}]}}') | get_path(_, 'user.addresses.-1.city')"
+            ),
+            Ok(DynamicValue::from("Paris"))
+        );
+
+        assert_eq!(
+            eval_code("parse_json('{\"user\": [1]}') | get_path(_, 'user.1.city', 'none')"),
+            Ok(DynamicValue::from("none"))
+        );
+
+        assert_eq!(
+            eval_code("parse_json('{\"user\": 3}') | get_path(_, 'user.name')"),
+            Ok(DynamicValue::None)
+        );
+
+        assert_eq!(
+            eval_code("json_stringify([1.5, true], pretty=true)"),
+            Ok(DynamicValue::from("[\n  1.5,\n  true\n]"))
+        );
     }
 
     #[test]
@@ -1125,6 +1439,150 @@ mod tests {
         assert_eq!(eval_code("argmax([a, b])"), Ok(DynamicValue::from(1)));
     }
 
+    #[test]
+    fn test_sum_product() {
+        assert_eq!(eval_code("sum([1, 2, 3])"), Ok(DynamicValue::from(6)));
+        assert_eq!(eval_code("sum([1, 2.5])"), Ok(DynamicValue::from(3.5)));
+        assert_eq!(
+            eval_code("sum(split('4|5|6', '|'))"),
+            Ok(DynamicValue::from(15))
+        );
+        assert_eq!(eval_code("sum([])"), Ok(DynamicValue::from(0)));
+        assert!(eval_code("sum([1, 'test'])").is_err());
+
+        assert_eq!(eval_code("product([2, 3, 4])"), Ok(DynamicValue::from(24)));
+        assert_eq!(eval_code("product([])"), Ok(DynamicValue::from(1)));
+        assert!(eval_code("product(['a', 2])").is_err());
+    }
+
+    #[test]
+    fn test_levenshtein_jaro_winkler() {
+        assert_eq!(
+            eval_code("levenshtein('kitten', 'sitting')"),
+            Ok(DynamicValue::from(3))
+        );
+        assert_eq!(
+            eval_code("levenshtein('', 'abc')"),
+            Ok(DynamicValue::from(3))
+        );
+        assert_eq!(
+            eval_code("levenshtein('été', 'ete')"),
+            Ok(DynamicValue::from(2))
+        );
+
+        match eval_code("jaro_winkler('MARTHA', 'MARHTA')") {
+            Ok(DynamicValue::Float(f)) => assert!((f - 0.9611).abs() < 1e-4),
+            _ => panic!("expected a float"),
+        }
+
+        assert_eq!(
+            eval_code("jaro_winkler('john', 'john')"),
+            Ok(DynamicValue::from(1.0))
+        );
+        assert_eq!(
+            eval_code("jaro_winkler('abc', 'xyz')"),
+            Ok(DynamicValue::from(0.0))
+        );
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(
+            eval_code("url_encode('a b/é?')"),
+            Ok(DynamicValue::from("a%20b%2F%C3%A9%3F"))
+        );
+        assert_eq!(
+            eval_code("url_decode('a%20b%2F%C3%A9%3F%zz')"),
+            Ok(DynamicValue::from("a b/é?%zz"))
+        );
+        assert_eq!(
+            eval_code("get(url_parse('https://User@LeMonde.fr:8080/path/a?q=1#top'), 'host')"),
+            Ok(DynamicValue::from("lemonde.fr"))
+        );
+        assert_eq!(
+            eval_code("get(url_parse('https://lemonde.fr:8080/path/a?q=1#top'), 'port')"),
+            Ok(DynamicValue::from(8080))
+        );
+        assert_eq!(
+            eval_code("get(url_parse('https://lemonde.fr:8080/path/a?q=1#top'), 'path')"),
+            Ok(DynamicValue::from("/path/a"))
+        );
+        assert_eq!(
+            eval_code("get(url_parse('https://lemonde.fr?q=1#top'), 'query')"),
+            Ok(DynamicValue::from("q=1"))
+        );
+        assert_eq!(eval_code("url_parse('lemonde.fr')"), Ok(DynamicValue::None));
+        assert!(eval_code("url_parse('lemonde.fr', strict=true)").is_err());
+    }
+
+    #[test]
+    fn test_domain_tld_normalize_url() {
+        assert_eq!(
+            eval_code("domain('https://news.bbc.co.uk/sport')"),
+            Ok(DynamicValue::from("bbc.co.uk"))
+        );
+        assert_eq!(
+            eval_code("domain('https://www.LeMonde.fr/page')"),
+            Ok(DynamicValue::from("lemonde.fr"))
+        );
+        assert_eq!(
+            eval_code("domain('http://127.0.0.1:8000')"),
+            Ok(DynamicValue::from("127.0.0.1"))
+        );
+        assert_eq!(eval_code("domain('nope')"), Ok(DynamicValue::None));
+        assert_eq!(
+            eval_code("tld('https://news.bbc.co.uk/sport')"),
+            Ok(DynamicValue::from("uk"))
+        );
+        assert_eq!(
+            eval_code("normalize_url('HTTPS://LeMonde.fr:443/a?utm_source=x&id=3&fbclid=y#top')"),
+            Ok(DynamicValue::from("https://lemonde.fr/a?id=3"))
+        );
+        assert_eq!(
+            eval_code("normalize_url('http://lemonde.fr:8080?utm_medium=x')"),
+            Ok(DynamicValue::from("http://lemonde.fr:8080/"))
+        );
+    }
+
+    #[test]
+    fn test_soundex_metaphone() {
+        assert_eq!(
+            eval_code("soundex('Robert')"),
+            Ok(DynamicValue::from("R163"))
+        );
+        assert_eq!(
+            eval_code("soundex('Rupert')"),
+            Ok(DynamicValue::from("R163"))
+        );
+        assert_eq!(
+            eval_code("soundex('Ashcraft')"),
+            Ok(DynamicValue::from("A261"))
+        );
+        assert_eq!(
+            eval_code("soundex('Émile')"),
+            Ok(DynamicValue::from("E540"))
+        );
+        assert_eq!(eval_code("soundex('123')"), Ok(DynamicValue::from("")));
+
+        assert_eq!(
+            eval_code("metaphone('Knight')"),
+            Ok(DynamicValue::from("NT"))
+        );
+        assert_eq!(
+            eval_code("metaphone('Smith')"),
+            Ok(DynamicValue::from("SM0"))
+        );
+        assert_eq!(
+            eval_code("metaphone('phone')"),
+            Ok(DynamicValue::from("FN"))
+        );
+        assert_eq!(
+            eval_code("metaphone('judge')"),
+            Ok(DynamicValue::from("JJ"))
+        );
+        assert_eq!(eval_code("metaphone('')"), Ok(DynamicValue::from("")));
+    }
+
     #[test]
     fn test_timestamp() {
         let tz = TimeZone::UTC;
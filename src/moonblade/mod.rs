@@ -9,9 +9,13 @@ mod special_functions;
 mod types;
 mod utils;
 
-pub use self::agg::{AggregationProgram, GroupAggregationProgram, Stats};
+pub use self::agg::{is_aggregation_function, AggregationProgram, GroupAggregationProgram, Stats};
 pub use self::choose::ChooseProgram;
 pub use self::error::{ConcretizationError, EvaluationError, SpecifiedEvaluationError};
+pub use self::functions::{jaro_winkler, levenshtein};
 pub use self::interpreter::Program;
+pub use self::parser::{
+    are_all_expressions_named, parse_named_expression_items, NamedExpressionKind,
+};
 pub use self::select::SelectionProgram;
 pub use self::types::DynamicValue;
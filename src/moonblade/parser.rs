@@ -615,6 +615,54 @@ pub fn parse_named_expressions(input: &str) -> Result<Vec<(Expr, String)>, Parse
         .collect()
 }
 
+// NOTE: this is used to check whether every expression of a comma-separated
+// list was explicitly named using the `as` keyword.
+pub fn are_all_expressions_named(input: &str) -> bool {
+    match MoonbladePestParser::parse(Rule::named_exprs, input) {
+        Err(_) => false,
+        Ok(pairs) => pairs
+            .filter(|p| !matches!(p.as_rule(), Rule::EOI))
+            .all(|p| matches!(p.as_rule(), Rule::named_expr)),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum NamedExpressionKind {
+    Identifier(String),
+    Call(String),
+    Other,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NamedExpressionItem {
+    pub code: String,
+    pub name: String,
+    pub kind: NamedExpressionKind,
+}
+
+// NOTE: this is used to know, for each expression of a comma-separated list
+// of possibly named expressions, whether it is a bare identifier or a function
+// call, without having to concretize it.
+pub fn parse_named_expression_items(input: &str) -> Result<Vec<NamedExpressionItem>, ParseError> {
+    let pairs = MoonbladePestParser::parse(Rule::named_exprs, input)?;
+
+    pairs
+        .filter(|p| !matches!(p.as_rule(), Rule::EOI))
+        .map(|p| {
+            let code = p.as_str().to_string();
+            let (expr, name) = parse_named_expressions(&code)?.pop().unwrap();
+
+            let kind = match expr {
+                Expr::Identifier(column, _) => NamedExpressionKind::Identifier(column),
+                Expr::Func(call) => NamedExpressionKind::Call(call.name),
+                _ => NamedExpressionKind::Other,
+            };
+
+            Ok(NamedExpressionItem { code, name, kind })
+        })
+        .collect()
+}
+
 #[derive(Debug, PartialEq)]
 pub struct Aggregation {
     pub agg_name: String,
@@ -1,6 +1,7 @@
 // NOTE: the runtime function take a &[ConcreteExpr] instead of BoundArguments
 // because they notoriously might want not to bind arguments in the first
 // place (e.g. "if"/"unless").
+use std::collections::HashMap;
 use std::sync::Arc;
 
 use csv::ByteRecord;
@@ -64,6 +65,11 @@ pub fn get_special_function(
             Some(runtime_col),
             FunctionArguments::with_range(1..=2),
         ),
+        "col_exists" => (
+            Some(comptime_col_exists),
+            Some(runtime_col_exists),
+            FunctionArguments::with_range(1..=2),
+        ),
         "cols" => (
             Some(|call: &FunctionCall, headers: &ByteRecord| {
                 comptime_cols_headers(call, headers, ConcreteExpr::Column)
@@ -80,10 +86,29 @@ pub fn get_special_function(
             None,
             FunctionArguments::with_range(0..=2),
         ),
+        // NOTE: row is statically known from the headers, except when
+        // there are none, in which case keys are the column indices.
+        "row" => (
+            Some(comptime_row),
+            Some(runtime_row),
+            FunctionArguments::nullary(),
+        ),
         // NOTE: index needs to be a special function because it relies on external
         // data that cannot be accessed by normal functions.
         "index" => (None, Some(runtime_index), FunctionArguments::nullary()),
 
+        // NOTE: filename is special for the same reason.
+        "filename" => (None, Some(runtime_filename), FunctionArguments::nullary()),
+
+        // NOTE: ncols is always statically known from the headers.
+        "ncols" => (
+            Some(|_call: &FunctionCall, headers: &ByteRecord| {
+                Ok(Some(ConcreteExpr::Value(DynamicValue::from(headers.len()))))
+            }),
+            None,
+            FunctionArguments::nullary(),
+        ),
+
         // NOTE: if and unless need to be special functions because they short-circuit
         // underlying evaluation and circumvent the typical DFS evaluation scheme.
         // NOTE: if and unless don't require a comptime version because static evaluation
@@ -95,6 +120,14 @@ pub fn get_special_function(
             FunctionArguments::with_range(2..=3),
         ),
 
+        // NOTE: try needs to be a special function because it must be able to
+        // catch the errors raised when evaluating its first argument.
+        "try" => (
+            None,
+            Some(runtime_try),
+            FunctionArguments::with_range(1..=2),
+        ),
+
         // NOTE: lambda evaluation need to be a special function because, like
         // if and unless, they cannot work in DFS fashion unless you
         // bind some values ahead of time.
@@ -118,6 +151,35 @@ fn comptime_col(call: &FunctionCall, headers: &ByteRecord) -> ComptimeFunctionRe
     Ok(None)
 }
 
+fn comptime_col_exists(call: &FunctionCall, headers: &ByteRecord) -> ComptimeFunctionResult {
+    if let Some(column_indexation) = ColumIndexationBy::from_arguments(&call.raw_args_as_ref()) {
+        return Ok(Some(ConcreteExpr::Value(DynamicValue::Boolean(
+            column_indexation.find_column_index(headers).is_some(),
+        ))));
+    }
+
+    Ok(None)
+}
+
+fn comptime_row(_call: &FunctionCall, headers: &ByteRecord) -> ComptimeFunctionResult {
+    if headers.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ConcreteExpr::Map(
+        headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| {
+                (
+                    String::from_utf8_lossy(h).into_owned(),
+                    ConcreteExpr::Column(i),
+                )
+            })
+            .collect(),
+    )))
+}
+
 fn comptime_cols_headers<F>(
     call: &FunctionCall,
     headers: &ByteRecord,
@@ -213,6 +275,22 @@ fn runtime_unless(
     }
 }
 
+fn runtime_try(
+    index: Option<usize>,
+    record: &ByteRecord,
+    context: &EvaluationContext,
+    args: &[ConcreteExpr],
+    lambda_variables: Option<&LambdaArguments>,
+) -> EvaluationResult {
+    match args[0].evaluate(index, record, context, lambda_variables) {
+        Ok(value) => Ok(value),
+        Err(_) => match args.get(1) {
+            None => Ok(DynamicValue::None),
+            Some(default) => default.evaluate(index, record, context, lambda_variables),
+        },
+    }
+}
+
 fn runtime_index(
     index: Option<usize>,
     _record: &ByteRecord,
@@ -226,6 +304,35 @@ fn runtime_index(
     })
 }
 
+fn runtime_row(
+    _index: Option<usize>,
+    record: &ByteRecord,
+    _context: &EvaluationContext,
+    _args: &[ConcreteExpr],
+    _lambda_variables: Option<&LambdaArguments>,
+) -> EvaluationResult {
+    Ok(DynamicValue::from(
+        record
+            .iter()
+            .enumerate()
+            .map(|(i, cell)| (i.to_string(), DynamicValue::from_bytes(cell)))
+            .collect::<HashMap<_, _>>(),
+    ))
+}
+
+fn runtime_filename(
+    _index: Option<usize>,
+    _record: &ByteRecord,
+    context: &EvaluationContext,
+    _args: &[ConcreteExpr],
+    _lambda_variables: Option<&LambdaArguments>,
+) -> EvaluationResult {
+    Ok(match context.filename() {
+        None => DynamicValue::None,
+        Some(filename) => DynamicValue::from(filename),
+    })
+}
+
 fn runtime_col(
     index: Option<usize>,
     record: &ByteRecord,
@@ -257,6 +364,33 @@ fn runtime_col(
     }
 }
 
+fn runtime_col_exists(
+    index: Option<usize>,
+    record: &ByteRecord,
+    context: &EvaluationContext,
+    args: &[ConcreteExpr],
+    lambda_variables: Option<&LambdaArguments>,
+) -> EvaluationResult {
+    let name_or_pos = args
+        .first()
+        .unwrap()
+        .evaluate(index, record, context, lambda_variables)?;
+    let pos = match args.get(1) {
+        Some(p) => Some(p.evaluate(index, record, context, lambda_variables)?),
+        None => None,
+    };
+
+    match ColumIndexationBy::from_bound_arguments(name_or_pos, pos) {
+        None => Err(SpecifiedEvaluationError::new(
+            "col_exists",
+            EvaluationError::Custom("invalid arguments".to_string()),
+        )),
+        Some(indexation) => Ok(DynamicValue::Boolean(
+            context.get_column_index(&indexation).is_some(),
+        )),
+    }
+}
+
 #[derive(Clone, Copy)]
 enum HigherOrderOperation {
     Filter,
@@ -3,7 +3,7 @@ use std::cmp::{Ord, Ordering, PartialEq, PartialOrd};
 use std::collections::{btree_map::Entry, BTreeMap, HashMap, VecDeque};
 use std::convert::{From, TryFrom};
 use std::fmt;
-use std::ops::{Add, AddAssign, Div, Mul, Neg, RangeInclusive, Rem, Sub};
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, RangeInclusive, Rem, Sub};
 use std::str::FromStr;
 use std::sync::Arc;
 
@@ -424,6 +424,13 @@ impl DynamicNumber {
         }
     }
 
+    pub fn is_finite(&self) -> bool {
+        match self {
+            Self::Float(f) => f.is_finite(),
+            Self::Integer(_) => true,
+        }
+    }
+
     pub fn is_float(&self) -> bool {
         matches!(self, Self::Float(_))
     }
@@ -513,6 +520,34 @@ impl DynamicNumber {
     pub fn sqrt(self) -> Self {
         self.map_float(|n| n.sqrt())
     }
+
+    pub fn sign(self) -> Self {
+        match self {
+            Self::Integer(n) => Self::Integer(n.signum()),
+            Self::Float(n) => {
+                if n.is_nan() {
+                    self
+                } else if n == 0.0 {
+                    Self::Integer(0)
+                } else {
+                    Self::Integer(n.signum() as i64)
+                }
+            }
+        }
+    }
+
+    pub fn try_as_integral(self) -> Option<i64> {
+        match self {
+            Self::Integer(n) => Some(n),
+            Self::Float(f) => {
+                if f.is_finite() && f.fract() == 0.0 {
+                    Some(f as i64)
+                } else {
+                    None
+                }
+            }
+        }
+    }
 }
 
 impl<'de> Deserialize<'de> for DynamicNumber {
@@ -653,6 +688,21 @@ impl Mul for DynamicNumber {
     }
 }
 
+impl MulAssign for DynamicNumber {
+    fn mul_assign(&mut self, rhs: Self) {
+        match self {
+            DynamicNumber::Float(a) => match rhs {
+                DynamicNumber::Float(b) => *a *= b,
+                DynamicNumber::Integer(b) => *a *= b as f64,
+            },
+            DynamicNumber::Integer(a) => match rhs {
+                DynamicNumber::Float(b) => *self = DynamicNumber::Float((*a as f64) * b),
+                DynamicNumber::Integer(b) => *a *= b,
+            },
+        };
+    }
+}
+
 impl Div for DynamicNumber {
     type Output = Self;
 
@@ -549,6 +549,10 @@ impl Selection {
         Self((0..len).collect())
     }
 
+    pub fn from_indices(indices: Vec<usize>) -> Self {
+        Self(indices)
+    }
+
     pub fn without_indices(len: usize, indices: &[usize]) -> Self {
         let mut sel = Self::full(len);
 
@@ -1,5 +1,6 @@
 use std::borrow::Cow;
 use std::cell::RefCell;
+use std::env;
 use std::fs;
 use std::io;
 use std::num::NonZeroUsize;
@@ -25,7 +26,7 @@ use serde::de::{Deserialize, DeserializeOwned, Deserializer, Error};
 use unicode_segmentation::UnicodeSegmentation;
 use unicode_width::UnicodeWidthStr;
 
-use crate::config::{Config, Delimiter};
+use crate::config::{Comment, Config, Delimiter};
 use crate::dates;
 use crate::select::SelectColumns;
 use crate::CliResult;
@@ -105,6 +106,7 @@ where
 pub fn many_configs(
     inps: &[String],
     delim: Option<Delimiter>,
+    comment: Option<Comment>,
     no_headers: bool,
     select: Option<&SelectColumns>,
 ) -> Result<Vec<Config>, String> {
@@ -117,6 +119,7 @@ pub fn many_configs(
         .map(|p| {
             let mut conf = Config::new(&Some(p))
                 .delimiter(delim)
+                .comment(comment)
                 .no_headers(no_headers);
 
             if let Some(sel) = select {
@@ -164,6 +167,16 @@ pub fn idx_path(csv_path: &Path) -> PathBuf {
     PathBuf::from(&p)
 }
 
+pub fn key_idx_path(csv_path: &Path) -> PathBuf {
+    let mut p = csv_path
+        .to_path_buf()
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    p.push_str(".kidx");
+    PathBuf::from(&p)
+}
+
 pub type Idx = Option<usize>;
 
 pub fn range(start: Idx, end: Idx, len: Idx, index: Idx) -> Result<(usize, usize), String> {
@@ -276,8 +289,14 @@ impl<'de> Deserialize<'de> for FilenameTemplate {
     }
 }
 
+pub fn env_seed() -> Option<usize> {
+    env::var("XAN_SEED")
+        .ok()
+        .and_then(|seed| seed.trim().parse::<usize>().ok())
+}
+
 pub fn acquire_rng(seed: Option<usize>) -> Box<dyn RngCore> {
-    match seed {
+    match seed.or_else(env_seed) {
         None => Box::new(rand::rng()),
         Some(seed) => Box::new(Seeder::from(seed).into_rng::<ChaCha8Rng>()),
     }
@@ -890,7 +909,10 @@ impl ExternalChunk<DeepSizedByteRecord> for CsvExternalChunk {
 
     fn new(reader: io::Take<io::BufReader<fs::File>>) -> Self {
         CsvExternalChunk {
-            reader: csv::Reader::from_reader(reader),
+            reader: csv::ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_reader(reader),
         }
     }
 
@@ -898,7 +920,9 @@ impl ExternalChunk<DeepSizedByteRecord> for CsvExternalChunk {
         chunk_writer: &mut io::BufWriter<fs::File>,
         items: impl IntoIterator<Item = DeepSizedByteRecord>,
     ) -> Result<(), Self::SerializationError> {
-        let mut csv_writer = csv::Writer::from_writer(chunk_writer);
+        let mut csv_writer = csv::WriterBuilder::new()
+            .flexible(true)
+            .from_writer(chunk_writer);
 
         for item in items.into_iter() {
             csv_writer.write_record(item.as_ref())?;
@@ -33,6 +33,9 @@ fn agg() {
     test_single_agg_function(&wrk, "q1(n) as q1", "q1", "1.75");
     test_single_agg_function(&wrk, "q3(n) as q3", "q3", "3.25");
     test_single_agg_function(&wrk, "median_low(n) as median", "median", "2");
+    test_single_agg_function(&wrk, "mad(n) as mad", "mad", "1");
+    test_single_agg_function(&wrk, "iqr(n) as iqr", "iqr", "1.5");
+    test_single_agg_function(&wrk, "span(n) as span", "span", "3");
     test_single_agg_function(&wrk, "median_high(n) as median", "median", "3");
     test_single_agg_function(&wrk, "var(n) as variance", "variance", "1.25");
     test_single_agg_function(&wrk, "var_pop(n) as variance", "variance", "1.25");
@@ -109,6 +112,29 @@ fn agg_mode_cardinality() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn agg_mode_tie() {
+    let wrk = Workdir::new("agg_mode_tie");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["color"],
+            svec!["yellow"],
+            svec!["red"],
+            svec!["blue"],
+            svec!["red"],
+            svec!["blue"],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("mode(color) as mode").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["mode"], svec!["blue"]];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn agg_sqlish_count() {
     let wrk = Workdir::new("agg_sqlish_count");
@@ -552,3 +578,119 @@ fn agg_correlation() {
     let expected = vec![svec!["c", "r"], svec!["3.8", "0.442939783914149"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn agg_count_distinct_where() {
+    let wrk = Workdir::new("agg_count_distinct_where");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["user", "status"],
+            svec!["john", "ok"],
+            svec!["mary", "ko"],
+            svec!["john", "ok"],
+            svec!["lucy", "ok"],
+            svec!["mary", "ko"],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("count_distinct_where(status eq 'ok', user) as users, count() as count")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["users", "count"], svec!["2", "5"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("count_distinct_where(user)").arg("data.csv");
+
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_where() {
+    let wrk = Workdir::new("agg_where");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["status", "amount"],
+            svec!["paid", "10"],
+            svec!["pending", "not-a-number"],
+            svec!["paid", "20"],
+            svec!["", "100"],
+            svec!["paid", ""],
+        ],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("sum_where(status eq 'paid', amount) as sum, mean_where(status eq 'paid', amount) as mean, count_where(status eq 'paid') as count, count_where(status) as with_status")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["sum", "mean", "count", "with_status"],
+        svec!["30", "15", "3", "4"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("sum_where(status eq 'paid')").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("count_where()").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_post() {
+    let wrk = Workdir::new("agg_post");
+    wrk.create(
+        "data.csv",
+        vec![svec!["n"], svec!["1"], svec!["2"], svec!["3"], svec!["6"]],
+    );
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("sum(n) as sum, count() as count")
+        .args(["--post", "sum, sum / count as avg"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["sum", "avg"], svec!["12", "3"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg("sum(n) as sum")
+        .args(["--post", "unknown * 2 as double"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn agg_approx_quantile() {
+    let wrk = Workdir::new("agg_approx_quantile");
+    let mut rows = vec![svec!["n", "empty"]];
+    for i in 1..=1001 {
+        rows.push(vec![i.to_string(), "".to_string()]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("agg");
+    cmd.arg(
+        "approx_median(n) as median, approx_quantile(n, 0.9) as p90, approx_median(empty) as none",
+    )
+    .args(["-p", "-c", "100"])
+    .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["median", "p90", "none"]);
+
+    let median = got[1][0].parse::<f64>().unwrap();
+    assert!((median - 501.0).abs() < 10.0);
+
+    let p90 = got[1][1].parse::<f64>().unwrap();
+    assert!((p90 - 901.0).abs() < 10.0);
+
+    assert_eq!(got[1][2], "");
+}
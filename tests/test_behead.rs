@@ -14,3 +14,49 @@ fn behead() {
     let expected = vec![svec!["a", "1"], svec!["b", "2"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn behead_rows() {
+    let wrk = Workdir::new("behead_rows").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["Some banner"],
+            svec!["letter", "number"],
+            svec!["a", "1"],
+            svec!["b", "2"],
+        ],
+    );
+    let mut cmd = wrk.command("behead");
+    cmd.args(["--rows", "2"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn behead_add() {
+    let wrk = Workdir::new("behead_add");
+    wrk.create("data.csv", vec![svec!["a", "1"], svec!["b", "2"]]);
+
+    let mut cmd = wrk.command("behead");
+    cmd.args(["--add", "letter,number"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+
+    // Replacing the header
+    let mut cmd = wrk.command("behead");
+    cmd.args(["--add", "l,n", "--rows", "1"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["l", "n"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+
+    // Misaligned header
+    let mut cmd = wrk.command("behead");
+    cmd.args(["--add", "letter"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -53,6 +53,38 @@ fn cat_rows_headers() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn cat_rows_mismatched_headers() {
+    let wrk = Workdir::new("cat_rows_mismatched_headers");
+    wrk.create("a.csv", vec![svec!["h1", "h2"], svec!["a", "b"]]);
+    wrk.create("b.csv", vec![svec!["h2", "h1"], svec!["c", "d"]]);
+    wrk.create("c.csv", vec![svec!["h1"], svec!["e"]]);
+    wrk.create("d.csv", vec![svec!["h1", "h3"], svec!["f", "g"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("rows").arg("a.csv").arg("b.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "--flexible"])
+        .arg("a.csv")
+        .arg("b.csv")
+        .arg("c.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h1", "h2"],
+        svec!["a", "b"],
+        svec!["d", "c"],
+        svec!["e", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("cat");
+    cmd.args(["rows", "--flexible"]).arg("a.csv").arg("d.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn cat_rows_paths() {
     let wrk = Workdir::new("cat_rows_paths");
@@ -142,3 +174,113 @@ fn cat_cols_pad() {
     let got: Vec<Vec<String>> = run_cat("cat_cols_headers", "columns", rows1, rows2, pad);
     assert_eq!(got, expected);
 }
+
+#[test]
+fn cat_interleave() {
+    let rows1 = vec![svec!["h1", "h2"], svec!["a", "b"], svec!["c", "d"]];
+    let rows2 = vec![
+        svec!["h1", "h2"],
+        svec!["w", "x"],
+        svec!["y", "z"],
+        svec!["u", "v"],
+    ];
+
+    let got: Vec<Vec<String>> = run_cat("cat_interleave", "interleave", rows1, rows2, |_| ());
+    let expected = vec![
+        svec!["h1", "h2"],
+        svec!["a", "b"],
+        svec!["w", "x"],
+        svec!["c", "d"],
+        svec!["y", "z"],
+        svec!["u", "v"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_interleave_paths() {
+    let wrk = Workdir::new("cat_interleave_paths");
+    wrk.create("a.csv", vec![svec!["name"], svec!["John"], svec!["Mary"]]);
+    wrk.create("b.csv", vec![svec!["name"], svec!["Suzy"], svec!["Abel"]]);
+    wrk.create("p.csv", vec![svec!["path"], svec!["a.csv"], svec!["b.csv"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("interleave")
+        .args(["--path-column", "path"])
+        .args(["--paths", "p.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name"],
+        svec!["John"],
+        svec!["Suzy"],
+        svec!["Mary"],
+        svec!["Abel"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("columns").args(["--paths", "p.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn cat_interleave_flexible() {
+    let wrk = Workdir::new("cat_interleave_flexible");
+    wrk.create("a.csv", vec![svec!["h1", "h2"], svec!["a", "b"]]);
+    wrk.create("b.csv", vec![svec!["h2", "h1"], svec!["d", "c"]]);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("interleave").arg("a.csv").arg("b.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("interleave")
+        .arg("--flexible")
+        .args(["--source-column", "source"])
+        .arg("a.csv")
+        .arg("b.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "h1", "h2"],
+        svec!["a.csv", "a", "b"],
+        svec!["b.csv", "c", "d"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn cat_rows_select() {
+    let wrk = Workdir::new("cat_rows_select");
+    wrk.create(
+        "a.csv",
+        vec![svec!["name", "age", "city"], svec!["John", "34", "Paris"]],
+    );
+    wrk.create(
+        "b.csv",
+        vec![svec!["age", "color", "name"], svec!["27", "red", "Suzy"]],
+    );
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("rows")
+        .args(["-s", "name,age"])
+        .args(["--source-column", "source"])
+        .arg("a.csv")
+        .arg("b.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["source", "name", "age"],
+        svec!["a.csv", "John", "34"],
+        svec!["b.csv", "Suzy", "27"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("cat");
+    cmd.arg("rows")
+        .args(["-s", "city"])
+        .arg("a.csv")
+        .arg("b.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -1,3 +1,5 @@
+use std::fs;
+
 use crate::workdir::Workdir;
 
 #[test]
@@ -64,3 +66,98 @@ fn count_empty_no_headers() {
 
     assert_eq!(got.trim(), "0");
 }
+
+#[test]
+fn count_comment() {
+    let wrk = Workdir::new("count_comment");
+    fs::write(
+        wrk.path("data.csv"),
+        "# comment\nname\n# another\njohn\n\"#mary\"\n",
+    )
+    .unwrap();
+
+    let mut cmd = wrk.command("count");
+    cmd.args(["--comment", "#"]).arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+
+    assert_eq!(got.trim(), "2");
+
+    let mut cmd = wrk.command("count");
+    cmd.arg("data.csv").args(["--comment", "#"]);
+
+    let got: String = wrk.stdout(&mut cmd);
+
+    assert_eq!(got.trim(), "2");
+
+    let mut cmd = wrk.command("count");
+    cmd.args(["--comment", "##"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn count_approx_lines_and_bytes() {
+    let wrk = Workdir::new("count_approx_lines_and_bytes");
+    fs::write(wrk.path("data.csv"), "name,text\njohn,\"a\nb\"\nmary,c").unwrap();
+
+    let mut cmd = wrk.command("count");
+    cmd.arg("--approx-lines").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got.trim(), "3");
+
+    let mut cmd = wrk.command("count");
+    cmd.args(["--approx-lines", "-n"]).arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got.trim(), "4");
+
+    let mut cmd = wrk.command("count");
+    cmd.arg("--bytes").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got.trim(), "27");
+}
+
+#[test]
+fn count_groupby() {
+    let wrk = Workdir::new("count_groupby");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["country", "city"],
+            svec!["fr", "Paris"],
+            svec!["us", "NYC"],
+            svec!["us", "LA"],
+            svec!["fr", "Lyon"],
+            svec!["us", "NYC"],
+            svec!["de", "Berlin"],
+        ],
+    );
+
+    let mut cmd = wrk.command("count");
+    cmd.args(["-g", "country"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "count"],
+        svec!["fr", "2"],
+        svec!["us", "3"],
+        svec!["de", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("count");
+    cmd.args(["-g", "country,city", "--sort"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "count"],
+        svec!["us", "NYC", "2"],
+        svec!["fr", "Paris", "1"],
+        svec!["us", "LA", "1"],
+        svec!["fr", "Lyon", "1"],
+        svec!["de", "Berlin", "1"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -366,3 +366,91 @@ fn dedup_choose_sorted() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn dedup_dups_to() {
+    let wrk = Workdir::new("dedup_dups_to");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "n"],
+            svec!["a", "1"],
+            svec!["b", "2"],
+            svec!["a", "3"],
+            svec!["a", "4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "id", "--dups-to", "dups.csv"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "n"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+
+    let dups: String = wrk.from_str(&wrk.path("dups.csv"));
+    assert_eq!(dups, "id,n\na,3\na,4\n");
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "id", "-l", "--dups-to", "dups.csv"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "n"], svec!["b", "2"], svec!["a", "4"]];
+    assert_eq!(got, expected);
+
+    let dups: String = wrk.from_str(&wrk.path("dups.csv"));
+    assert_eq!(dups, "id,n\na,1\na,3\n");
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["-s", "id", "--check", "--dups-to", "dups.csv"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn dedup_fuzzy() {
+    let wrk = Workdir::new("dedup_fuzzy");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "n"],
+            svec!["Jonathan Smith", "1"],
+            svec!["Alice Walker", "2"],
+            svec!["jonathan smyth ", "3"],
+            svec!["John Smith", "4"],
+            svec!["Alicia Walker", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--fuzzy", "name"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "n"],
+        svec!["Jonathan Smith", "1"],
+        svec!["Alice Walker", "2"],
+        svec!["John Smith", "4"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--fuzzy", "name", "--threshold", "0.95"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "n"],
+        svec!["Jonathan Smith", "1"],
+        svec!["Alice Walker", "2"],
+        svec!["John Smith", "4"],
+        svec!["Alicia Walker", "5"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("dedup");
+    cmd.args(["--fuzzy", "name", "-s", "n"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -179,3 +179,100 @@ fn explode_multipe_columns_rename() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn explode_json() {
+    let wrk = Workdir::new("explode_json");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "items"],
+            svec!["Mary", "[\"yellow\", 3, null]"],
+            svec!["John", "[{\"a\": 1}, [1, 2], true]"],
+            svec!["Jack", "[]"],
+            svec!["Lisa", ""],
+        ],
+    );
+    let mut cmd = wrk.command("explode");
+    cmd.arg("items").arg("--json").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "items"],
+        svec!["Mary", "yellow"],
+        svec!["Mary", "3"],
+        svec!["Mary", ""],
+        svec!["John", "{\"a\":1}"],
+        svec!["John", "[1,2]"],
+        svec!["John", "true"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("explode");
+    cmd.arg("items")
+        .args(["--json", "--keep-empty"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[7..], [svec!["Jack", ""], svec!["Lisa", ""]]);
+
+    wrk.create("invalid.csv", vec![svec!["items"], svec!["{\"a\": 1}"]]);
+    let mut cmd = wrk.command("explode");
+    cmd.arg("items").arg("--json").arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn explode_skip_empty() {
+    let wrk = Workdir::new("explode_skip_empty");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "colors"],
+            svec!["Mary", "yellow"],
+            svec!["Jack", ""],
+            svec!["John", "blue|orange"],
+        ],
+    );
+    let mut cmd = wrk.command("explode");
+    cmd.arg("colors").arg("--skip-empty").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "colors"],
+        svec!["Mary", "yellow"],
+        svec!["John", "blue"],
+        svec!["John", "orange"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("explode");
+    cmd.arg("colors").args(["--sep", ""]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn explode_implode_round_trip() {
+    let wrk = Workdir::new("explode_implode_round_trip");
+    let data = vec![
+        svec!["name", "colors"],
+        svec!["Mary", "yellow"],
+        svec!["Jack", ""],
+        svec!["John", "blue|orange"],
+        svec!["Lucy", "|red||"],
+        svec!["Mary", "green"],
+    ];
+    wrk.create("data.csv", data.clone());
+
+    let mut cmd = wrk.command("explode");
+    cmd.arg("colors")
+        .arg("data.csv")
+        .args(["-o", "exploded.csv"]);
+    wrk.run(&mut cmd);
+
+    let mut cmd = wrk.command("implode");
+    cmd.arg("colors").arg("exploded.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, data);
+}
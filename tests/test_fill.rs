@@ -0,0 +1,88 @@
+use crate::workdir::Workdir;
+
+fn data() -> Vec<Vec<String>> {
+    vec![
+        svec!["name", "status"],
+        svec!["john", ""],
+        svec!["mary", "ok"],
+        svec!["lucy", ""],
+        svec!["", "ko"],
+        svec!["paul", ""],
+    ]
+}
+
+#[test]
+fn fill() {
+    let wrk = Workdir::new("fill");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("fill");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "status"],
+        svec!["john", ""],
+        svec!["mary", "ok"],
+        svec!["lucy", "ok"],
+        svec!["lucy", "ko"],
+        svec!["paul", "ko"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fill_value() {
+    let wrk = Workdir::new("fill_value");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("fill");
+    cmd.args(["-s", "status", "-v", "?"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "status"],
+        svec!["john", "?"],
+        svec!["mary", "ok"],
+        svec!["lucy", "?"],
+        svec!["", "ko"],
+        svec!["paul", "?"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("fill");
+    cmd.args(["-s", "status", "-v", "?", "--down"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "status"],
+        svec!["john", "?"],
+        svec!["mary", "ok"],
+        svec!["lucy", "ok"],
+        svec!["", "ko"],
+        svec!["paul", "ko"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn fill_up() {
+    let wrk = Workdir::new("fill_up");
+    wrk.create("data.csv", data());
+    let mut cmd = wrk.command("fill");
+    cmd.args(["-s", "status", "--up"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "status"],
+        svec!["john", "ok"],
+        svec!["mary", "ok"],
+        svec!["lucy", "ko"],
+        svec!["", "ko"],
+        svec!["paul", ""],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("fill");
+    cmd.args(["--up", "--down"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
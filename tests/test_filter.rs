@@ -44,3 +44,76 @@ fn filter_limit() {
     let expected = vec![svec!["a"], svec!["2"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn filter_in() {
+    let wrk = Workdir::new("filter_in");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["1", "john"],
+            svec!["2", "mary"],
+            svec!["3", "lucy"],
+            svec!["4", "abel"],
+        ],
+    );
+    wrk.create(
+        "ids.csv",
+        vec![svec!["user", "id"], svec!["x", "2"], svec!["y", "4"]],
+    );
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--in", "ids.csv", "id"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["2", "mary"], svec!["4", "abel"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--not-in", "ids.csv", "id"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["1", "john"], svec!["3", "lucy"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--in", "ids.csv", "id", "--where", "id > 2"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"], svec!["4", "abel"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--in", "ids.csv", "--ref-column", "user", "id"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id", "name"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--in", "ids.csv", "--not-in", "ids.csv", "id"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["-v", "--in", "ids.csv", "id"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn filter_glob() {
+    let wrk = Workdir::new("filter_glob");
+    wrk.create("a.csv", vec![svec!["n"], svec!["1"], svec!["2"]]);
+    wrk.create("b.csv", vec![svec!["n"], svec!["3"], svec!["4"]]);
+
+    let mut cmd = wrk.command("filter");
+    cmd.args(["--glob", "*.csv"])
+        .arg("n > 1 && filename() ne 'b.csv'");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["2"]];
+    assert_eq!(got, expected);
+}
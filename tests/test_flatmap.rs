@@ -77,3 +77,39 @@ fn flatmap_filtermap() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn flatmap_json() {
+    let wrk = Workdir::new("flatmap_json");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "meta", "age"],
+            svec!["john", "{\"user\": {\"id\": 1}, \"lang\": \"en\"}", "34"],
+            svec!["mary", "{\"user\": {\"id\": 2, \"verified\": true}}", "45"],
+            svec!["jordan", "", "22"],
+        ],
+    );
+    let mut cmd = wrk.command("flatmap");
+    cmd.arg("--json").arg("meta").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "name",
+            "meta.lang",
+            "meta.user.id",
+            "meta.user.verified",
+            "age"
+        ],
+        svec!["john", "en", "1", "", "34"],
+        svec!["mary", "", "2", "true", "45"],
+        svec!["jordan", "", "", "", "22"],
+    ];
+    assert_eq!(got, expected);
+
+    wrk.create("invalid.csv", vec![svec!["meta"], svec!["[1, 2]"]]);
+    let mut cmd = wrk.command("flatmap");
+    cmd.arg("--json").arg("meta").arg("invalid.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -83,3 +83,28 @@ abcdefaghijkl
 mnopqrastuvwx";
     assert_eq!(got, expected.to_string());
 }
+
+#[test]
+fn fmt_align() {
+    let wrk = Workdir::new("fmt_align");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "count", "city"],
+            svec!["john", "1", "paris"],
+            svec!["marianne", "1234", ""],
+            svec!["bob", "-3.5", "new\nyork"],
+        ],
+    );
+
+    let mut cmd = wrk.command("fmt");
+    cmd.arg("--align").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+name      count  city
+john          1  paris
+marianne   1234
+bob        -3.5  new york";
+    assert_eq!(got, expected);
+}
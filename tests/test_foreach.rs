@@ -0,0 +1,164 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+#[test]
+fn foreach() {
+    let wrk = Workdir::new("foreach");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "text"],
+            svec!["one.txt", "1"],
+            svec!["two.txt", "2"],
+        ],
+    );
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("write(text, name)").arg("data.csv");
+
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(fs::read_to_string(wrk.path("one.txt")).unwrap(), "1");
+    assert_eq!(fs::read_to_string(wrk.path("two.txt")).unwrap(), "2");
+}
+
+#[test]
+fn foreach_parallel() {
+    let wrk = Workdir::new("foreach_parallel");
+
+    let mut rows = vec![svec!["name", "text"]];
+
+    for i in 0..50 {
+        rows.push(vec![format!("{}.txt", i), i.to_string()]);
+    }
+
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("write(text, name)")
+        .args(["-t", "4"])
+        .arg("data.csv");
+
+    wrk.assert_success(&mut cmd);
+
+    for i in 0..50 {
+        assert_eq!(
+            fs::read_to_string(wrk.path(&format!("{}.txt", i))).unwrap(),
+            i.to_string()
+        );
+    }
+}
+
+#[test]
+fn foreach_parallel_errors_panic() {
+    let wrk = Workdir::new("foreach_parallel_errors_panic");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n"],
+            svec!["1"],
+            svec!["two"],
+            svec!["3"],
+            svec!["four"],
+        ],
+    );
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("n + 1").arg("-p").arg("data.csv");
+
+    let output = cmd.output().unwrap();
+    assert!(!output.status.success());
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.starts_with("Row n°2:"));
+}
+
+#[test]
+fn foreach_parallel_errors_log() {
+    let wrk = Workdir::new("foreach_parallel_errors_log");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n"],
+            svec!["1"],
+            svec!["two"],
+            svec!["3"],
+            svec!["four"],
+        ],
+    );
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("n + 1")
+        .args(["-E", "log"])
+        .args(["-t", "2"])
+        .arg("data.csv");
+
+    let output = wrk.output(&mut cmd);
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let rows = stderr
+        .lines()
+        .map(|line| line.split(':').next().unwrap())
+        .collect::<Vec<_>>();
+
+    assert_eq!(rows, vec!["Row n°2", "Row n°4"]);
+}
+
+#[test]
+fn foreach_command_dry_run() {
+    let wrk = Workdir::new("foreach_command_dry_run");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["path", "name"],
+            svec!["a.jpg", "a"],
+            svec!["my file.jpg", "it's"],
+        ],
+    );
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("-c")
+        .arg("--dry-run")
+        .arg("convert {path} {name}.png {{}}")
+        .arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+convert a.jpg a.png {}
+convert 'my file.jpg' 'it'\\''s'.png {}";
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("-c").arg("echo {unknown}").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn foreach_command() {
+    let wrk = Workdir::new("foreach_command");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "text"],
+            svec!["one.txt", "1"],
+            svec!["two words.txt", "2"],
+        ],
+    );
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("-c")
+        .arg("printf %s {text} > {name}")
+        .arg("data.csv");
+
+    wrk.assert_success(&mut cmd);
+
+    assert_eq!(fs::read_to_string(wrk.path("one.txt")).unwrap(), "1");
+    assert_eq!(fs::read_to_string(wrk.path("two words.txt")).unwrap(), "2");
+
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("-c").arg("exit {text}").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("foreach");
+    cmd.arg("-c")
+        .arg("exit {text}")
+        .args(["-E", "ignore"])
+        .arg("data.csv");
+    wrk.assert_success(&mut cmd);
+}
@@ -73,6 +73,24 @@ fn frequency_nulls() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn frequency_empty_label() {
+    let (wrk, mut cmd) = setup("frequency_empty_label");
+    cmd.args(["--limit", "0"])
+        .args(["--select", "h1"])
+        .args(["--empty-label", "N/A"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["h1", "N/A", "1"],
+        svec!["h1", "a", "3"],
+        svec!["h1", "b", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn frequency_limit() {
     let (wrk, mut cmd) = setup("frequency_limit");
@@ -295,3 +313,35 @@ fn frequency_all() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn frequency_parallel() {
+    let (wrk, mut cmd) = setup("frequency_parallel");
+    cmd.arg("-p").args(["-c", "2"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["field", "value", "count"],
+        svec!["h1", "a", "3"],
+        svec!["h1", "<empty>", "1"],
+        svec!["h1", "b", "1"],
+        svec!["h2", "z", "3"],
+        svec!["h2", "y", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    // With groupby
+    let (wrk, mut cmd) = setup("frequency_parallel_groupby");
+    cmd.args(["-g", "h1"]).arg("-p").args(["-c", "1"]);
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got.sort();
+    let expected = vec![
+        svec!["field", "h1", "value", "count"],
+        svec!["h2", "", "z", "1"],
+        svec!["h2", "a", "y", "2"],
+        svec!["h2", "a", "z", "1"],
+        svec!["h2", "b", "z", "1"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,50 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+#[test]
+fn from_json_dir() {
+    let wrk = Workdir::new("from_json_dir");
+
+    fs::create_dir_all(wrk.path("docs/sub")).unwrap();
+    fs::write(
+        wrk.path("docs/a.json"),
+        r#"{"name": "John", "meta": {"age": 34}}"#,
+    )
+    .unwrap();
+    fs::write(
+        wrk.path("docs/b.json"),
+        r#"{"name": "Mary", "city": "Paris"}"#,
+    )
+    .unwrap();
+    fs::write(wrk.path("docs/notes.txt"), "not json").unwrap();
+    fs::write(wrk.path("docs/sub/c.json"), r#"{"name": "Abel"}"#).unwrap();
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["-f", "json-dir"]).arg("docs");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "meta.age", "city"],
+        svec!["John", "34", ""],
+        svec!["Mary", "", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["-f", "json-dir", "--recursive"]).arg("docs");
+
+    let mut got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    got[1..].sort();
+    let expected = vec![
+        svec!["name", "meta.age", "city"],
+        svec!["Abel", "", ""],
+        svec!["John", "34", ""],
+        svec!["Mary", "", "Paris"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("from");
+    cmd.args(["-f", "json-dir"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,59 @@
+use std::fs;
+
+use crate::workdir::Workdir;
+
+#[test]
+fn glob() {
+    let wrk = Workdir::new("glob");
+    fs::write(wrk.path("one.txt"), "hello").unwrap();
+    fs::write(wrk.path("two.txt"), "world").unwrap();
+    fs::write(wrk.path("three.md"), "!").unwrap();
+
+    let mut cmd = wrk.command("glob");
+    cmd.arg("*.txt");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["path"], svec!["one.txt"], svec!["two.txt"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn glob_meta() {
+    let wrk = Workdir::new("glob_meta");
+    fs::write(wrk.path("one.txt"), "hello").unwrap();
+    fs::write(wrk.path("two"), "!").unwrap();
+
+    let mut cmd = wrk.command("glob");
+    cmd.arg("*").arg("--meta");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    assert_eq!(got[0], svec!["path", "size", "mtime", "ext"]);
+    assert_eq!(got.len(), 3);
+
+    assert_eq!(got[1][0], "one.txt");
+    assert_eq!(got[1][1], "5");
+    assert!(got[1][2].ends_with('Z'));
+    assert_eq!(got[1][3], "txt");
+
+    assert_eq!(got[2][0], "two");
+    assert_eq!(got[2][1], "1");
+    assert_eq!(got[2][3], "");
+}
+
+#[cfg(unix)]
+#[test]
+fn glob_meta_broken_symlink() {
+    let wrk = Workdir::new("glob_meta_broken_symlink");
+    std::os::unix::fs::symlink(wrk.path("missing.txt"), wrk.path("broken.txt")).unwrap();
+
+    let mut cmd = wrk.command("glob");
+    cmd.arg("*").arg("--meta");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["path", "size", "mtime", "ext"],
+        svec!["broken.txt", "", "", "txt"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -58,6 +58,67 @@ fn groupby_count() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn groupby_with_count() {
+    let wrk = Workdir::new("groupby_with_count");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "value_A"],
+            svec!["x", "1"],
+            svec!["y", "2"],
+            svec!["y", "1"],
+            svec!["z", "3"],
+        ],
+    );
+
+    let expected = vec![
+        svec!["id", "sumA", "count"],
+        svec!["x", "1", "1"],
+        svec!["y", "3", "2"],
+        svec!["z", "3", "1"],
+    ];
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("sum(value_A) as sumA")
+        .arg("data.csv")
+        .arg("--with-count");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("sum(value_A) as sumA")
+        .arg("data.csv")
+        .args(["--with-count", "--sorted"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    // Expression can be omitted
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id").arg("--with-count").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "count"],
+        svec!["x", "1"],
+        svec!["y", "2"],
+        svec!["z", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    // Name clash
+    let mut cmd = wrk.command("groupby");
+    cmd.arg("id")
+        .arg("sum(value_A) as count")
+        .arg("data.csv")
+        .arg("--with-count");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn groupby_sum() {
     let wrk = Workdir::new("groupby");
@@ -55,3 +55,66 @@ h2
 h3";
     assert_eq!(got, expected.to_string());
 }
+
+#[test]
+fn headers_diff() {
+    let (wrk, mut cmd) = setup("headers_diff");
+    wrk.create("in3.csv", vec![svec!["h3", "h1"]]);
+    cmd.arg("in3.csv").arg("--diff");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = "\
+Only in in1.csv:
+  h2 (1)
+
+Only in in3.csv:
+  h3 (0)
+
+In both:
+  h1 (0 -> 1)";
+    assert_eq!(got, expected.to_string());
+}
+
+#[test]
+fn headers_diff_csv() {
+    let (wrk, mut cmd) = setup("headers_diff_csv");
+    cmd.arg("in2.csv").arg("--diff").arg("--csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["column", "status", "left_index", "right_index"],
+        svec!["h1", "left", "0", ""],
+        svec!["h2", "both", "1", "0"],
+        svec!["h3", "right", "", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let (wrk, mut cmd) = setup("headers_diff_csv_err");
+    cmd.arg("--diff");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn headers_json() {
+    let (wrk, mut cmd) = setup("headers_json");
+    cmd.arg("--json");
+
+    let got: String = wrk.stdout(&mut cmd);
+    let expected = r#"[
+  {
+    "index": 0,
+    "name": "h1"
+  },
+  {
+    "index": 1,
+    "name": "h2"
+  }
+]"#;
+    assert_eq!(got, expected.to_string());
+
+    let (wrk, mut cmd) = setup("headers_json_multiple");
+    cmd.arg("in2.csv").arg("--json");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert!(got.contains(r#""path": "in2.csv""#));
+}
@@ -207,3 +207,43 @@ fn implode_cmp() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn implode_unique() {
+    let wrk = Workdir::new("implode_unique");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "color", "letter"],
+            svec!["Mary", "yellow", "a"],
+            svec!["John", "blue", "b"],
+            svec!["John", "orange", "c"],
+            svec!["John", "blue", "b"],
+            svec!["John", "blue", "d"],
+        ],
+    );
+
+    let mut cmd = wrk.command("implode");
+    cmd.args(["color", "--unique"])
+        .arg("data.csv")
+        .args(["--cmp", "name"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "color", "letter"],
+        svec!["Mary", "yellow", "a"],
+        svec!["John", "blue|orange", "d"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("implode");
+    cmd.args(["color,letter", "--unique"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "color", "letter"],
+        svec!["Mary", "yellow", "a"],
+        svec!["John", "blue|orange|blue", "b|c|d"],
+    ];
+    assert_eq!(got, expected);
+}
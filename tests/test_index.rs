@@ -22,6 +22,34 @@ fn index_outdated() {
     wrk.assert_err(&mut cmd);
 }
 
+#[test]
+fn index_key() {
+    let wrk = Workdir::new("index_key");
+    wrk.create(
+        "in.csv",
+        vec![svec!["id", "name"], svec!["2", "john"], svec!["1", "mary"]],
+    );
+
+    let mut cmd = wrk.command("index");
+    cmd.args(["--key", "id", "in.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    assert!(fs::metadata(wrk.path("in.csv.idx")).is_ok());
+
+    let kidx = fs::read(wrk.path("in.csv.kidx")).unwrap();
+    assert!(kidx.starts_with(b"XANKIDX1"));
+}
+
+#[test]
+fn index_key_invalid_column() {
+    let wrk = Workdir::new("index_key_invalid_column");
+    wrk.create("in.csv", vec![svec!["id", "name"], svec!["1", "john"]]);
+
+    let mut cmd = wrk.command("index");
+    cmd.args(["--key", "unknown", "in.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
 fn future_time(ft: FileTime) -> FileTime {
     let secs = ft.seconds_relative_to_1970();
     FileTime::from_seconds_since_1970(secs + 10_000, 0)
@@ -0,0 +1,30 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn input_repair() {
+    let wrk = Workdir::new("input_repair").flexible(true);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "age", "city"],
+            svec!["john", "34"],
+            svec!["mary", "45", "Paris"],
+            svec!["lucy", "23", "Lyon", "extra"],
+        ],
+    );
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("input");
+    cmd.arg("--repair").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "age", "city"],
+        svec!["john", "34", ""],
+        svec!["mary", "45", "Paris"],
+    ];
+    assert_eq!(got, expected);
+}
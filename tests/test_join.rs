@@ -238,3 +238,323 @@ fn join_prefix() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn join_index() {
+    let wrk = Workdir::new("join_index");
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["city", "state"],
+            svec!["Boston", "MA"],
+            svec!["New York", "NY"],
+            svec!["Buffalo", "NY"],
+        ],
+    );
+    wrk.create(
+        "places.csv",
+        vec![
+            svec!["city", "place"],
+            svec!["Boston", "Logan Airport"],
+            svec!["Buffalo", "Ralph Wilson Stadium"],
+            svec!["Boston", "Boston Garden"],
+            svec!["Orlando", "Disney World"],
+        ],
+    );
+
+    let mut cmd = wrk.command("index");
+    cmd.args(["--key", "city", "places.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    // Inner
+    let mut cmd = wrk.command("join");
+    cmd.args(["--index", "city", "cities.csv", "city", "places.csv"]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place"],
+        svec!["Boston", "MA", "Boston", "Logan Airport"],
+        svec!["Boston", "MA", "Boston", "Boston Garden"],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium"],
+    ];
+    assert_eq!(got, expected);
+
+    // Left
+    let mut cmd = wrk.command("join");
+    cmd.args([
+        "--index",
+        "--left",
+        "city",
+        "cities.csv",
+        "city",
+        "places.csv",
+    ]);
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place"],
+        svec!["Boston", "MA", "Boston", "Logan Airport"],
+        svec!["Boston", "MA", "Boston", "Boston Garden"],
+        svec!["New York", "NY", "", ""],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn join_index_errors() {
+    let wrk = Workdir::new("join_index_errors");
+    wrk.create("cities.csv", vec![svec!["city"], svec!["Boston"]]);
+    wrk.create("places.csv", vec![svec!["city"], svec!["Boston"]]);
+
+    // Missing index
+    let mut cmd = wrk.command("join");
+    cmd.args(["--index", "city", "cities.csv", "city", "places.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    // Missing key index
+    let mut cmd = wrk.command("index");
+    cmd.arg("places.csv");
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--index", "city", "cities.csv", "city", "places.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    // Unsupported join
+    let mut cmd = wrk.command("join");
+    cmd.args([
+        "--index",
+        "--full",
+        "city",
+        "cities.csv",
+        "city",
+        "places.csv",
+    ]);
+    wrk.assert_err(&mut cmd);
+
+    // Unsupported key normalization
+    let mut cmd = wrk.command("index");
+    cmd.args(["--key", "city", "places.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--index", "-i", "city", "cities.csv", "city", "places.csv"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn join_ignore_case_and_trim() {
+    let wrk = Workdir::new("join_ignore_case_and_trim");
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["city", "country"],
+            svec!["Paris ", "FR"],
+            svec!["İzmir", "TR"],
+        ],
+    );
+    wrk.create(
+        "places.csv",
+        vec![
+            svec!["place", "country", "name"],
+            svec!["paris", "fr", "Louvre"],
+            svec!["i̇zmir", "tr", "Agora"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["city,country", "cities.csv", "place,country", "places.csv"])
+        .args(["--ignore-case", "--trim"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "country", "place", "country", "name"],
+        svec!["Paris ", "FR", "paris", "fr", "Louvre"],
+        svec!["İzmir", "TR", "i̇zmir", "tr", "Agora"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["city", "cities.csv", "place", "places.csv", "-i"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 2);
+}
+
+#[test]
+fn join_indicator() {
+    let wrk = setup("join_indicator", true);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["city", "cities.csv", "city", "places.csv"])
+        .arg("--full")
+        .args(["--indicator", "_merge"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place", "_merge"],
+        svec!["Boston", "MA", "Boston", "Logan Airport", "both"],
+        svec!["Boston", "MA", "Boston", "Boston Garden", "both"],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium", "both"],
+        svec!["", "", "Orlando", "Disney World", "right_only"],
+        svec!["New York", "NY", "", "", "left_only"],
+        svec!["San Francisco", "CA", "", "", "left_only"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["city", "cities.csv", "city", "places.csv"])
+        .arg("--left")
+        .args(["--indicator", "_merge"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["city", "state", "city", "place", "_merge"],
+        svec!["Boston", "MA", "Boston", "Logan Airport", "both"],
+        svec!["Boston", "MA", "Boston", "Boston Garden", "both"],
+        svec!["New York", "NY", "", "", "left_only"],
+        svec!["San Francisco", "CA", "", "", "left_only"],
+        svec!["Buffalo", "NY", "Buffalo", "Ralph Wilson Stadium", "both"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["city", "cities.csv", "city", "places.csv"])
+        .args(["--indicator", "_merge"]);
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn join_interval() {
+    let wrk = Workdir::new("join_interval");
+    wrk.create(
+        "events.csv",
+        vec![
+            svec!["event", "ts"],
+            svec!["a", "5"],
+            svec!["b", "12"],
+            svec!["c", "-3"],
+            svec!["d", "25"],
+        ],
+    );
+    wrk.create(
+        "periods.csv",
+        vec![
+            svec!["start", "end", "label"],
+            svec!["10", "20", "second"],
+            svec!["0", "10", "first"],
+            svec!["8", "15", "overlap"],
+            svec!["20", "", "open"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--interval")
+        .args(["ts", "events.csv", "start,end", "periods.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["event", "ts", "start", "end", "label"],
+        svec!["a", "5", "0", "10", "first"],
+        svec!["b", "12", "8", "15", "overlap"],
+        svec!["b", "12", "10", "20", "second"],
+        svec!["d", "25", "20", "", "open"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--interval", "--left"])
+        .args(["ts", "events.csv", "start,end", "periods.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[4], svec!["c", "-3", "", "", ""]);
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--interval")
+        .args(["ts", "events.csv", "start", "periods.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    for flag in ["-i", "--trim", "--nulls"] {
+        let mut cmd = wrk.command("join");
+        cmd.args(["--interval", flag])
+            .args(["ts", "events.csv", "start,end", "periods.csv"]);
+        wrk.assert_err(&mut cmd);
+    }
+}
+
+#[test]
+fn join_sorted() {
+    let wrk = Workdir::new("join_sorted");
+    wrk.create(
+        "left.csv",
+        vec![
+            svec!["id", "left"],
+            svec!["", "l0"],
+            svec!["a", "l1"],
+            svec!["b", "l2"],
+            svec!["b", "l3"],
+            svec!["d", "l4"],
+        ],
+    );
+    wrk.create(
+        "right.csv",
+        vec![
+            svec!["id", "right"],
+            svec!["", "r0"],
+            svec!["b", "r1"],
+            svec!["b", "r2"],
+            svec!["c", "r3"],
+            svec!["d", "r4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.arg("--sorted")
+        .args(["id", "left.csv", "id", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "left", "id", "right"],
+        svec!["b", "l2", "b", "r1"],
+        svec!["b", "l2", "b", "r2"],
+        svec!["b", "l3", "b", "r1"],
+        svec!["b", "l3", "b", "r2"],
+        svec!["d", "l4", "d", "r4"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--sorted", "--full", "--indicator", "status"])
+        .args(["id", "left.csv", "id", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "left", "id", "right", "status"],
+        svec!["", "l0", "", "", "left_only"],
+        svec!["", "", "", "r0", "right_only"],
+        svec!["a", "l1", "", "", "left_only"],
+        svec!["b", "l2", "b", "r1", "both"],
+        svec!["b", "l2", "b", "r2", "both"],
+        svec!["b", "l3", "b", "r1", "both"],
+        svec!["b", "l3", "b", "r2", "both"],
+        svec!["", "", "c", "r3", "right_only"],
+        svec!["d", "l4", "d", "r4", "both"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--sorted", "--nulls"])
+        .args(["id", "left.csv", "id", "right.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[1], svec!["", "l0", "", "r0"]);
+
+    wrk.create(
+        "unsorted.csv",
+        vec![svec!["id", "right"], svec!["c", "r1"], svec!["b", "r2"]],
+    );
+
+    let mut cmd = wrk.command("join");
+    cmd.args(["--sorted", "--right"])
+        .args(["id", "left.csv", "id", "unsorted.csv"]);
+    wrk.assert_err(&mut cmd);
+}
@@ -0,0 +1,72 @@
+use crate::workdir::Workdir;
+
+fn setup(name: &str) -> Workdir {
+    let wrk = Workdir::new(name);
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name"],
+            svec!["99", "john"],
+            svec!["42", "mary"],
+            svec!["7", "lucy"],
+            svec!["42", "lisa"],
+        ],
+    );
+
+    let mut cmd = wrk.command("index");
+    cmd.args(["--key", "id", "data.csv"]);
+    wrk.assert_success(&mut cmd);
+
+    wrk
+}
+
+#[test]
+fn lookup() {
+    let wrk = setup("lookup");
+    let mut cmd = wrk.command("lookup");
+    cmd.args(["id", "42,99", "data.csv"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["id", "name"],
+        svec!["42", "mary"],
+        svec!["42", "lisa"],
+        svec!["99", "john"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn lookup_missing() {
+    let wrk = setup("lookup_missing");
+    let mut cmd = wrk.command("lookup");
+    cmd.args(["id", "7,13", "data.csv"]);
+
+    let output = wrk.output(&mut cmd);
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "id,name\n7,lucy\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("13"));
+
+    let mut cmd = wrk.command("lookup");
+    cmd.args(["--ignore-missing", "id", "7,13", "data.csv"]);
+
+    let output = wrk.output(&mut cmd);
+    assert!(output.status.success());
+    assert!(output.stderr.is_empty());
+}
+
+#[test]
+fn lookup_errors() {
+    let wrk = setup("lookup_errors");
+
+    // Wrong key column
+    let mut cmd = wrk.command("lookup");
+    cmd.args(["name", "john", "data.csv"]);
+    wrk.assert_err(&mut cmd);
+
+    // No key index
+    wrk.create("other.csv", vec![svec!["id"], svec!["1"]]);
+    let mut cmd = wrk.command("lookup");
+    cmd.args(["id", "1", "other.csv"]);
+    wrk.assert_err(&mut cmd);
+}
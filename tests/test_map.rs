@@ -152,3 +152,201 @@ fn map_errors_log() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn map_errors_to() {
+    let wrk = Workdir::new("map_errors_to");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "test"], svec!["2", "3"]],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.arg("add(a, b)")
+        .arg("c")
+        .args(["--errors-to", "errors.csv"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c"],
+        svec!["1", "test", ""],
+        svec!["2", "3", "5"],
+    ];
+    assert_eq!(got, expected);
+
+    let errors: String = wrk.from_str(&wrk.path("errors.csv"));
+    assert!(errors.starts_with("index,error\n0,"));
+    assert_eq!(errors.lines().count(), 2);
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("add(a, b)")
+        .arg("c")
+        .args(["--errors-to", "errors.csv", "-E", "panic"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn map_multiple_columns() {
+    let wrk = Workdir::new("map_multiple_columns");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "2"], svec!["2", "3"]],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.arg("a + b as c, a * b as d").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c", "d"],
+        svec!["1", "2", "3", "2"],
+        svec!["2", "3", "5", "6"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn map_multiple_columns_errors_report() {
+    let wrk = Workdir::new("map_multiple_columns_errors_report");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "test"], svec!["2", "3"]],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.arg("a as c, add(a, b) as d")
+        .args(["-E", "report"])
+        .args(["--error-column", "error"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c", "d", "error"],
+        svec!["1", "test", "", "", "error when calling function \"add\": cannot safely cast from type \"bytes\" to type \"number\""],
+        svec!["2", "3", "2", "5", ""],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn map_seeded_random() {
+    let wrk = Workdir::new("map_seeded_random");
+    wrk.create("data.csv", vec![svec!["a"], svec!["1"], svec!["2"]]);
+
+    let run = |seed: &str| -> Vec<Vec<String>> {
+        let mut cmd = wrk.command("map");
+        cmd.arg("random() as r, uuid() as id")
+            .arg("data.csv")
+            .env("XAN_SEED", seed);
+        wrk.read_stdout(&mut cmd)
+    };
+
+    let first = run("42");
+
+    assert_eq!(first.len(), 3);
+    assert_ne!(first[1], first[2]);
+    assert_eq!(first, run("42"));
+    assert_ne!(first, run("43"));
+}
+
+#[test]
+fn map_col_exists_ncols() {
+    let wrk = Workdir::new("map_col_exists_ncols");
+    wrk.create("data.csv", vec![svec!["a", "b"], svec!["1", "2"]]);
+    let mut cmd = wrk.command("map");
+    cmd.arg("if(col_exists('price'), price?, 0) as price, col_exists('b') as has_b, col_exists(concat('a', '')) as has_a, ncols() as n")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "price", "has_b", "has_a", "n"],
+        svec!["1", "2", "0", "true", "true", "2"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn map_prelude() {
+    let wrk = Workdir::new("map_prelude");
+    wrk.create(
+        "data.csv",
+        vec![svec!["value"], svec!["3"], svec!["5"], svec!["8"]],
+    );
+    let mut cmd = wrk.command("map");
+    cmd.args(["--prelude", "min(value) as lo, max(value) as hi"])
+        .arg("value - lo as shifted, hi as top")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["value", "shifted", "top"],
+        svec!["3", "0", "8"],
+        svec!["5", "2", "8"],
+        svec!["8", "5", "8"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("map");
+    cmd.args(["--prelude", "max(value) as hi"])
+        .arg("value - hi as diff");
+    wrk.assert_err(&mut cmd);
+
+    // Prelude names cannot shadow existing columns
+    let mut cmd = wrk.command("map");
+    cmd.args(["--prelude", "max(value) as value"])
+        .arg("value * 2 as double")
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn map_glob() {
+    let wrk = Workdir::new("map_glob");
+    wrk.create("a.csv", vec![svec!["n"], svec!["1"], svec!["2"]]);
+    wrk.create("b.csv", vec![svec!["n"], svec!["3"]]);
+    wrk.create("c.tsv", vec![svec!["m"], svec!["4"]]);
+
+    let mut cmd = wrk.command("map");
+    cmd.args(["--glob", "*.csv"])
+        .arg("filename() as file, index() as i");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "file", "i"],
+        svec!["1", "a.csv", "0"],
+        svec!["2", "a.csv", "1"],
+        svec!["3", "b.csv", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("map");
+    cmd.args(["--glob", "*.*"]).arg("filename() as file");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn map_row() {
+    let wrk = Workdir::new("map_row");
+    wrk.create("data.csv", vec![svec!["a", "b"], svec!["1", "2"]]);
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("json_stringify(row()) as json").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "json"],
+        svec!["1", "2", "{\"a\":\"1\",\"b\":\"2\"}"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("map");
+    cmd.arg("-n")
+        .arg("json_stringify(row()) as json")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "{\"0\":\"a\",\"1\":\"b\"}"],
+        svec!["1", "2", "{\"0\":\"1\",\"1\":\"2\"}"],
+    ];
+    assert_eq!(got, expected);
+}
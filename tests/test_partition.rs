@@ -417,3 +417,95 @@ TX,Fort Worth
 "
     );
 }
+
+#[test]
+fn partition_manifest() {
+    let wrk = Workdir::new("partition_manifest");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.arg("state")
+        .arg("out")
+        .args(["--manifest", "manifest.csv"])
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(
+        wrk,
+        "manifest.csv",
+        "\
+value,path,rows
+NY,out/NY.csv,2
+CA,out/CA.csv,1
+TX,out/TX.csv,2
+"
+    );
+
+    wrk.create("sorted.csv", sorted_data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.arg("state")
+        .arg("sorted")
+        .args(["--manifest", "manifest.csv"])
+        .arg("-S")
+        .arg("sorted.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(
+        wrk,
+        "manifest.csv",
+        "\
+value,path,rows
+NY,sorted/NY.csv,2
+CA,sorted/CA.csv,1
+TX,sorted/TX.csv,2
+"
+    );
+}
+
+#[test]
+fn partition_evaluate() {
+    let wrk = Workdir::new("partition_evaluate");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(["-e", "len(city) > 7"])
+        .arg("out")
+        .args(["--filename", "long_{}.csv"])
+        .args(["--manifest", "manifest.csv"])
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    part_eq!(
+        wrk,
+        "out/long_true.csv",
+        "\
+state,city
+NY,Manhattan
+CA,San Francisco
+TX,Fort Worth
+"
+    );
+    part_eq!(
+        wrk,
+        "out/long_false.csv",
+        "\
+state,city
+TX,Dallas
+NY,Buffalo
+"
+    );
+    part_eq!(
+        wrk,
+        "manifest.csv",
+        "\
+value,path,rows
+true,out/long_true.csv,3
+false,out/long_false.csv,2
+"
+    );
+
+    let mut cmd = wrk.command("partition");
+    cmd.args(["-e", "state", "--drop"]).arg("out").arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
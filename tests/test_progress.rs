@@ -0,0 +1,31 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn progress() {
+    let wrk = Workdir::new("progress");
+    wrk.create(
+        "data.csv",
+        vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]],
+    );
+    let mut cmd = wrk.command("progress");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn progress_indexed() {
+    let wrk = Workdir::new("progress_indexed");
+    wrk.create_indexed(
+        "data.csv",
+        vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]],
+    );
+    let mut cmd = wrk.command("progress");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["letter", "number"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+}
@@ -59,3 +59,101 @@ fn range_inclusive() {
     let expected = vec![svec!["n"], svec!["0"], svec!["1"], svec!["2"], svec!["3"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn range_float_step() {
+    let wrk = Workdir::new("range_float_step");
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("0.5").args(["--step", "0.1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n"],
+        svec!["0"],
+        svec!["0.1"],
+        svec!["0.2"],
+        svec!["0.3"],
+        svec!["0.4"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_float_inclusive() {
+    let wrk = Workdir::new("range_float_inclusive");
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("1.5")
+        .args(["-s", "0.5"])
+        .args(["--step", "0.25"])
+        .arg("-i");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n"],
+        svec!["0.5"],
+        svec!["0.75"],
+        svec!["1"],
+        svec!["1.25"],
+        svec!["1.5"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_negative_step() {
+    let wrk = Workdir::new("range_negative_step");
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("0").args(["-s", "3"]).args(["--step", "-1"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["3"], svec!["2"], svec!["1"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_count() {
+    let wrk = Workdir::new("range_count");
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("1").args(["--count", "5"]).arg("-i");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n"],
+        svec!["0"],
+        svec!["0.25"],
+        svec!["0.5"],
+        svec!["0.75"],
+        svec!["1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("1").args(["--count", "4"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n"],
+        svec!["0"],
+        svec!["0.25"],
+        svec!["0.5"],
+        svec!["0.75"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn range_invalid_step() {
+    let wrk = Workdir::new("range_invalid_step");
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("1").args(["--step", "1e-5"]);
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("range");
+    cmd.arg("1").args(["--step", "0.0000000000000000001"]);
+    wrk.assert_err(&mut cmd);
+}
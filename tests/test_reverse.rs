@@ -52,3 +52,45 @@ fn reverse_in_memory() {
     let expected = vec![svec!["n"], svec!["3"], svec!["2"], svec!["1"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn reverse_columns() {
+    let wrk = Workdir::new("reverse_columns");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["a", "b", "c"],
+            svec!["1", "2", "3"],
+            svec!["4", "5", "6"],
+        ],
+    );
+
+    let mut cmd = wrk.command("reverse");
+    cmd.arg("--columns").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["c", "b", "a"],
+        svec!["3", "2", "1"],
+        svec!["6", "5", "4"],
+    ];
+    assert_eq!(got, expected);
+
+    let expected = vec![
+        svec!["c", "b", "a"],
+        svec!["6", "5", "4"],
+        svec!["3", "2", "1"],
+    ];
+
+    let mut cmd = wrk.command("reverse");
+    cmd.args(["--columns", "--rows"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("reverse");
+    cmd.args(["--columns", "--rows", "-m"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+}
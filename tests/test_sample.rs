@@ -107,3 +107,34 @@ fn sample_weighted_grouped() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn sample_fraction() {
+    let wrk = Workdir::new("sample_fraction");
+    let mut rows = vec![svec!["number"]];
+    for i in 0..1000 {
+        rows.push(vec![i.to_string()]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--fraction", "0.1", "--seed", "123"])
+        .arg("data.csv");
+
+    let first: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(first[0], svec!["number"]);
+    assert!(first.len() > 50 && first.len() < 150);
+
+    let second: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(first, second);
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--fraction", "1"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 1001);
+
+    let mut cmd = wrk.command("sample");
+    cmd.args(["--fraction", "0"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
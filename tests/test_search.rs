@@ -438,3 +438,80 @@ fn search_all() {
     let expected = vec![svec!["name", "color"], svec!["John", "red"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn search_join() {
+    let wrk = Workdir::new("search_join");
+
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "surname"],
+            svec!["John", "Smith"],
+            svec!["Mary", "John"],
+        ],
+    );
+
+    let mut cmd = wrk.command("search");
+    cmd.args(["--join", " ", "John Smith"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name", "surname"], svec!["John", "Smith"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("search");
+    cmd.args(["--join", "|", "-c", "count", "-r", "^\\w+\\|"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "surname", "count"],
+        svec!["John", "Smith", "1"],
+        svec!["Mary", "John", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("search");
+    cmd.args(["--join", " ", "--all", "John"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn search_dotall() {
+    let wrk = Workdir::new("search_dotall");
+
+    wrk.create(
+        "data.csv",
+        vec![svec!["text"], svec!["hello\nworld"], svec!["hello world"]],
+    );
+
+    let mut cmd = wrk.command("search");
+    cmd.args(["-r", "hello.world"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["text"], svec!["hello world"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("search");
+    cmd.args(["-r", "--dotall", "hello.world"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["text"], svec!["hello\nworld"], svec!["hello world"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn search_highlight_not_a_tty() {
+    let wrk = Workdir::new("search_highlight_not_a_tty");
+    wrk.create("data.csv", data(true));
+    let mut cmd = wrk.command("search");
+    cmd.args(["-r", "^foo", "--highlight"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["h1", "h2"],
+        svec!["foobar", "barfoo"],
+        svec!["barfoo", "foobar"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -298,3 +298,101 @@ fn select_glob() {
     let expected = vec![svec!["1_vec", "2_vec", "name"], svec!["3", "4", "john"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn select_regex() {
+    let wrk = Workdir::new("select_regex");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "value_A", "_id", "value_B"],
+            svec!["john", "1", "2", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.args(["-r", "^value_"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["value_A", "value_B"], svec!["1", "3"]];
+    assert_eq!(got, expected);
+
+    // Inverted
+    let mut cmd = wrk.command("select");
+    cmd.args(["-r", "^_", "--invert"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name", "value_A", "value_B"], svec!["john", "1", "3"]];
+    assert_eq!(got, expected);
+
+    // Empty
+    let mut cmd = wrk.command("select");
+    cmd.args(["-r", "^nope"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+
+    let mut cmd = wrk.command("select");
+    cmd.args(["-r", "^nope", "--allow-empty"]).arg("data.csv");
+    wrk.assert_success(&mut cmd);
+}
+
+#[test]
+fn select_non_empty_drop_constant() {
+    let wrk = Workdir::new("select_non_empty_drop_constant");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "empty", "constant", "sparse"],
+            svec!["john", "", "1", ""],
+            svec!["lisa", "", "1", "2"],
+        ],
+    );
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("--non-empty").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "constant", "sparse"],
+        svec!["john", "1", ""],
+        svec!["lisa", "1", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("--drop-constant").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "sparse"],
+        svec!["john", ""],
+        svec!["lisa", "2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("--non-empty").arg("name").arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn select_utf8_bom() {
+    let wrk = Workdir::new("select_utf8_bom");
+    std::fs::write(
+        wrk.path("data.csv"),
+        "\u{feff}id,name\r\n1,john\r\n2,mary\r\n",
+    )
+    .unwrap();
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("id").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["id"], svec!["1"], svec!["2"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("select");
+    cmd.arg("name").arg("data.csv");
+
+    let got: String = wrk.stdout(&mut cmd);
+    assert_eq!(got, "name\njohn\nmary");
+}
@@ -90,3 +90,33 @@ fn shuffle_in_memory_no_headers() {
     let expected = vec![svec!["2"], svec!["1"], svec!["3"], svec!["4"]];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn shuffle_limit() {
+    let wrk = Workdir::new("shuffle_limit");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["number"],
+            svec!["1"],
+            svec!["2"],
+            svec!["3"],
+            svec!["4"],
+            svec!["5"],
+            svec!["6"],
+        ],
+    );
+    let mut cmd = wrk.command("shuffle");
+    cmd.arg("data.csv").args(["--seed", "123", "--limit", "3"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["number"], svec!["2"], svec!["5"], svec!["4"]];
+    assert_eq!(got, expected);
+
+    // Limit greater than the number of rows
+    let mut cmd = wrk.command("shuffle");
+    cmd.arg("data.csv").args(["--seed", "123", "--limit", "10"]);
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got.len(), 7);
+}
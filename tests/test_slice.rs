@@ -197,6 +197,57 @@ fn slice_indices_indexed() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn slice_indices_spans() {
+    let wrk = Workdir::new("slice_indices_spans");
+    let data = vec![
+        svec!["n"],
+        svec!["zero"],
+        svec!["one"],
+        svec!["two"],
+        svec!["three"],
+        svec!["four"],
+        svec!["five"],
+    ];
+    wrk.create("data.csv", data.clone());
+    wrk.create_indexed("indexed.csv", data);
+
+    for path in ["data.csv", "indexed.csv"] {
+        // In file order
+        let mut cmd = wrk.command("slice");
+        cmd.args(["-I", "0,2-3,5,99"]).arg(path);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let expected = vec![
+            svec!["n"],
+            svec!["zero"],
+            svec!["two"],
+            svec!["three"],
+            svec!["five"],
+        ];
+        assert_eq!(got, expected);
+
+        // In arbitrary order, with duplicates
+        let mut cmd = wrk.command("slice");
+        cmd.args(["-I", "4,1-2,4,0"]).arg(path);
+
+        let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+        let expected = vec![
+            svec!["n"],
+            svec!["four"],
+            svec!["one"],
+            svec!["two"],
+            svec!["four"],
+            svec!["zero"],
+        ];
+        assert_eq!(got, expected);
+    }
+
+    let mut cmd = wrk.command("slice");
+    cmd.args(["-I", "3-1"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
 #[test]
 fn slice_byte_offset() {
     let wrk = Workdir::new("slice_byte_offset");
@@ -1,4 +1,5 @@
 use std::cmp;
+use std::fs;
 
 use crate::workdir::Workdir;
 
@@ -366,6 +367,34 @@ fn sort_check_numeric() {
     wrk.assert_success(&mut cmd);
 }
 
+#[test]
+fn sort_stable_if_sorted() {
+    let wrk = Workdir::new("sort_stable_if_sorted");
+    wrk.create(
+        "in.csv",
+        vec![svec!["n"], svec!["1"], svec!["2"], svec!["2"], svec!["3"]],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--stable-if-sorted", "-N", "-u"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["1"], svec!["2"], svec!["3"]];
+    assert_eq!(got, expected);
+
+    wrk.create(
+        "in.csv",
+        vec![svec!["n"], svec!["3"], svec!["1"], svec!["2"]],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--stable-if-sorted", "-N"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n"], svec!["1"], svec!["2"], svec!["3"]];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn sort_check_numeric_reverse() {
     let wrk = Workdir::new("sort_check");
@@ -407,3 +436,188 @@ where
         }
     }
 }
+
+#[test]
+fn sort_external() {
+    let wrk = Workdir::new("sort_external");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["N", "S"],
+            svec!["10", "a"],
+            svec!["LETTER", "b"],
+            svec!["2", "c"],
+            svec!["1", "d"],
+        ],
+    );
+    fs::create_dir(wrk.path("tmp")).unwrap();
+
+    let expected = vec![
+        svec!["N", "S"],
+        svec!["LETTER", "b"],
+        svec!["1", "d"],
+        svec!["2", "c"],
+        svec!["10", "a"],
+    ];
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-N", "--external", "--tmp-dir", "tmp"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    // --memory-limit implies --external
+    let mut cmd = wrk.command("sort");
+    cmd.args(["-N", "--memory-limit", "1", "--tmp-dir", "tmp"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got, expected);
+
+    // Temporary chunks should have been cleaned up
+    assert_eq!(fs::read_dir(wrk.path("tmp")).unwrap().count(), 0);
+}
+
+#[test]
+fn sort_external_comment() {
+    let wrk = Workdir::new("sort_external_comment");
+    fs::write(
+        wrk.path("in.csv"),
+        "# comment\nname\nmary\n\"#john\"\nabel\n",
+    )
+    .unwrap();
+    fs::create_dir(wrk.path("tmp")).unwrap();
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--comment", "#", "--external", "--tmp-dir", "tmp"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["name"], svec!["#john"], svec!["abel"], svec!["mary"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sort_collator() {
+    let wrk = Workdir::new("sort_collator");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["name"],
+            svec!["zoé"],
+            svec!["élise"],
+            svec!["Emma"],
+            svec!["eric"],
+            svec!["zack"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--collator").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name"],
+        svec!["élise"],
+        svec!["Emma"],
+        svec!["eric"],
+        svec!["zack"],
+        svec!["zoé"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--collator", "--reverse", "--check"])
+        .arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sort_columns() {
+    let wrk = Workdir::new("sort_columns");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["c", "a", "b"],
+            svec!["3", "1", "2"],
+            svec!["6", "4", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.arg("--columns").arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["a", "b", "c"],
+        svec!["1", "2", "3"],
+        svec!["4", "5", "6"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--columns", "-R"]).arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["c", "b", "a"],
+        svec!["3", "2", "1"],
+        svec!["6", "5", "4"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--columns", "-n"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn sort_by_key() {
+    let wrk = Workdir::new("sort_by_key");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["country", "city", "population"],
+            svec!["France", "Lyon", "500"],
+            svec!["Belgium", "Liège", "190"],
+            svec!["France", "Paris", "2100"],
+            svec!["Belgium", "Brussels", "1200"],
+            svec!["France", "Lille", "90"],
+        ],
+    );
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by-key", "country,population:desc:numeric"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["Belgium", "Brussels", "1200"],
+        svec!["Belgium", "Liège", "190"],
+        svec!["France", "Paris", "2100"],
+        svec!["France", "Lyon", "500"],
+        svec!["France", "Lille", "90"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by-key", "country:desc,city:asc"])
+        .arg("in.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "city", "population"],
+        svec!["France", "Lille", "90"],
+        svec!["France", "Lyon", "500"],
+        svec!["France", "Paris", "2100"],
+        svec!["Belgium", "Brussels", "1200"],
+        svec!["Belgium", "Liège", "190"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sort");
+    cmd.args(["--by-key", "country", "-R"]).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
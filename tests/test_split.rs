@@ -386,3 +386,204 @@ fn split_custom_filename() {
     assert!(wrk.path("prefix-2.csv").exists());
     assert!(wrk.path("prefix-4.csv").exists());
 }
+
+#[test]
+fn split_parts() {
+    let wrk = Workdir::new("split_parts");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--parts", "3"]).arg(&wrk.path(".")).arg("in.csv");
+    wrk.run(&mut cmd);
+
+    split_eq!(
+        wrk,
+        "0.csv",
+        "\
+h1,h2
+a,b
+c,d
+"
+    );
+    split_eq!(
+        wrk,
+        "2.csv",
+        "\
+h1,h2
+e,f
+g,h
+"
+    );
+    split_eq!(
+        wrk,
+        "4.csv",
+        "\
+h1,h2
+i,j
+k,l
+"
+    );
+    assert!(!wrk.path("6.csv").exists());
+}
+
+#[test]
+fn split_parts_uneven() {
+    let wrk = Workdir::new("split_parts_uneven");
+    wrk.create("in.csv", data(true));
+    wrk.create_indexed("in_idx.csv", data(true));
+
+    for input in ["in.csv", "in_idx.csv"] {
+        let mut cmd = wrk.command("split");
+        cmd.args(["--parts", "4"])
+            .arg(&wrk.path(input.trim_end_matches(".csv")))
+            .arg(input);
+        wrk.run(&mut cmd);
+
+        let dir = input.trim_end_matches(".csv");
+
+        split_eq!(
+            wrk,
+            &format!("{}/0.csv", dir),
+            "\
+h1,h2
+a,b
+c,d
+"
+        );
+        split_eq!(
+            wrk,
+            &format!("{}/2.csv", dir),
+            "\
+h1,h2
+e,f
+g,h
+"
+        );
+        split_eq!(
+            wrk,
+            &format!("{}/4.csv", dir),
+            "\
+h1,h2
+i,j
+"
+        );
+        split_eq!(
+            wrk,
+            &format!("{}/5.csv", dir),
+            "\
+h1,h2
+k,l
+"
+        );
+        assert!(!wrk.path(&format!("{}/6.csv", dir)).exists());
+    }
+}
+
+#[test]
+fn split_parts_round_robin() {
+    let wrk = Workdir::new("split_parts_round_robin");
+    wrk.create("in.csv", data(true));
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--parts", "4", "--round-robin"])
+        .arg(&wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    split_eq!(
+        wrk,
+        "0.csv",
+        "\
+h1,h2
+a,b
+i,j
+"
+    );
+    split_eq!(
+        wrk,
+        "1.csv",
+        "\
+h1,h2
+c,d
+k,l
+"
+    );
+    split_eq!(
+        wrk,
+        "2.csv",
+        "\
+h1,h2
+e,f
+"
+    );
+    split_eq!(
+        wrk,
+        "3.csv",
+        "\
+h1,h2
+g,h
+"
+    );
+    assert!(!wrk.path("4.csv").exists());
+
+    let mut cmd = wrk.command("split");
+    cmd.arg("--round-robin").arg(&wrk.path(".")).arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn split_by_change() {
+    let wrk = Workdir::new("split_by_change");
+    wrk.create(
+        "in.csv",
+        vec![
+            svec!["session", "event"],
+            svec!["s1", "a"],
+            svec!["s1", "b"],
+            svec!["s2", "c"],
+            svec!["s1", "d"],
+            svec!["s1", "e"],
+        ],
+    );
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--by-change", "session"])
+        .arg(&wrk.path("."))
+        .arg("in.csv");
+    wrk.run(&mut cmd);
+
+    split_eq!(
+        wrk,
+        "0.csv",
+        "\
+session,event
+s1,a
+s1,b
+"
+    );
+    split_eq!(
+        wrk,
+        "2.csv",
+        "\
+session,event
+s2,c
+"
+    );
+    split_eq!(
+        wrk,
+        "3.csv",
+        "\
+session,event
+s1,d
+s1,e
+"
+    );
+    assert!(!wrk.path("1.csv").exists());
+    assert!(!wrk.path("4.csv").exists());
+
+    let mut cmd = wrk.command("split");
+    cmd.args(["--by-change", "session", "--parts", "2"])
+        .arg(&wrk.path("."))
+        .arg("in.csv");
+    wrk.assert_err(&mut cmd);
+}
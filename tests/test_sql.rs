@@ -0,0 +1,147 @@
+use crate::workdir::Workdir;
+
+fn cities(wrk: &Workdir) {
+    wrk.create(
+        "cities.csv",
+        vec![
+            svec!["name", "country", "population"],
+            svec!["Paris", "France", "2100"],
+            svec!["Lyon", "France", "520"],
+            svec!["Berlin", "Germany", "3600"],
+            svec!["Hamburg", "Germany", "1800"],
+            svec!["Rome", "Italy", "2800"],
+        ],
+    );
+}
+
+#[test]
+fn sql_select_where() {
+    let wrk = Workdir::new("sql_select_where");
+    cities(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name, population / 100 AS p FROM cities WHERE country = 'France'")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "p"],
+        svec!["Paris", "21"],
+        svec!["Lyon", "5.2"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("select * from cities where population > 2000 and country <> 'Italy' limit 1")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "country", "population"],
+        svec!["Paris", "France", "2100"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_where_string_columns() {
+    let wrk = Workdir::new("sql_where_string_columns");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["x", "x"], svec!["y", "z"]],
+    );
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT a FROM data WHERE a = b").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a"], svec!["x"]];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT a FROM data WHERE a <> b").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["a"], svec!["y"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_order_by() {
+    let wrk = Workdir::new("sql_order_by");
+    cities(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name, population FROM cities ORDER BY population DESC LIMIT 2")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "population"],
+        svec!["Berlin", "3600"],
+        svec!["Rome", "2800"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_group_by() {
+    let wrk = Workdir::new("sql_group_by");
+    cities(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg(
+        "SELECT country, sum(population) AS total, count() AS n FROM cities GROUP BY country ORDER BY total DESC LIMIT 2;",
+    )
+    .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["country", "total", "n"],
+        svec!["Germany", "5400", "2"],
+        svec!["Italy", "2800", "1"],
+    ];
+    assert_eq!(got, expected);
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT country  AS  \"Country\", count() FROM cities GROUP BY country LIMIT 1")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["Country", "count()"], svec!["France", "2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn sql_aggregate() {
+    let wrk = Workdir::new("sql_aggregate");
+    cities(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT count() AS n, max(population) AS max FROM cities WHERE country = 'Germany'")
+        .arg("cities.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["n", "max"], svec!["2", "3600"]];
+    assert_eq!(got, expected);
+    // Invalid aggregations are reported as such
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT sum(population, 1, 2) FROM cities")
+        .arg("cities.csv");
+
+    wrk.assert_err(&mut cmd);
+
+    let output = cmd.output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("sum: expected 1 argument"));
+}
+
+#[test]
+fn sql_invalid_query() {
+    let wrk = Workdir::new("sql_invalid_query");
+    cities(&wrk);
+
+    let mut cmd = wrk.command("sql");
+    cmd.arg("SELECT name").arg("cities.csv");
+
+    wrk.assert_err(&mut cmd);
+}
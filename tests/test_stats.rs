@@ -227,6 +227,7 @@ stats_tests!(
 stats_tests!(stats_cardinality, "cardinality", &["a", "b", "a"], "2");
 stats_tests!(stats_mode, "mode", &["a", "b", "a"], "a");
 stats_tests!(stats_mode_null, "mode", &["", "a", "b", "a"], "a");
+stats_tests!(stats_mode_tie, "mode", &["c", "b", "a", "c", "b"], "b");
 stats_tests!(stats_median, "median", &["1", "2", "3"], "2");
 stats_tests!(stats_median_null, "median", &["", "1", "2", "3"], "2");
 stats_tests!(stats_median_even, "median", &["1", "2", "3", "4"], "2.5");
@@ -270,3 +271,149 @@ mod stats_header_fields {
     stats_test_headers!(stats_header_field_name, "field", &["a"], "header");
     stats_test_no_headers!(stats_header_no_field_name, "field", &["a"], "0");
 }
+
+#[test]
+fn stats_corr() {
+    let wrk = Workdir::new("stats_corr");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["x", "name", "double", "opposite"],
+            svec!["1", "john", "2", "-1"],
+            svec!["2", "mary", "4", "-2"],
+            svec!["3", "lucy", "", "-3"],
+            svec!["4", "lisa", "8", "-4"],
+        ],
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--corr").arg("data.csv");
+
+    let output = wrk.output(&mut cmd);
+    assert!(String::from_utf8_lossy(&output.stderr).contains("name"));
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let got = got
+        .into_iter()
+        .map(|row| {
+            row.into_iter()
+                .map(|cell| match cell.parse::<f64>() {
+                    Ok(r) => ((r * 1e6).round() / 1e6).to_string(),
+                    Err(_) => cell,
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+    let expected = vec![
+        svec!["field", "x", "double", "opposite"],
+        svec!["x", "1", "1", "-1"],
+        svec!["double", "1", "1", "-1"],
+        svec!["opposite", "-1", "-1", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn stats_transpose() {
+    let wrk = Workdir::new("stats_transpose");
+    wrk.create(
+        "data.csv",
+        vec![svec!["a", "b"], svec!["1", "x"], svec!["3", "y"]],
+    );
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("-T").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    assert_eq!(got[0], svec!["field", "a", "b"]);
+    assert_eq!(got[1], svec!["count", "2", "2"]);
+    assert_eq!(got[3], svec!["type", "int", "string"]);
+    assert_eq!(got[6], svec!["mean", "2", ""]);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-T", "-g", "a"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_approx() {
+    let wrk = Workdir::new("stats_approx");
+    let mut rows = vec![svec!["n", "name"]];
+    for i in 1..=1001 {
+        rows.push(vec![i.to_string(), format!("name{}", i % 10)]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-a", "-T"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let approx_median = got.iter().find(|row| row[0] == "approx_median").unwrap();
+
+    let median = approx_median[1].parse::<f64>().unwrap();
+    assert!((median - 501.0).abs() < 10.0);
+    assert_eq!(approx_median[2], "");
+}
+
+#[test]
+fn stats_sketch() {
+    let wrk = Workdir::new("stats_sketch");
+    let mut rows = vec![svec!["n", "name"]];
+    for i in [1, 2, 3, 4, 5, 6, 7, 8, 100] {
+        rows.push(vec![i.to_string(), format!("name{}", i)]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("stats");
+    cmd.arg("--sketch").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec![
+            "field",
+            "count",
+            "min",
+            "q1",
+            "median",
+            "q3",
+            "max",
+            "iqr",
+            "lower_fence",
+            "upper_fence"
+        ],
+        svec!["n", "9", "1", "3", "5", "7", "100", "4", "-3", "13"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["--sketch", "-g", "name"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn stats_parallel() {
+    let wrk = Workdir::new("stats_parallel");
+    let mut rows = vec![svec!["n", "name"]];
+    for i in 1..=1001 {
+        rows.push(vec![i.to_string(), format!("name{}", i % 10)]);
+    }
+    wrk.create("data.csv", rows);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-c", "-T"]).arg("data.csv");
+    let expected: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-c", "-T", "-p", "--chunk-size", "10"])
+        .arg("data.csv");
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+
+    for statistic in ["count", "sum", "min", "max", "cardinality", "lex_first"] {
+        let find = |rows: &Vec<Vec<String>>| rows.iter().find(|row| row[0] == statistic).cloned();
+        assert_eq!(find(&got), find(&expected));
+    }
+
+    let mut cmd = wrk.command("stats");
+    cmd.args(["-q", "-p"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -25,6 +25,33 @@ fn tokenize() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn tokenize_counts() {
+    let wrk = Workdir::new("tokenize_counts");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n", "text"],
+            svec!["1", "le chat et le chien"],
+            svec!["2", "la souris"],
+        ],
+    );
+    let mut cmd = wrk.command("tokenize");
+    cmd.arg("words").arg("--counts").arg("text").arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "token", "count"],
+        svec!["1", "le", "2"],
+        svec!["1", "chat", "1"],
+        svec!["1", "et", "1"],
+        svec!["1", "chien", "1"],
+        svec!["2", "la", "1"],
+        svec!["2", "souris", "1"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn tokenize_simple() {
     let wrk = Workdir::new("tokenize_simple");
@@ -405,6 +432,31 @@ fn tokenize_sentences() {
     assert_eq!(got, expected);
 }
 
+#[test]
+fn tokenize_sentences_ngrams() {
+    let wrk = Workdir::new("tokenize_sentences_ngrams");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["n", "text"],
+            svec!["1", "Bonjour. Je suis John! Et toi?"],
+        ],
+    );
+    let mut cmd = wrk.command("tokenize");
+    cmd.arg("sentences")
+        .args(["-N", "2"])
+        .arg("text")
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["n", "sentence"],
+        svec!["1", "Bonjour. Je suis John!"],
+        svec!["1", "Je suis John! Et toi?"],
+    ];
+    assert_eq!(got, expected);
+}
+
 #[test]
 fn tokenize_sentences_parallel() {
     let wrk = Workdir::new("tokenize_sentences_parallel");
@@ -205,3 +205,143 @@ fn top_ties() {
     ];
     assert_eq!(got, expected);
 }
+
+#[test]
+fn top_tiebreak() {
+    let wrk = Workdir::new("top_tiebreak");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "count", "group"],
+            svec!["zoe", "3", "a"],
+            svec!["adam", "3", "b"],
+            svec!["bob", "2", "a"],
+            svec!["lucy", "3", "a"],
+            svec!["max", "1", "b"],
+        ],
+    );
+
+    // Ascending
+    let mut cmd = wrk.command("top");
+    cmd.args(["count", "-l", "2", "--tiebreak", "name"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "count", "group"],
+        svec!["adam", "3", "b"],
+        svec!["lucy", "3", "a"],
+    ];
+    assert_eq!(got, expected);
+
+    // Descending
+    let mut cmd = wrk.command("top");
+    cmd.args(["count", "-l", "2", "--tiebreak", "-name"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "count", "group"],
+        svec!["zoe", "3", "a"],
+        svec!["lucy", "3", "a"],
+    ];
+    assert_eq!(got, expected);
+
+    // Groupby
+    let mut cmd = wrk.command("top");
+    cmd.args(["count", "-l", "1", "-g", "group", "--tiebreak", "name"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "count", "group"],
+        svec!["lucy", "3", "a"],
+        svec!["adam", "3", "b"],
+    ];
+    assert_eq!(got, expected);
+
+    // Incompatible with --ties
+    let mut cmd = wrk.command("top");
+    cmd.args(["count", "-T", "--tiebreak", "name"])
+        .arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn top_by() {
+    let wrk = Workdir::new("top_by");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "retweets", "likes"],
+            svec!["john", "3", "1"],
+            svec!["mary", "1", "1"],
+            svec!["lucy", "2", "5"],
+        ],
+    );
+
+    let mut cmd = wrk.command("top");
+    cmd.args(["--by", "retweets + likes", "-l", "2"])
+        .arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "retweets", "likes"],
+        svec!["lucy", "2", "5"],
+        svec!["john", "3", "1"],
+    ];
+    assert_eq!(got, expected);
+
+    // Non-numerical results are ranked last
+    wrk.create(
+        "other.csv",
+        vec![
+            svec!["name", "likes"],
+            svec!["lisa", "hello"],
+            svec!["john", "1"],
+            svec!["mary", "3"],
+        ],
+    );
+
+    let mut cmd = wrk.command("top");
+    cmd.args(["--by", "likes", "-R"]).arg("other.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "likes"],
+        svec!["john", "1"],
+        svec!["mary", "3"],
+        svec!["lisa", "hello"],
+    ];
+    assert_eq!(got, expected);
+
+    // Strict mode
+    let mut cmd = wrk.command("top");
+    cmd.args(["--by", "likes", "--strict"]).arg("other.csv");
+    wrk.assert_err(&mut cmd);
+
+    let output = cmd.output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("for row n°1,"));
+
+    let mut cmd = wrk.command("top");
+    cmd.args(["likes", "--strict"]).arg("other.csv");
+    wrk.assert_err(&mut cmd);
+
+    // Failing expressions are also ranked last
+    let mut cmd = wrk.command("top");
+    cmd.args(["--by", "likes + 1"]).arg("other.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "likes"],
+        svec!["mary", "3"],
+        svec!["john", "1"],
+        svec!["lisa", "hello"],
+    ];
+    assert_eq!(got, expected);
+
+    let mut cmd = wrk.command("top");
+    cmd.args(["--by", "likes + 1", "--strict"]).arg("other.csv");
+    wrk.assert_err(&mut cmd);
+}
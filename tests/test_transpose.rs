@@ -0,0 +1,40 @@
+use crate::workdir::Workdir;
+
+#[test]
+fn transpose() {
+    let wrk = Workdir::new("transpose");
+    wrk.create(
+        "data.csv",
+        vec![svec!["metric", "a", "b"], svec!["x", "1", "2"]],
+    );
+    let mut cmd = wrk.command("transpose");
+    cmd.arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![svec!["metric", "x"], svec!["a", "1"], svec!["b", "2"]];
+    assert_eq!(got, expected);
+}
+
+#[test]
+fn transpose_header_col() {
+    let wrk = Workdir::new("transpose_header_col");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["id", "name", "score"],
+            svec!["1", "john", "4"],
+            svec!["2", "mary", "5"],
+            svec!["3", "john", "6"],
+        ],
+    );
+    let mut cmd = wrk.command("transpose");
+    cmd.args(["--header-col", "name"]).arg("data.csv");
+
+    let got: Vec<Vec<String>> = wrk.read_stdout(&mut cmd);
+    let expected = vec![
+        svec!["name", "john", "mary", "john_1"],
+        svec!["id", "1", "2", "3"],
+        svec!["score", "4", "5", "6"],
+    ];
+    assert_eq!(got, expected);
+}
@@ -0,0 +1,181 @@
+use std::process;
+
+use crate::workdir::Workdir;
+
+// NOTE: isolating tests from the user's own view defaults
+fn view_command(wrk: &Workdir) -> process::Command {
+    let mut cmd = wrk.command("view");
+    cmd.env_remove("XAN_VIEW_ARGS");
+    cmd
+}
+
+fn view_output(wrk: &Workdir, cmd: &mut process::Command) -> String {
+    cmd.arg("--no-color");
+
+    let output = wrk.output(cmd);
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+// NOTE: using the borderless theme, the rendered table can be split
+// back into rows of cells on whitespace.
+fn view_rows(wrk: &Workdir, cmd: &mut process::Command) -> Vec<Vec<String>> {
+    cmd.args(["-t", "borderless", "-M"]);
+
+    view_output(wrk, cmd)
+        .lines()
+        .map(|line| {
+            line.split_whitespace()
+                .map(|cell| cell.to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|row| !row.is_empty())
+        .collect()
+}
+
+#[test]
+fn view_record() {
+    let wrk = Workdir::new("view_record");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["name", "surname"],
+            svec!["John", "Smith"],
+            svec!["Mary", ""],
+        ],
+    );
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-r", "1", "--cols", "20"]).arg("data.csv");
+
+    let got = view_output(&wrk, &mut cmd);
+    let expected = "\
+Row n°1
+────────────────────
+name    Mary
+surname <empty>
+";
+    assert_eq!(got, expected);
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-r", "2"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn view_number() {
+    let wrk = Workdir::new("view_number");
+    wrk.create(
+        "data.csv",
+        vec![svec!["name"], svec!["a"], svec!["b"], svec!["c"]],
+    );
+
+    let mut cmd = view_command(&wrk);
+    cmd.arg("data.csv");
+
+    let got = view_rows(&wrk, &mut cmd);
+    assert!(got.contains(&svec!["0", "a"]));
+    assert!(got.contains(&svec!["2", "c"]));
+
+    let mut cmd = view_command(&wrk);
+    cmd.arg("-#").arg("data.csv");
+
+    let got = view_rows(&wrk, &mut cmd);
+    assert!(got.contains(&svec!["1", "a"]));
+    assert!(got.contains(&svec!["3", "c"]));
+    assert!(!got.contains(&svec!["0", "a"]));
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["--number", "-I"]).arg("data.csv");
+
+    let got = view_rows(&wrk, &mut cmd);
+    assert!(got.contains(&svec!["1", "a"]));
+}
+
+#[test]
+fn view_wrap() {
+    let wrk = Workdir::new("view_wrap");
+    wrk.create(
+        "data.csv",
+        vec![svec!["text"], svec!["the quick brown fox jumps"]],
+    );
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-I", "--max-width", "10"]).arg("data.csv");
+
+    let got = view_output(&wrk, &mut cmd);
+    assert!(got.contains('…'));
+    assert!(!got.contains("jumps"));
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-I", "--max-width", "10", "--wrap"])
+        .arg("data.csv");
+
+    let got = view_rows(&wrk, &mut cmd);
+    assert!(got.contains(&svec!["the", "quick"]));
+    assert!(got.contains(&svec!["brown", "fox"]));
+    assert!(got.contains(&svec!["jumps"]));
+
+    // --no-wrap overrides XAN_VIEW_ARGS
+    let mut cmd = view_command(&wrk);
+    cmd.env("XAN_VIEW_ARGS", "-w")
+        .args(["-I", "--max-width", "10", "--no-wrap"])
+        .arg("data.csv");
+
+    let got = view_output(&wrk, &mut cmd);
+    assert!(got.contains('…'));
+    assert!(!got.contains("jumps"));
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["--wrap", "--no-wrap"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn view_color_types() {
+    let wrk = Workdir::new("view_color_types");
+    wrk.create(
+        "data.csv",
+        vec![
+            svec!["count"],
+            svec!["1"],
+            svec!["2"],
+            svec!["oops"],
+            svec!["3"],
+        ],
+    );
+
+    let mut cmd = view_command(&wrk);
+    cmd.arg("-C").arg("data.csv");
+
+    let got = String::from_utf8_lossy(&wrk.output(&mut cmd).stdout).to_string();
+    assert!(got.contains("\x1b[32moops"));
+
+    // Mismatched cells are emphasized using bold & underline
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-C", "-y"]).arg("data.csv");
+
+    let got = String::from_utf8_lossy(&wrk.output(&mut cmd).stdout).to_string();
+    assert!(got.contains("\x1b[1;4;32moops"));
+    assert!(!got.contains("\x1b[1;4;31m"));
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["-y", "-R"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
+
+#[test]
+fn view_no_color() {
+    let wrk = Workdir::new("view_no_color");
+    wrk.create("data.csv", vec![svec!["count"], svec!["1"], svec!["oops"]]);
+
+    let mut cmd = view_command(&wrk);
+    cmd.arg("-y").arg("data.csv");
+
+    let got = view_output(&wrk, &mut cmd);
+    assert!(got.contains("oops"));
+    assert!(!got.contains('\x1b'));
+
+    let mut cmd = view_command(&wrk);
+    cmd.args(["--no-color", "-C"]).arg("data.csv");
+    wrk.assert_err(&mut cmd);
+}
@@ -28,20 +28,27 @@ mod test_count;
 mod test_dedup;
 mod test_enumerate;
 mod test_explode;
+mod test_fill;
 mod test_filter;
 mod test_fixlengths;
 mod test_flatmap;
 mod test_fmt;
+mod test_foreach;
 mod test_frequency;
+mod test_from;
+mod test_glob;
 mod test_groupby;
 mod test_headers;
 mod test_implode;
 mod test_index;
+mod test_input;
 mod test_join;
+mod test_lookup;
 mod test_map;
 mod test_merge;
 mod test_parallel;
 mod test_partition;
+mod test_progress;
 mod test_range;
 mod test_regex_join;
 mod test_rename;
@@ -53,11 +60,14 @@ mod test_shuffle;
 mod test_slice;
 mod test_sort;
 mod test_split;
+mod test_sql;
 mod test_stats;
 mod test_to;
 mod test_tokenize;
 mod test_top;
 mod test_transform;
+mod test_transpose;
+mod test_view;
 mod test_vocab;
 
 pub type CsvVecs = Vec<Vec<String>>;